@@ -1,12 +1,44 @@
 use proc_macro::TokenStream;
 
-use syn::DeriveInput;
+use syn::{DeriveInput, LitStr};
 
 pub fn impl_trait_component(ast: DeriveInput) -> TokenStream {
     let ident = ast.ident;
 
+    // #[component(name = "...")]
+    let mut name = None;
+    for attr in ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("component"))
+    {
+        let res = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported component attribute"))
+            }
+        });
+
+        if let Err(err) = res {
+            return err.to_compile_error().into();
+        }
+    }
+
+    let name_fn = name.map(|name| {
+        quote::quote! {
+            #[inline]
+            fn name() -> &'static str {
+                #name
+            }
+        }
+    });
+
     quote::quote! {
-        impl Component for #ident { }
+        impl Component for #ident {
+            #name_fn
+        }
     }
     .into()
 }
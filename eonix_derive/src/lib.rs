@@ -1,11 +1,12 @@
 mod component;
 mod nosend;
 mod resource;
+mod system_param;
 
 use proc_macro::TokenStream;
 use syn::DeriveInput;
 
-#[proc_macro_derive(Resource)]
+#[proc_macro_derive(Resource, attributes(resource))]
 pub fn derive_resource(tokens: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(tokens).unwrap();
 
@@ -25,3 +26,10 @@ pub fn derive_system_nosend(tokens: TokenStream) -> TokenStream {
 
     nosend::impl_trait_nosend(ast)
 }
+
+#[proc_macro_derive(SystemParam)]
+pub fn derive_system_param(tokens: TokenStream) -> TokenStream {
+    let ast: DeriveInput = syn::parse(tokens).unwrap();
+
+    system_param::impl_trait_system_param(ast)
+}
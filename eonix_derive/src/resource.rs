@@ -4,8 +4,42 @@ use syn::DeriveInput;
 pub fn impl_trait_resource(ast: DeriveInput) -> TokenStream {
     let ident = ast.ident;
 
+    // #[resource(from_world)]
+    let mut from_world = false;
+    for attr in ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("resource"))
+    {
+        let res = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("from_world") {
+                from_world = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported resource attribute"))
+            }
+        });
+
+        if let Err(err) = res {
+            return err.to_compile_error().into();
+        }
+    }
+
+    let from_world_check = from_world.then(|| {
+        quote::quote! {
+            const _: () = {
+                fn assert_from_world<T: FromWorld>() {}
+                fn check() {
+                    assert_from_world::<#ident>();
+                }
+            };
+        }
+    });
+
     quote::quote! {
         impl Resource for #ident {}
+
+        #from_world_check
     }
     .into()
 }
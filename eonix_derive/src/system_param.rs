@@ -0,0 +1,199 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{
+    Data, DeriveInput, Fields, GenericParam, Lifetime, Type, spanned::Spanned, visit_mut::VisitMut,
+};
+
+/// Replaces all occurrences of one lifetime with another.
+struct ReplaceLifetime<'a> {
+    from: &'a Lifetime,
+    to: &'a Lifetime,
+}
+
+impl VisitMut for ReplaceLifetime<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == self.from.ident {
+            *lifetime = self.to.clone();
+        }
+    }
+}
+
+pub fn impl_trait_system_param(ast: DeriveInput) -> TokenStream {
+    let ident = ast.ident;
+
+    // only a single lifetime is supported, that gets replaced by the world lifetime
+    let mut lifetime = None;
+    for param in &ast.generics.params {
+        match param {
+            GenericParam::Lifetime(def) if lifetime.is_none() => lifetime = Some(&def.lifetime),
+            _ => {
+                return syn::Error::new(
+                    param.span(),
+                    "SystemParam can only be derived for structs with at most one lifetime parameter",
+                )
+                .to_compile_error()
+                .into();
+            }
+        }
+    }
+
+    let Data::Struct(data) = ast.data else {
+        return syn::Error::new(ident.span(), "SystemParam can only be derived for structs")
+            .to_compile_error()
+            .into();
+    };
+
+    let world_lifetime = Lifetime::new("'__world", Span::call_site());
+
+    let field_types = data
+        .fields
+        .iter()
+        .map(|field| &field.ty)
+        .collect::<Vec<_>>();
+
+    // field types, bound to the given lifetime
+    let bind_types = |to: &Lifetime| {
+        data.fields
+            .iter()
+            .map(|field| {
+                let mut ty: Type = field.ty.clone();
+                if let Some(from) = lifetime {
+                    ReplaceLifetime { from, to }.visit_type_mut(&mut ty);
+                }
+                ty
+            })
+            .collect::<Vec<_>>()
+    };
+    let types = bind_types(&world_lifetime);
+    // used by the init function, which can't name the lifetime of the impl
+    let static_types = bind_types(&Lifetime::new("'static", Span::call_site()));
+
+    let item = match lifetime {
+        Some(_) => quote::quote! { #ident<'__new> },
+        None => quote::quote! { #ident },
+    };
+    let impl_lifetime = lifetime.map(|lifetime| quote::quote! { <#lifetime> });
+
+    // every field gets its own per-system state, and reports itself if it can't be retrieved
+    let retrieve = types.iter().enumerate().map(|(index, ty)| {
+        quote::quote! {
+            ::eonix::retrieved_field::<#ty, _>(
+                <#ty as ::eonix::SystemParam>::retrieve_with_local(
+                    world,
+                    ::eonix::nested_local_field(local, #index),
+                ),
+                ::std::any::type_name::<Self>(),
+            )?
+        }
+    });
+
+    let retrieve_local = types.iter().enumerate().map(|(index, ty)| {
+        quote::quote! {
+            ::eonix::retrieved_field::<#ty, _>(
+                if <#ty as ::eonix::SystemParam>::local() {
+                    <#ty as ::eonix::SystemParam>::retrieve_local_with_local(
+                        world,
+                        ::eonix::nested_local_field(local, #index),
+                    )
+                } else {
+                    <#ty as ::eonix::SystemParam>::retrieve_with_local(
+                        world.send_world(),
+                        ::eonix::nested_local_field(local, #index),
+                    )
+                },
+                ::std::any::type_name::<Self>(),
+            )?
+        }
+    });
+
+    let construct = |values: Vec<proc_macro2::TokenStream>| match &data.fields {
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|field| &field.ident);
+            quote::quote! { #ident { #(#names: #values,)* } }
+        }
+        Fields::Unnamed(_) => quote::quote! { #ident ( #(#values,)* ) },
+        Fields::Unit => quote::quote! { #ident },
+    };
+
+    let retrieve = construct(retrieve.collect());
+    let retrieve_local = construct(retrieve_local.collect());
+
+    quote::quote! {
+        impl #impl_lifetime ::eonix::SystemParam for #ident #impl_lifetime {
+            type Item<'__new> = #item;
+
+            #[inline]
+            fn local() -> bool {
+                false #(|| <#field_types as ::eonix::SystemParam>::local())*
+            }
+
+            fn get_types() -> ::std::vec::Vec<::eonix::ParamType> {
+                let mut types = ::std::vec::Vec::new();
+                #(types.append(&mut <#field_types as ::eonix::SystemParam>::get_types());)*
+                types
+            }
+
+            fn get_filter() -> ::std::vec::Vec<::eonix::FilterType> {
+                let mut filter = ::std::vec::Vec::new();
+                #(filter.append(&mut <#field_types as ::eonix::SystemParam>::get_filter());)*
+                filter
+            }
+
+            fn init() -> ::std::option::Option<::eonix::InitFn> {
+                fn init_fields(world: &mut ::eonix::World) {
+                    #(
+                        if let ::std::option::Option::Some(init) =
+                            <#static_types as ::eonix::SystemParam>::init()
+                        {
+                            init(world);
+                        }
+                    )*
+                }
+
+                let any = false #(|| <#field_types as ::eonix::SystemParam>::init().is_some())*;
+                any.then_some(init_fields as ::eonix::InitFn)
+            }
+
+            fn init_local() -> ::std::option::Option<::eonix::LocalState> {
+                ::eonix::nested_local(::std::vec![
+                    #(<#field_types as ::eonix::SystemParam>::init_local(),)*
+                ])
+            }
+
+            #[inline]
+            fn retrieve<#world_lifetime>(
+                world: ::eonix::SendWorld<#world_lifetime>,
+            ) -> ::std::option::Option<Self::Item<#world_lifetime>> {
+                Self::retrieve_with_local(world, ::std::option::Option::None)
+            }
+
+            fn retrieve_with_local<#world_lifetime>(
+                world: ::eonix::SendWorld<#world_lifetime>,
+                local: ::std::option::Option<&#world_lifetime ::eonix::LocalState>,
+            ) -> ::std::option::Option<Self::Item<#world_lifetime>> {
+                ::std::option::Option::Some(#retrieve)
+            }
+
+            #[inline]
+            fn retrieve_local<#world_lifetime>(
+                world: &#world_lifetime ::eonix::World,
+            ) -> ::std::option::Option<Self::Item<#world_lifetime>> {
+                Self::retrieve_local_with_local(world, ::std::option::Option::None)
+            }
+
+            fn retrieve_local_with_local<#world_lifetime>(
+                world: &#world_lifetime ::eonix::World,
+                local: ::std::option::Option<&#world_lifetime ::eonix::LocalState>,
+            ) -> ::std::option::Option<Self::Item<#world_lifetime>> {
+                ::std::option::Option::Some(#retrieve_local)
+            }
+
+            #[inline]
+            fn skip_silently() -> bool {
+                // the fields already reported themselves
+                true
+            }
+        }
+    }
+    .into()
+}
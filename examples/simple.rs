@@ -22,7 +22,8 @@ fn main() {
         .add_system(Update, (increase_velocity, update))
         .add_system(PostUpdate, after)
         .add_system(Shutdown, good_bye)
-        .build();
+        .build()
+        .unwrap();
 
     schedule.run_setup(&mut world);
 
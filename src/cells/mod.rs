@@ -1,5 +1,5 @@
 mod ref_cell;
 mod split_world_cell;
 
-pub use ref_cell::{AtomicRefCell, MutGuard, RefGuard};
+pub use ref_cell::{AtomicRefCell, BorrowState, MutGuard, RefGuard};
 pub use split_world_cell::{WorldCellComplete, WorldCellSend, split_world};
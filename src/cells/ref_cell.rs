@@ -46,6 +46,29 @@ const ERROR_MUTABLE_BORROWED: Error = Error("Already mutably borrowed!");
 const ERROR_SHARED_BORROWED: Error = Error("Already shared borrowed!");
 const PANIC_TOO_MANY_SHARED: &str = "Too many shared borrows";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The current borrows of a cell, see `AtomicRefCell::borrow_state`.
+pub enum BorrowState {
+    Unshared,
+    /// Number of shared borrows given out.
+    Shared(usize),
+    MutBorrowed,
+}
+
+impl BorrowState {
+    #[inline]
+    pub(crate) const fn from_counter(counter: usize) -> Self {
+        // failed attempts to borrow shared count up, while the high bit is set
+        if counter & HIGH != 0 {
+            Self::MutBorrowed
+        } else if counter == 0 {
+            Self::Unshared
+        } else {
+            Self::Shared(counter)
+        }
+    }
+}
+
 /// An atomic `RefCell`.
 #[derive(Debug)]
 pub struct AtomicRefCell<T> {
@@ -74,6 +97,14 @@ impl<T> AtomicRefCell<T> {
         self.data.get_mut()
     }
 
+    #[inline]
+    /// Returns the current borrows, e.g. to find out why a borrow failed.
+    ///
+    /// The state can change right after reading it, if the cell is shared between threads.
+    pub fn borrow_state(&self) -> BorrowState {
+        BorrowState::from_counter(self.borrow.load(Ordering::Relaxed))
+    }
+
     #[inline]
     /// Get a shared reference to the contained value.
     ///
@@ -98,6 +129,23 @@ impl<T> AtomicRefCell<T> {
         }
     }
 
+    #[inline]
+    /// Get a shared reference to the contained value, without updating the borrow counter.
+    ///
+    /// # Safety
+    /// No mutable reference may be given out, while the returned reference is alive.
+    /// # Panics
+    /// - if there is already a mutable reference given out (only with `runtime-checks`)
+    pub unsafe fn get_unchecked(&self) -> &T {
+        #[cfg(feature = "runtime-checks")]
+        assert!(
+            self.borrow.load(Ordering::Relaxed) & HIGH == 0,
+            "{ERROR_MUTABLE_BORROWED}"
+        );
+
+        unsafe { &*self.data.get() }
+    }
+
     #[inline]
     /// Get a shared reference to the contained value.
     ///
@@ -119,13 +167,13 @@ impl<T> AtomicRefCell<T> {
                 value,
             })
         }
-        // high bit NOT set
+        // high bit NOT set, shared borrowed
         else if old & HIGH == 0 {
-            Err(ERROR_MUTABLE_BORROWED)
+            Err(ERROR_SHARED_BORROWED)
         }
         // mutably borrowed,
         else {
-            Err(ERROR_SHARED_BORROWED)
+            Err(ERROR_MUTABLE_BORROWED)
         }
     }
 
@@ -227,9 +275,33 @@ impl<T> std::ops::DerefMut for MutGuard<'_, T> {
 }
 
 #[cfg(test)]
+#[allow(clippy::significant_drop_tightening)]
 mod tests {
 
-    use super::AtomicRefCell;
+    use super::{AtomicRefCell, BorrowState};
+
+    #[test]
+    fn test_borrow_state() {
+        let ref_cell = AtomicRefCell::new(0i32);
+        assert_eq!(ref_cell.borrow_state(), BorrowState::Unshared);
+
+        let ref_1 = ref_cell.borrow();
+        let ref_2 = ref_cell.borrow();
+        assert_eq!(ref_cell.borrow_state(), BorrowState::Shared(2));
+        drop(ref_1);
+        assert_eq!(ref_cell.borrow_state(), BorrowState::Shared(1));
+        drop(ref_2);
+
+        let ref_3 = ref_cell.borrow_mut();
+        assert_eq!(ref_cell.borrow_state(), BorrowState::MutBorrowed);
+
+        // failed attempts don't change the state
+        assert!(ref_cell.try_borrow().is_err());
+        assert_eq!(ref_cell.borrow_state(), BorrowState::MutBorrowed);
+        drop(ref_3);
+
+        assert_eq!(ref_cell.borrow_state(), BorrowState::Unshared);
+    }
 
     #[test]
     fn test_shared_borrow() {
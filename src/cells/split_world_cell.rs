@@ -9,6 +9,8 @@ use std::{
 
 use crate::{World, macros::unwrap, world::SendWorldPtr};
 
+use super::BorrowState;
+
 const HIGH: usize = !(usize::MAX >> 1);
 const MAX_BORROWS_ATTEMPTS: usize = HIGH + (HIGH >> 1);
 
@@ -27,7 +29,7 @@ const ERROR_MUTABLE_BORROWED: Error = Error("Already mutably borrowed!");
 const ERROR_SHARED_BORROWED: Error = Error("Already shared borrowed!");
 const PANIC_TOO_MANY_SHARED: &str = "Too many shared borrows";
 
-pub fn split_world(world: &mut World) -> (WorldCellComplete, WorldCellSend) {
+pub fn split_world(world: &mut World) -> (WorldCellComplete<'_>, WorldCellSend<'_>) {
     let borrow = Arc::new(AtomicUsize::new(0));
 
     let complete = WorldCellComplete::new(world, borrow.clone());
@@ -57,14 +59,20 @@ impl<'a> WorldCellComplete<'a> {
         }
     }
 
-    pub fn borrow_mut(&self) -> SplitWorldMut {
+    #[inline]
+    /// Returns the current borrows of the world, shared with all `WorldCellSend`s of the split.
+    pub fn borrow_state(&self) -> BorrowState {
+        BorrowState::from_counter(self.borrow.load(Ordering::Relaxed))
+    }
+
+    pub fn borrow_mut(&self) -> SplitWorldMut<'_> {
         match self.try_borrow_mut() {
             Ok(out) => out,
             Err(err) => panic!("{err}"),
         }
     }
 
-    pub fn borrow(&self) -> SplitWorldRef<&World> {
+    pub fn borrow(&self) -> SplitWorldRef<'_, &World> {
         match self.try_borrow() {
             Ok(out) => out,
             Err(err) => panic!("{err}"),
@@ -72,7 +80,7 @@ impl<'a> WorldCellComplete<'a> {
     }
 
     #[inline]
-    pub fn try_borrow_mut(&self) -> Result<SplitWorldMut, Error> {
+    pub fn try_borrow_mut(&self) -> Result<SplitWorldMut<'_>, Error> {
         let old = match self
             .borrow
             .compare_exchange(0, HIGH, Ordering::Acquire, Ordering::Relaxed)
@@ -91,18 +99,18 @@ impl<'a> WorldCellComplete<'a> {
                 value,
             })
         }
-        // high bit NOT set
+        // high bit NOT set, shared borrowed
         else if old & HIGH == 0 {
-            Err(ERROR_MUTABLE_BORROWED)
+            Err(ERROR_SHARED_BORROWED)
         }
         // mutably borrowed,
         else {
-            Err(ERROR_SHARED_BORROWED)
+            Err(ERROR_MUTABLE_BORROWED)
         }
     }
 
     #[inline]
-    pub fn try_borrow(&self) -> Result<SplitWorldRef<&World>, Error> {
+    pub fn try_borrow(&self) -> Result<SplitWorldRef<'_, &World>, Error> {
         // reserve borrow
         let new = self.borrow.fetch_add(1, Ordering::Acquire) + 1;
 
@@ -135,6 +143,18 @@ impl<'a> WorldCellComplete<'a> {
             })
         }
     }
+
+    /// Releases every outstanding borrow, returning `true` if any was left behind.
+    ///
+    /// Guards release their borrow while unwinding, but a guard that is leaked (e.g. a caught panic inside a `Drop`)
+    /// would keep the world locked for every following stage.
+    ///
+    /// # Safety
+    /// No `SplitWorldMut` or `SplitWorldRef` of this cell may be alive, i.e. every system of the stage has finished.
+    #[inline]
+    pub unsafe fn reset_borrow(&self) -> bool {
+        self.borrow.swap(0, Ordering::AcqRel) != 0
+    }
 }
 
 #[derive(Clone)]
@@ -152,7 +172,13 @@ impl<'a> WorldCellSend<'a> {
         Self { data, borrow }
     }
 
-    pub fn borrow(&self) -> SplitWorldRef<SendWorldPtr> {
+    #[inline]
+    /// Returns the current borrows of the world, shared with the `WorldCellComplete` of the split.
+    pub fn borrow_state(&self) -> BorrowState {
+        BorrowState::from_counter(self.borrow.load(Ordering::Relaxed))
+    }
+
+    pub fn borrow(&self) -> SplitWorldRef<'_, SendWorldPtr<'_>> {
         match self.try_borrow() {
             Ok(out) => out,
             Err(err) => panic!("{err}"),
@@ -160,7 +186,7 @@ impl<'a> WorldCellSend<'a> {
     }
 
     #[inline]
-    pub fn try_borrow(&self) -> Result<SplitWorldRef<SendWorldPtr>, Error> {
+    pub fn try_borrow(&self) -> Result<SplitWorldRef<'_, SendWorldPtr<'_>>, Error> {
         // reserve borrow
         let new = self.borrow.fetch_add(1, Ordering::Acquire) + 1;
 
@@ -249,3 +275,46 @@ impl std::ops::DerefMut for SplitWorldMut<'_> {
         &mut self.value
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::split_world;
+    use crate::{World, cells::BorrowState};
+
+    #[test]
+    fn test_split_borrow_state() {
+        let mut world = World::new();
+        let (complete, send) = split_world(&mut world);
+        assert_eq!(complete.borrow_state(), BorrowState::Unshared);
+
+        // both cells share the counter
+        let shared = send.borrow();
+        let shared_too = complete.borrow();
+        assert_eq!(complete.borrow_state(), BorrowState::Shared(2));
+        assert_eq!(send.borrow_state(), BorrowState::Shared(2));
+        drop(shared);
+        drop(shared_too);
+
+        let exclusive = complete.borrow_mut();
+        assert_eq!(send.borrow_state(), BorrowState::MutBorrowed);
+        drop(exclusive);
+
+        assert_eq!(send.borrow_state(), BorrowState::Unshared);
+    }
+
+    #[test]
+    fn test_reset_leaked_borrow() {
+        let mut world = World::new();
+        let (complete, _send) = split_world(&mut world);
+
+        // nothing borrowed, nothing to reset
+        assert!(!unsafe { complete.reset_borrow() });
+
+        std::mem::forget(complete.borrow_mut());
+        assert!(complete.try_borrow().is_err());
+
+        assert!(unsafe { complete.reset_borrow() });
+        assert!(complete.try_borrow_mut().is_ok());
+    }
+}
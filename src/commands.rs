@@ -3,12 +3,14 @@ use std::any::TypeId;
 use crossbeam_channel::{Receiver, Sender, unbounded};
 
 use crate::{
-    Entity, Resource,
+    Component, Entity, NoSend, Resource,
     components::{
-        ComponentAddModifier, ComponentRemoveModifier, ComponentSet, UntypedComponentSet,
+        ComponentAddModifier, ComponentBatchModifier, ComponentMutateModifier, ComponentMutation,
+        ComponentPredicate, ComponentPredicateModifier, ComponentRemoveModifier, ComponentSet,
+        UntypedComponentMutation, UntypedComponentPredicate, UntypedComponentSet,
     },
     entity::EntitySpawner,
-    resources::{ResourceStorageModifier, UntypedResource},
+    resources::{ResourceStorageModifier, Resources, UntypedResource},
 };
 
 #[derive(Debug)]
@@ -80,6 +82,28 @@ impl CommandCenter {
     }
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// The number of commands applied by `World::apply_commands`, per kind.
+pub struct CommandStats {
+    pub entity: usize,
+    pub component: usize,
+    pub resource: usize,
+}
+
+impl CommandStats {
+    #[inline]
+    /// Returns the total number of applied commands.
+    pub const fn total(&self) -> usize {
+        self.entity + self.component + self.resource
+    }
+
+    #[inline]
+    /// Returns `true`, if no commands were applied.
+    pub const fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
 #[derive(Debug)]
 /// A struct that allows the dispatch of different commands.
 ///
@@ -109,6 +133,34 @@ impl Commands {
         entity
     }
 
+    /// Spawns one `Entity` per `ComponentSet` in the iterator.
+    ///
+    /// All entities are reserved at once and their components are added with a single batched command.
+    /// The returned entities can be used, but are not yet valid.
+    pub fn spawn_batch<C: ComponentSet, I: IntoIterator<Item = C>>(&self, iter: I) -> Vec<Entity> {
+        let components = iter.into_iter().collect::<Vec<C>>();
+        let entities = (0..components.len())
+            .map(|_| self.spawner.reserve())
+            .collect::<Vec<_>>();
+
+        _ = self
+            .entity_sender
+            .send(EntityCommands::SpawnBatch(entities.clone()));
+        _ = self.component_sender.send(ComponentCommands::AddBatch {
+            entities: entities.clone(),
+            components: Box::new(components),
+            producer: ComponentBatchModifier::new::<C>,
+        });
+
+        entities
+    }
+
+    #[inline]
+    /// Activates an `Entity` reserved by a `Spawner`.
+    pub fn spawn_reserved(&self, entity: Entity) {
+        _ = self.entity_sender.send(EntityCommands::SpawnEntity(entity));
+    }
+
     #[inline]
     /// Deletes an `Entity`.
     ///
@@ -119,6 +171,14 @@ impl Commands {
             .send(EntityCommands::DeleteEntity(entity));
     }
 
+    #[inline]
+    /// Deletes all entities that have the component `C`.
+    pub fn despawn_all_with<C: Component>(&self) {
+        _ = self
+            .entity_sender
+            .send(EntityCommands::DeleteAllWith(TypeId::of::<C>()));
+    }
+
     #[inline]
     /// Addes a component to a given `Entity`.
     pub fn add_component<C: ComponentSet>(&self, entity: &Entity, component: C) {
@@ -129,6 +189,18 @@ impl Commands {
         });
     }
 
+    #[inline]
+    /// Addes a component to a given `Entity`, if it does not already have it.
+    ///
+    /// Already existing components are kept.
+    pub fn insert_if_new<C: ComponentSet>(&self, entity: &Entity, component: C) {
+        _ = self.component_sender.send(ComponentCommands::AddComponent {
+            entity: *entity,
+            components: Box::new(component),
+            producer: ComponentAddModifier::new_if_new::<C>,
+        });
+    }
+
     #[inline]
     /// Removes a component from a given `Entity`.
     pub fn remove_component<C: ComponentSet>(&self, entity: &Entity) {
@@ -140,6 +212,40 @@ impl Commands {
             });
     }
 
+    #[inline]
+    /// Mutates the component `C` of a given `Entity`, with its value at the time the command is applied.
+    ///
+    /// Does nothing if the `Entity` does not have the component.
+    pub fn mutate_component<C: Component>(
+        &self,
+        entity: &Entity,
+        f: impl FnOnce(&mut C) + Send + 'static,
+    ) {
+        let mutation: ComponentMutation<C> = Box::new(f);
+
+        _ = self
+            .component_sender
+            .send(ComponentCommands::MutateComponent {
+                entity: *entity,
+                type_id: TypeId::of::<C>(),
+                mutation: Box::new(mutation),
+                modifier: ComponentMutateModifier::new::<C>,
+            });
+    }
+
+    #[inline]
+    /// Deletes a given `Entity`, if its component `C` passes the predicate at the time the command is applied.
+    ///
+    /// Does nothing if the `Entity` does not have the component.
+    pub fn despawn_if<C: Component>(&self, entity: &Entity, predicate: ComponentPredicate<C>) {
+        _ = self.component_sender.send(ComponentCommands::DespawnIf {
+            entity: *entity,
+            type_id: TypeId::of::<C>(),
+            predicate: Box::new(predicate),
+            modifier: ComponentPredicateModifier::new::<C>,
+        });
+    }
+
     #[inline]
     /// Adds a new resource.
     pub fn add_resource<R: Resource>(&self, resource: R) {
@@ -177,16 +283,35 @@ impl Commands {
                 type_id: TypeId::of::<R>(),
             })
     }
+
+    #[inline]
+    /// Initializes a global `NoSend` resource with its default value, if it does not exist yet.
+    ///
+    /// Only the type is send, the resource itself is created on the main thread.
+    pub fn init_nosend_resource<R: NoSend + Default>(&self) {
+        _ = self
+            .resource_sender
+            .send(ResourceCommands::GlobalInitNoSend {
+                init: |resources| {
+                    if !resources.contains_resource::<R>() {
+                        resources.insert_resource(R::default());
+                    }
+                },
+            })
+    }
 }
 
 #[derive(Debug)]
 /// Different kind of `Entity` commands.
 pub enum EntityCommands {
     SpawnEntity(Entity),
+    SpawnBatch(Vec<Entity>),
     DeleteEntity(Entity),
+    DeleteAllWith(TypeId),
 }
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 /// Different kind of component commands.
 pub enum ComponentCommands {
     AddComponent {
@@ -198,9 +323,27 @@ pub enum ComponentCommands {
         entity: Entity,
         modifier: fn() -> ComponentRemoveModifier,
     },
+    MutateComponent {
+        entity: Entity,
+        type_id: TypeId,
+        mutation: Box<UntypedComponentMutation>,
+        modifier: fn() -> ComponentMutateModifier,
+    },
+    DespawnIf {
+        entity: Entity,
+        type_id: TypeId,
+        predicate: Box<UntypedComponentPredicate>,
+        modifier: fn() -> ComponentPredicateModifier,
+    },
+    AddBatch {
+        entities: Vec<Entity>,
+        components: Box<UntypedComponentSet>,
+        producer: fn() -> ComponentBatchModifier,
+    },
 }
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 /// Different kind of resource commands.
 pub enum ResourceCommands {
     AddResource {
@@ -218,4 +361,9 @@ pub enum ResourceCommands {
     GlobalRemoveResource {
         type_id: TypeId,
     },
+
+    // no value crosses threads, the resource is created when applied
+    GlobalInitNoSend {
+        init: fn(&mut Resources<dyn NoSend>),
+    },
 }
@@ -1,16 +1,43 @@
-use std::any::{Any, TypeId};
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use crate::{
-    entity::{Entity, EntitySpawner, Generation},
+    entity::{Entity, EntitySpawner, Generation, SpawnError},
     macros::unwrap,
-    table::{ExtendableTable, Table, TableId, TableIdent},
+    table::{ExtendableTable, Row, Table, TableId, TableIdent},
 };
 
-pub trait Component: Any + Send + Sync {}
+pub trait Component: Any + Send + Sync {
+    #[inline]
+    /// Returns a stable name of this component, e.g. for inspectors.
+    ///
+    /// Defaults to the type name, can be set with `#[component(name = "...")]` when deriving.
+    fn name() -> &'static str
+    where
+        Self: Sized,
+    {
+        std::any::type_name::<Self>()
+    }
+}
 
 /// A trait representing a type erased component.
 pub type UntypedComponentSet = dyn Any + Send + Sync;
 
+/// A type erased closure, mutating a single component.
+pub type UntypedComponentMutation = dyn Any + Send;
+
+/// A closure mutating a component of type `C`.
+pub type ComponentMutation<C> = Box<dyn FnOnce(&mut C) + Send>;
+
+/// A type erased predicate, checking a single component.
+pub type UntypedComponentPredicate = dyn Any + Send;
+
+/// A predicate checking a component of type `C`.
+pub type ComponentPredicate<C> = fn(&C) -> bool;
+
 pub trait ComponentSet: TableIdent + Send + Sync + 'static {
     /// Returns all the types this ComponentSet contains.
     fn types() -> Vec<TypeId>;
@@ -26,13 +53,187 @@ pub trait ComponentSet: TableIdent + Send + Sync + 'static {
     fn update_rows(self, table: &mut Table, position: usize);
 
     fn push_or_update(self, table: &mut Table, position: usize);
+
+    /// Pushes only the components missing at position, dropping the already existing ones.
+    fn push_missing(self, table: &mut Table, position: usize);
 }
 
-#[derive(Default)]
 pub struct EntityComponents {
     pub(crate) tables: Vec<Table>,
-    pub(crate) entities: Vec<(Generation, TableId)>,
+    pub(crate) entities: EntitySlots,
     spawner: EntitySpawner,
+
+    // position of every table in `tables`
+    table_index: TableIndex,
+
+    // recently resolved target tables when adding components
+    transitions: TableTransitions,
+
+    // changes whenever tables are added or removed, see `QueryState`
+    table_generation: u64,
+}
+
+// shared by all scenes, so the generation of one scene never matches another one
+static TABLE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[inline]
+fn next_table_generation() -> u64 {
+    TABLE_GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+impl Default for EntityComponents {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A small LRU cache of `(current table, added components) -> target table`.
+///
+/// Skips recomputing the target `TableId` when components are added one at a time to many entities.
+#[derive(Default)]
+struct TableTransitions {
+    // most recently used first
+    entries: Vec<(TableId, TableId, TableId)>,
+}
+
+impl TableTransitions {
+    const CAPACITY: usize = 8;
+
+    #[inline]
+    fn get(&mut self, current: TableId, added: TableId) -> Option<TableId> {
+        let i = self
+            .entries
+            .iter()
+            .position(|(from, set, _)| *from == current && *set == added)?;
+
+        // move to front
+        self.entries[..=i].rotate_right(1);
+
+        Some(self.entries[0].2)
+    }
+
+    #[inline]
+    fn insert(&mut self, current: TableId, added: TableId, target: TableId) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop();
+        }
+
+        self.entries.insert(0, (current, added, target));
+    }
+}
+
+/// The generation and table of every entity, by position.
+///
+/// Slots start at the first position of the spawner, so a world with a range far from 0 doesn't allocate slots for positions it never hands out.
+#[derive(Debug, Clone)]
+pub struct EntitySlots {
+    start: u32,
+    slots: Vec<(Generation, TableId)>,
+}
+
+impl EntitySlots {
+    #[inline]
+    const fn new(start: u32) -> Self {
+        Self {
+            start,
+            slots: Vec::new(),
+        }
+    }
+
+    #[inline]
+    /// Returns the index of the slot of an `Entity`, or `None` if its position is before the first slot.
+    const fn index(&self, entity: &Entity) -> Option<usize> {
+        entity.id().checked_sub(self.start as usize)
+    }
+
+    #[inline]
+    pub fn get(&self, entity: &Entity) -> Option<&(Generation, TableId)> {
+        self.slots.get(self.index(entity)?)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, entity: &Entity) -> Option<&mut (Generation, TableId)> {
+        let index = self.index(entity)?;
+        self.slots.get_mut(index)
+    }
+
+    #[cfg(test)]
+    #[inline]
+    /// Returns the number of slots, including the ones of dead entities.
+    const fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Grows the slots, so all positions below `end` fit.
+    fn grow_to(&mut self, end: usize) {
+        let len = end.saturating_sub(self.start as usize);
+
+        if self.slots.len() < len {
+            self.slots.resize(
+                len.next_power_of_two(),
+                (Generation::invalid(), TableId::invalid()),
+            );
+        }
+    }
+
+    /// Iterates over all slots, together with their position.
+    fn iter(&self) -> impl Iterator<Item = (u32, &(Generation, TableId))> {
+        (self.start..).zip(&self.slots)
+    }
+}
+
+/// The position of every table in `EntityComponents::tables`, by `TableId`.
+///
+/// Kept in sync whenever tables are added or removed, so finding a table doesn't scan all of them.
+#[derive(Default)]
+struct TableIndex {
+    positions: HashMap<TableId, usize>,
+}
+
+impl TableIndex {
+    fn new(tables: &[Table]) -> Self {
+        let mut index = Self::default();
+        index.rebuild(tables);
+        index
+    }
+
+    #[inline]
+    fn get(&self, table_id: TableId) -> Option<usize> {
+        self.positions.get(&table_id).copied()
+    }
+
+    /// Appends a table, returning its position.
+    #[inline]
+    fn push(&mut self, tables: &mut Vec<Table>, table: Table) -> usize {
+        let i = tables.len();
+        self.positions.insert(table.id(), i);
+        tables.push(table);
+        i
+    }
+
+    /// Removes the table at `i`, fixing the position of the last table moved in its place.
+    #[inline]
+    fn swap_remove(&mut self, tables: &mut Vec<Table>, i: usize) -> Table {
+        let table = tables.swap_remove(i);
+        self.positions.remove(&table.id());
+
+        if let Some(moved) = tables.get(i) {
+            self.positions.insert(moved.id(), i);
+        }
+
+        table
+    }
+
+    /// Recomputes all positions, after tables were removed in bulk.
+    fn rebuild(&mut self, tables: &[Table]) {
+        self.positions.clear();
+
+        // the first table wins, if an id appears more than once (see `EntityComponents::defragment`)
+        for (i, table) in tables.iter().enumerate().rev() {
+            self.positions.insert(table.id(), i);
+        }
+    }
 }
 
 #[cfg(feature = "debug-utils")]
@@ -48,13 +249,46 @@ impl std::fmt::Debug for EntityComponents {
 
 impl EntityComponents {
     pub fn new() -> Self {
+        Self::with_spawner(EntitySpawner::new())
+    }
+
+    pub fn with_spawner(spawner: EntitySpawner) -> Self {
         Self {
             tables: Vec::new(),
-            entities: Vec::new(),
-            spawner: EntitySpawner::new(),
+            entities: EntitySlots::new(spawner.range().start),
+            spawner,
+            table_index: TableIndex::default(),
+            transitions: TableTransitions::default(),
+            table_generation: next_table_generation(),
         }
     }
 
+    /// Clones all entities and tables, using `clone_row` for every row.
+    ///
+    /// Returns `None`, if a row can't be cloned.
+    pub fn try_clone(&self, clone_row: impl Fn(&Row) -> Option<Row>) -> Option<Self> {
+        let tables = self
+            .tables
+            .iter()
+            .map(|table| table.try_clone(&clone_row))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            table_index: TableIndex::new(&tables),
+            tables,
+            entities: self.entities.clone(),
+            spawner: self.spawner.snapshot(),
+            transitions: TableTransitions::default(),
+            table_generation: next_table_generation(),
+        })
+    }
+
+    #[inline]
+    /// Returns a value that changes whenever tables are added or removed.
+    pub(crate) const fn table_generation(&self) -> u64 {
+        self.table_generation
+    }
+
     #[inline]
     pub fn spawner(&self) -> EntitySpawner {
         self.spawner.clone()
@@ -65,67 +299,243 @@ impl EntityComponents {
         let entity = self.spawner.reserve();
 
         // activate entity
-        self.activate_entity(entity);
+        let activated = self.activate_entity(entity);
+        debug_assert!(activated);
 
         entity
     }
 
+    /// Grows the entity slots once, so all currently reserved entities fit.
+    ///
+    /// Avoids repeated reallocation when activating many reserved entities.
+    pub fn reserve_slots(&mut self) {
+        self.entities.grow_to(self.spawner.reserved_len());
+    }
+
     /// Activates a previously reserved `Entity` via commands.
-    pub fn activate_entity(&mut self, entity: Entity) {
+    ///
+    /// Returns `false` (and does nothing), if the reservation is stale, because the slot is already in use.
+    pub fn activate_entity(&mut self, entity: Entity) -> bool {
         // don't active invalid entity
-        debug_assert!(!entity.generation().is_invalid());
+        if entity.generation().is_invalid() {
+            return false;
+        }
 
         // set entity generation
-        if self.entities.len() <= entity.id() {
-            self.entities.resize(
-                (entity.id() + 1).next_power_of_two(),
-                (Generation::invalid(), TableId::invalid()),
-            );
-        }
+        self.entities.grow_to(entity.id() + 1);
 
-        // only activate invalid entity
-        debug_assert!(self.entities[entity.id()].0.is_invalid());
-        debug_assert!(self.entities[entity.id()].1.is_invalid());
+        // only activate invalid entity, positions before the range have no slot
+        let Some((generation, in_table)) = self.entities.get_mut(&entity) else {
+            return false;
+        };
+        if !generation.is_invalid() {
+            return false;
+        }
+        debug_assert!(in_table.is_invalid());
 
         // update/set generation
-        self.entities[entity.id()].0 = entity.generation();
+        *generation = entity.generation();
+
+        true
     }
 
-    pub fn delete_entity(&mut self, entity: Entity) {
+    /// Activates a specific `Entity`, instead of letting the spawner allocate one.
+    ///
+    /// # Errors
+    /// Returns a `SpawnError`, if the slot is already occupied or the `Entity` is invalid.
+    pub fn activate_entity_with_id(&mut self, entity: Entity) -> Result<(), SpawnError> {
+        if entity.generation().is_invalid() {
+            return Err(SpawnError::InvalidGeneration(entity));
+        }
+
+        if !self.spawner.range().contains(&entity.to_raw().0) {
+            return Err(SpawnError::OutOfRange(entity));
+        }
+
+        // slot is in use by a live entity
+        if let Some((generation, _)) = self.entities.get(&entity)
+            && !generation.is_invalid()
+        {
+            return Err(SpawnError::Occupied(entity));
+        }
+
+        // slot is reserved, but not yet activated
+        if !self.spawner.claim(&entity) {
+            return Err(SpawnError::Occupied(entity));
+        }
+
+        let activated = self.activate_entity(entity);
+        debug_assert!(activated);
+
+        Ok(())
+    }
+
+    /// Deletes an `Entity` and all its components.
+    ///
+    /// Returns `false`, if the `Entity` was not alive.
+    pub fn delete_entity(&mut self, entity: Entity) -> bool {
         // look up in what table the entity is
-        let (generation, table_id) = unwrap!(self.entities.get_mut(entity.id()));
+        let Some((generation, table_id)) = self.entities.get_mut(&entity) else {
+            return false;
+        };
 
         // entity is not valid
         let ent_gen = entity.generation();
         if ent_gen.is_invalid() || ent_gen != *generation {
-            return;
+            return false;
+        }
+
+        // invalidate slot and unset table-link
+        generation.set_invalid();
+        let table_id = std::mem::replace(table_id, TableId::invalid());
+
+        self.spawner.free(entity);
+
+        // entity has no components
+        if table_id.is_invalid() {
+            return true;
         }
 
         // find table
-        let (pos, table) = unwrap!(
-            self.tables
-                .iter_mut()
-                .enumerate()
-                .find(|(_, table)| table.id() == *table_id)
-        );
+        let pos = unwrap!(self.table_index.get(table_id));
+        let table = &mut self.tables[pos];
 
         // delete entity from table
         table.delete_entity(entity);
 
-        // unset table-link
-        self.entities[pos].1 = TableId::invalid();
-
-        self.spawner.free(entity);
-
         // if table is empty, remove it?
         if table.is_empty() {
-            self.tables.swap_remove(pos);
+            self.table_index.swap_remove(&mut self.tables, pos);
+            self.table_generation = next_table_generation();
+        }
+
+        true
+    }
+
+    /// Merges tables with the same `TableId`, returning the number of merged tables.
+    ///
+    /// Tables are only created, if there isn't one with the same id, so this is a repair tool, that should find nothing to do.
+    pub fn defragment(&mut self) -> usize {
+        let mut merged = 0;
+
+        let mut i = 0;
+        while i < self.tables.len() {
+            let id = self.tables[i].id();
+
+            while let Some(j) = self.tables[i + 1..]
+                .iter()
+                .position(|table| table.id() == id)
+            {
+                // entities link to the id of the table, which stays the same
+                let duplicate = self.tables.swap_remove(i + 1 + j);
+                self.tables[i].merge(duplicate);
+
+                merged += 1;
+            }
+
+            i += 1;
+        }
+
+        if merged > 0 {
+            self.table_index.rebuild(&self.tables);
+            self.table_generation = next_table_generation();
+        }
+
+        merged
+    }
+
+    /// Returns all live entities, ordered by their position.
+    ///
+    /// Includes entities without components, but not entities that are only reserved (not yet activated).
+    pub fn iter_entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities
+            .iter()
+            .filter(|(_, (generation, _))| !generation.is_invalid())
+            .map(|(position, (generation, _))| Entity::new(position, *generation))
+    }
+
+    /// Returns `true`, if the `Entity` is alive.
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        let ent_gen = entity.generation();
+        self.entities
+            .get(entity)
+            .is_some_and(|(generation, _)| !ent_gen.is_invalid() && ent_gen == *generation)
+    }
+
+    /// Returns the table an `Entity` is stored in, mutably.
+    ///
+    /// Returns `None` for invalid entities, or entities without components.
+    pub fn table_of_mut(&mut self, entity: &Entity) -> Option<&mut Table> {
+        let (generation, table_id) = self.entities.get(entity)?;
+
+        // entity is not valid
+        let ent_gen = entity.generation();
+        if ent_gen.is_invalid() || ent_gen != *generation || table_id.is_invalid() {
+            return None;
+        }
+
+        let i = self.table_index.get(*table_id)?;
+        self.tables.get_mut(i)
+    }
+
+    /// Returns the table an `Entity` is stored in.
+    ///
+    /// Returns `None` for invalid entities, or entities without components.
+    pub fn table_of(&self, entity: &Entity) -> Option<&Table> {
+        let (generation, table_id) = self.entities.get(entity)?;
+
+        // entity is not valid
+        let ent_gen = entity.generation();
+        if ent_gen.is_invalid() || ent_gen != *generation {
+            return None;
+        }
+
+        // entity has no components
+        if table_id.is_invalid() {
+            return None;
+        }
+
+        let i = self.table_index.get(*table_id)?;
+        self.tables.get(i)
+    }
+
+    /// Deletes all entities that have a component with the given `TypeId`.
+    ///
+    /// Matching tables are dropped as a whole, instead of deleting entity by entity.
+    pub fn delete_entities_with(&mut self, type_id: TypeId) {
+        self.table_generation = next_table_generation();
+
+        for table in self
+            .tables
+            .extract_if(.., |table| table.contains_one(type_id))
+        {
+            for entity in &table.entities {
+                // unset table-link and invalidate slot
+                let (generation, in_table) = unwrap!(self.entities.get_mut(entity));
+                generation.set_invalid();
+                *in_table = TableId::invalid();
+
+                self.spawner.free(*entity);
+            }
         }
+
+        self.table_index.rebuild(&self.tables);
     }
 
+    #[inline]
     pub fn add_components<C: ComponentSet>(&mut self, entity: &Entity, components: C) {
+        self.insert_components(entity, components, false);
+    }
+
+    /// Adds components to an `Entity`, without overriding components it already has.
+    #[inline]
+    pub fn add_components_if_new<C: ComponentSet>(&mut self, entity: &Entity, components: C) {
+        self.insert_components(entity, components, true);
+    }
+
+    fn insert_components<C: ComponentSet>(&mut self, entity: &Entity, components: C, if_new: bool) {
         // try to find entity
-        let (generation, in_table) = match self.entities.get_mut(entity.id()) {
+        let (generation, in_table) = match self.entities.get_mut(entity) {
             Some((generation, in_table)) => (generation, in_table),
             None => return,
         };
@@ -144,11 +554,7 @@ impl EntityComponents {
 
             *in_table = component_table_id;
 
-            match self
-                .tables
-                .iter_mut()
-                .position(|table| table.id() == component_table_id)
-            {
+            match self.table_index.get(component_table_id) {
                 Some(table_i) => {
                     // insert directly in correct table
                     let target_table = unwrap!(self.tables.get_mut(table_i));
@@ -161,51 +567,60 @@ impl EntityComponents {
                     new_table.push(*entity, components);
 
                     // insert new table in table list
-                    self.tables.push(new_table);
+                    self.table_index.push(&mut self.tables, new_table);
+                    self.table_generation = next_table_generation();
                     return;
                 }
             }
         }
 
         // get current table
-        let current_table_i = unwrap!(
-            self.tables
-                .iter_mut()
-                .position(|table| table.id() == *in_table)
-        );
+        let current_table_i = unwrap!(self.table_index.get(*in_table));
 
         let current_table = unwrap!(self.tables.get_mut(current_table_i));
 
         // same components, just update table
         if *in_table == component_table_id {
-            current_table.update::<C>(entity, components);
+            if !if_new {
+                current_table.update::<C>(entity, components);
+            }
             return;
         }
 
-        // ComponentSet is subset of current table (no move, just update/override)
-        let types = C::types();
-        if current_table.contains_all(&types) {
-            current_table.update_partial::<C>(entity, components);
-            return;
-        }
+        // a previous call already resolved the target table (which is never the current one)
+        let target_table_id = match self.transitions.get(*in_table, component_table_id) {
+            Some(target_table_id) => target_table_id,
+            None => {
+                // ComponentSet is subset of current table (no move, just update/override)
+                let types = C::types();
+                if current_table.contains_all(&types) {
+                    if !if_new {
+                        current_table.update_partial::<C>(entity, components);
+                    }
+                    return;
+                }
 
-        // #! entity has components, adds additional (potential overlapping) components
+                // #! entity has components, adds additional (potential overlapping) components
 
-        // compute types and TableId
-        let mut set = types;
-        for t in current_table.types() {
-            // insert uniques
-            if !set.contains(&t) {
-                set.push(t);
+                // compute types and TableId
+                let mut set = types;
+                for t in current_table.types() {
+                    // insert uniques
+                    if !set.contains(&t) {
+                        set.push(t);
+                    }
+                }
+                let target_table_id = TableId::from_uniques(set.iter());
+
+                self.transitions
+                    .insert(*in_table, component_table_id, target_table_id);
+
+                target_table_id
             }
-        }
-        let target_table_id = TableId::from_uniques(set.iter());
+        };
 
         // find fitting table
-        let target_table_index = self
-            .tables
-            .iter_mut()
-            .position(|table| table.id() == target_table_id);
+        let target_table_index = self.table_index.get(target_table_id);
 
         // find table to push ComponentSet in
         let target_table_i = target_table_index.unwrap_or_else(|| {
@@ -220,8 +635,8 @@ impl EntityComponents {
             let new_table = extend.finish();
 
             // insert new table in table list
-            let i = self.tables.len();
-            self.tables.push(new_table);
+            let i = self.table_index.push(&mut self.tables, new_table);
+            self.table_generation = next_table_generation();
             i
         });
 
@@ -235,7 +650,11 @@ impl EntityComponents {
         current_table.move_entity_up(target_table, entity);
 
         // push missing component and/or override already existing
-        target_table.push_missing_or_update(entity, components);
+        if if_new {
+            target_table.push_missing(entity, components);
+        } else {
+            target_table.push_missing_or_update(entity, components);
+        }
 
         *in_table = target_table_id;
     }
@@ -247,7 +666,7 @@ impl EntityComponents {
         modifier: ComponentAddModifier,
     ) {
         // try to find entity
-        let (generation, in_table) = match self.entities.get_mut(entity.id()) {
+        let (generation, in_table) = match self.entities.get_mut(entity) {
             Some((generation, in_table)) => (generation, in_table),
             None => return,
         };
@@ -266,11 +685,7 @@ impl EntityComponents {
 
             *in_table = component_table_id;
 
-            match self
-                .tables
-                .iter_mut()
-                .position(|table| table.id() == component_table_id)
-            {
+            match self.table_index.get(component_table_id) {
                 Some(table_i) => {
                     // insert directly in correct table
                     let target_table = unwrap!(self.tables.get_mut(table_i));
@@ -285,31 +700,32 @@ impl EntityComponents {
                     (modifier.push)(&mut new_table, *entity, components);
 
                     // insert new table in table list
-                    self.tables.push(new_table);
+                    self.table_index.push(&mut self.tables, new_table);
+                    self.table_generation = next_table_generation();
                     return;
                 }
             }
         }
 
         // get current table
-        let current_table_i = unwrap!(
-            self.tables
-                .iter_mut()
-                .position(|table| table.id() == *in_table)
-        );
+        let current_table_i = unwrap!(self.table_index.get(*in_table));
 
         let current_table = unwrap!(self.tables.get_mut(current_table_i));
 
         // same components, just update table
         if *in_table == component_table_id {
-            (modifier.update)(current_table, entity, components);
+            if !modifier.if_new {
+                (modifier.update)(current_table, entity, components);
+            }
             return;
         }
 
         // ComponentSet is subset of current table (no move, just update/override)
         let types = (modifier.types)();
         if current_table.contains_all(&types) {
-            (modifier.update_partial)(current_table, entity, components);
+            if !modifier.if_new {
+                (modifier.update_partial)(current_table, entity, components);
+            }
             return;
         }
 
@@ -326,10 +742,7 @@ impl EntityComponents {
         let target_table_id = TableId::from_uniques(set.iter());
 
         // find fitting table
-        let target_table_index = self
-            .tables
-            .iter_mut()
-            .position(|table| table.id() == target_table_id);
+        let target_table_index = self.table_index.get(target_table_id);
 
         // find table to push ComponentSet in
         let target_table_i = target_table_index.unwrap_or_else(|| {
@@ -344,8 +757,8 @@ impl EntityComponents {
             let new_table = extend.finish();
 
             // insert new table in table list
-            let i = self.tables.len();
-            self.tables.push(new_table);
+            let i = self.table_index.push(&mut self.tables, new_table);
+            self.table_generation = next_table_generation();
             i
         });
 
@@ -359,14 +772,119 @@ impl EntityComponents {
         current_table.move_entity_up(target_table, entity);
 
         // push missing component and/or override already existing
-        (modifier.push_missing_or_update)(target_table, entity, components);
+        if modifier.if_new {
+            (modifier.push_missing)(target_table, entity, components);
+        } else {
+            (modifier.push_missing_or_update)(target_table, entity, components);
+        }
 
         *in_table = target_table_id;
     }
 
+    /// Applies a type erased mutation to the component with the given `TypeId`.
+    ///
+    /// Does nothing for invalid entities, or entities without that component.
+    pub fn mutate_component_untyped(
+        &mut self,
+        entity: &Entity,
+        type_id: TypeId,
+        mutation: Box<UntypedComponentMutation>,
+        modifier: ComponentMutateModifier,
+    ) {
+        let Some((generation, table_id)) = self.entities.get(entity) else {
+            return;
+        };
+
+        // entity is not valid
+        let ent_gen = entity.generation();
+        if ent_gen.is_invalid() || ent_gen != *generation || table_id.is_invalid() {
+            return;
+        }
+
+        let Some(table) = self
+            .table_index
+            .get(*table_id)
+            .and_then(|i| self.tables.get_mut(i))
+        else {
+            return;
+        };
+
+        // entity does not have the component
+        if !table.contains_one(type_id) {
+            return;
+        }
+
+        (modifier.apply)(table, entity, mutation);
+    }
+
+    /// Deletes an `Entity`, if its component with the given `TypeId` passes the type erased predicate.
+    ///
+    /// Returns `false` for invalid entities, entities without that component, or if the predicate failed.
+    pub fn despawn_if_untyped(
+        &mut self,
+        entity: Entity,
+        type_id: TypeId,
+        predicate: Box<UntypedComponentPredicate>,
+        modifier: ComponentPredicateModifier,
+    ) -> bool {
+        let Some(table) = self.table_of_mut(&entity) else {
+            return false;
+        };
+
+        // entity does not have the component
+        if !table.contains_one(type_id) {
+            return false;
+        }
+
+        if !(modifier.check)(table, &entity, predicate) {
+            return false;
+        }
+
+        self.delete_entity(entity)
+    }
+
+    /// Adds a type erased batch of components to freshly spawned entities.
+    ///
+    /// All entities end up in the same table, which is looked up (or created) only once.
+    /// Invalid entities, or entities that already have components, are skipped.
+    pub fn add_batch_untyped(
+        &mut self,
+        entities: &[Entity],
+        components: Box<UntypedComponentSet>,
+        modifier: ComponentBatchModifier,
+    ) {
+        let component_table_id = (modifier.table_id)();
+
+        let mut keep = Vec::with_capacity(entities.len());
+        for entity in entities {
+            let valid = match self.entities.get_mut(entity) {
+                Some((generation, in_table)) => {
+                    let valid = entity.generation() == *generation
+                        && !generation.is_invalid()
+                        && in_table.is_invalid();
+                    if valid {
+                        *in_table = component_table_id;
+                    }
+                    valid
+                }
+                None => false,
+            };
+            keep.push(valid);
+        }
+
+        let table_i = self.table_index.get(component_table_id).unwrap_or_else(|| {
+            self.table_generation = next_table_generation();
+            self.table_index
+                .push(&mut self.tables, (modifier.table_new)())
+        });
+        let table = unwrap!(self.tables.get_mut(table_i));
+
+        (modifier.push_batch)(table, entities, &keep, components);
+    }
+
     pub fn remove_component<C: ComponentSet>(&mut self, entity: &Entity) {
         // try to find entity
-        let (generation, in_table) = match self.entities.get_mut(entity.id()) {
+        let (generation, in_table) = match self.entities.get_mut(entity) {
             Some((generation, in_table)) => (generation, in_table),
             None => return,
         };
@@ -381,7 +899,7 @@ impl EntityComponents {
             return;
         }
 
-        let current_table_i = unwrap!(self.tables.iter().position(|table| table.id() == *in_table));
+        let current_table_i = unwrap!(self.table_index.get(*in_table));
 
         let current_table = &self.tables[current_table_i];
 
@@ -390,6 +908,11 @@ impl EntityComponents {
             .filter(|t| !C::contains_type(*t))
             .collect::<Vec<_>>();
 
+        // entity has none of the components, nothing to move
+        if new_types.len() == current_table.rows.len() {
+            return;
+        }
+
         // if all components are removed from entity
         if new_types.is_empty() {
             let current_table = &mut self.tables[current_table_i];
@@ -400,27 +923,14 @@ impl EntityComponents {
 
         let target_table_id = TableId::from_uniques(new_types.iter());
 
-        let target_table_i = self
-            .tables
-            .iter()
-            .position(|table| table.id() == target_table_id)
-            .unwrap_or_else(|| {
-                let current_table = unwrap!(self.tables.get(current_table_i));
-
-                // get a fresh/empty clone of the current table
-                // use already computed types and id here
-                let mut extend = current_table.get_extendable_precomputed(target_table_id);
-
-                // remove all rows belonging to C
-                extend.remove_rows::<C>();
-
-                let new_table = extend.finish();
-
-                // insert new table in table list
-                let i = self.tables.len();
-                self.tables.push(new_table);
-                i
-            });
+        let target_table_i = Self::find_or_create_table_down(
+            &mut self.tables,
+            &mut self.table_index,
+            &mut self.table_generation,
+            current_table_i,
+            target_table_id,
+            ExtendableTable::remove_rows::<C>,
+        );
 
         // get disjoint
         let [current_table, target_table] = unwrap!(
@@ -433,13 +943,129 @@ impl EntityComponents {
         *in_table = target_table_id;
     }
 
+    /// Removes the components `C` from all given entities.
+    ///
+    /// Entities are grouped by their current table, so the target table is resolved once per source table.
+    pub fn remove_components_batch<C: ComponentSet>(&mut self, entities: &[Entity]) {
+        // group valid entities by their current table
+        let mut groups: Vec<(TableId, Vec<Entity>)> = Vec::new();
+        for entity in entities {
+            let Some((generation, in_table)) = self.entities.get(entity) else {
+                continue;
+            };
+
+            // check entity validity, entities without components have nothing to remove
+            if entity.generation() != *generation
+                || generation.is_invalid()
+                || in_table.is_invalid()
+            {
+                continue;
+            }
+
+            match groups.iter_mut().find(|(table_id, _)| table_id == in_table) {
+                Some((_, group)) => group.push(*entity),
+                None => groups.push((*in_table, vec![*entity])),
+            }
+        }
+
+        for (table_id, group) in groups {
+            let current_table_i = unwrap!(self.table_index.get(table_id));
+
+            let new_types = self.tables[current_table_i]
+                .types()
+                .filter(|t| !C::contains_type(*t))
+                .collect::<Vec<_>>();
+
+            // if all components are removed from the entities
+            if new_types.is_empty() {
+                let current_table = &mut self.tables[current_table_i];
+                for entity in group {
+                    let (_, in_table) = unwrap!(self.entities.get_mut(&entity));
+
+                    // skip duplicates
+                    if *in_table != table_id {
+                        continue;
+                    }
+
+                    current_table.delete_entity(entity);
+                    *in_table = TableId::invalid();
+                }
+                continue;
+            }
+
+            let target_table_id = TableId::from_uniques(new_types.iter());
+
+            // none of the components are in this table
+            if target_table_id == table_id {
+                continue;
+            }
+
+            let target_table_i = Self::find_or_create_table_down(
+                &mut self.tables,
+                &mut self.table_index,
+                &mut self.table_generation,
+                current_table_i,
+                target_table_id,
+                ExtendableTable::remove_rows::<C>,
+            );
+
+            // get disjoint
+            let [current_table, target_table] = unwrap!(
+                self.tables
+                    .get_disjoint_mut([current_table_i, target_table_i])
+            );
+
+            for entity in group {
+                let (_, in_table) = unwrap!(self.entities.get_mut(&entity));
+
+                // skip duplicates
+                if *in_table != table_id {
+                    continue;
+                }
+
+                current_table.move_entity_down(target_table, &entity);
+                *in_table = target_table_id;
+            }
+        }
+    }
+
+    // returns the position of the target table, creating it from the current table if necessary
+    fn find_or_create_table_down(
+        tables: &mut Vec<Table>,
+        table_index: &mut TableIndex,
+        table_generation: &mut u64,
+        current_table_i: usize,
+        target_table_id: TableId,
+        remove_rows: impl FnOnce(&mut ExtendableTable),
+    ) -> usize {
+        if let Some(i) = table_index.get(target_table_id) {
+            return i;
+        }
+
+        let current_table = unwrap!(tables.get(current_table_i));
+
+        // get a fresh/empty clone of the current table
+        // use already computed types and id here
+        let mut extend = current_table.get_extendable_precomputed(target_table_id);
+
+        // remove all rows belonging to C
+        remove_rows(&mut extend);
+
+        let new_table = extend.finish();
+
+        // insert new table in table list
+        let i = table_index.push(tables, new_table);
+        *table_generation = next_table_generation();
+        i
+    }
+
     pub fn remove_components_untyped(
         &mut self,
         entity: &Entity,
         modifier: ComponentRemoveModifier,
     ) {
         // try to find entity
-        let (generation, in_table) = match self.entities.get_mut(entity.id()) {
+        let (generation, in_table) = match self.entities.get_mut(entity) {
             Some((generation, in_table)) => (generation, in_table),
             None => return,
         };
@@ -454,7 +1080,7 @@ impl EntityComponents {
             return;
         }
 
-        let current_table_i = unwrap!(self.tables.iter().position(|table| table.id() == *in_table));
+        let current_table_i = unwrap!(self.table_index.get(*in_table));
 
         let current_table = &self.tables[current_table_i];
 
@@ -463,6 +1089,11 @@ impl EntityComponents {
             .filter(|t| !(modifier.contains_type)(*t))
             .collect::<Vec<_>>();
 
+        // entity has none of the components, nothing to move
+        if new_types.len() == current_table.rows.len() {
+            return;
+        }
+
         // if all components are removed from entity
         if new_types.is_empty() {
             let current_table = &mut self.tables[current_table_i];
@@ -473,27 +1104,14 @@ impl EntityComponents {
 
         let target_table_id = TableId::from_uniques(new_types.iter());
 
-        let target_table_i = self
-            .tables
-            .iter()
-            .position(|table| table.id() == target_table_id)
-            .unwrap_or_else(|| {
-                let current_table = unwrap!(self.tables.get(current_table_i));
-
-                // get a fresh/empty clone of the current table
-                // use already computed types and id here
-                let mut extend = current_table.get_extendable_precomputed(target_table_id);
-
-                // remove all rows belonging to C
-                (modifier.remove_rows)(&mut extend);
-
-                let new_table = extend.finish();
-
-                // insert new table in table list
-                let i = self.tables.len();
-                self.tables.push(new_table);
-                i
-            });
+        let target_table_i = Self::find_or_create_table_down(
+            &mut self.tables,
+            &mut self.table_index,
+            &mut self.table_generation,
+            current_table_i,
+            target_table_id,
+            modifier.remove_rows,
+        );
 
         // get disjoint
         let [current_table, target_table] = unwrap!(
@@ -516,6 +1134,10 @@ pub struct ComponentAddModifier {
     update_partial: fn(&mut Table, &Entity, Box<UntypedComponentSet>),
     extend_rows: fn(&mut ExtendableTable),
     push_missing_or_update: fn(&mut Table, &Entity, Box<UntypedComponentSet>),
+    push_missing: fn(&mut Table, &Entity, Box<UntypedComponentSet>),
+
+    /// Don't override already existing components.
+    if_new: bool,
 }
 
 impl ComponentAddModifier {
@@ -530,9 +1152,19 @@ impl ComponentAddModifier {
             update_partial: Self::ptf_update_partial::<C>,
             extend_rows: Self::ptf_extend_rows::<C>,
             push_missing_or_update: Self::ptf_push_missing_or_update::<C>,
+            push_missing: Self::ptf_push_missing::<C>,
+            if_new: false,
         }
     }
 
+    #[inline]
+    /// Same as `new`, but keeps already existing components.
+    pub const fn new_if_new<C: ComponentSet>() -> Self {
+        let mut modifier = Self::new::<C>();
+        modifier.if_new = true;
+        modifier
+    }
+
     fn ptf_push<C: ComponentSet>(
         table: &mut Table,
         entity: Entity,
@@ -576,6 +1208,49 @@ impl ComponentAddModifier {
 
         table.push_missing_or_update(entity, components);
     }
+
+    fn ptf_push_missing<C: ComponentSet>(
+        table: &mut Table,
+        entity: &Entity,
+        components: Box<UntypedComponentSet>,
+    ) {
+        let components = *unwrap!(components.downcast::<C>());
+
+        table.push_missing(entity, components);
+    }
+}
+
+pub struct ComponentBatchModifier {
+    table_id: fn() -> TableId,
+    table_new: fn() -> Table,
+    push_batch: fn(&mut Table, &[Entity], &[bool], Box<UntypedComponentSet>),
+}
+
+impl ComponentBatchModifier {
+    pub const fn new<C: ComponentSet>() -> Self {
+        Self {
+            table_id: C::table_id,
+            table_new: Table::new::<C>,
+            push_batch: Self::ptf_push_batch::<C>,
+        }
+    }
+
+    fn ptf_push_batch<C: ComponentSet>(
+        table: &mut Table,
+        entities: &[Entity],
+        keep: &[bool],
+        components: Box<UntypedComponentSet>,
+    ) {
+        let components = *unwrap!(components.downcast::<Vec<C>>());
+        debug_assert_eq!(entities.len(), components.len());
+
+        table.entities.reserve(entities.len());
+        for ((entity, keep), components) in entities.iter().zip(keep).zip(components) {
+            if *keep {
+                table.push(*entity, components);
+            }
+        }
+    }
 }
 
 pub struct ComponentRemoveModifier {
@@ -599,3 +1274,268 @@ impl ComponentRemoveModifier {
         table.remove_rows::<C>();
     }
 }
+
+pub struct ComponentMutateModifier {
+    apply: fn(&mut Table, &Entity, Box<UntypedComponentMutation>),
+}
+
+impl ComponentMutateModifier {
+    pub const fn new<C: Component>() -> Self {
+        Self {
+            apply: Self::ptf_apply::<C>,
+        }
+    }
+
+    fn ptf_apply<C: Component>(
+        table: &mut Table,
+        entity: &Entity,
+        mutation: Box<UntypedComponentMutation>,
+    ) {
+        let mutation = *unwrap!(mutation.downcast::<ComponentMutation<C>>());
+
+        if let Some(component) = table.get_component_mut::<C>(entity) {
+            (mutation)(component);
+        }
+    }
+}
+
+pub struct ComponentPredicateModifier {
+    check: fn(&mut Table, &Entity, Box<UntypedComponentPredicate>) -> bool,
+}
+
+impl ComponentPredicateModifier {
+    pub const fn new<C: Component>() -> Self {
+        Self {
+            check: Self::ptf_check::<C>,
+        }
+    }
+
+    fn ptf_check<C: Component>(
+        table: &mut Table,
+        entity: &Entity,
+        predicate: Box<UntypedComponentPredicate>,
+    ) -> bool {
+        let predicate = *unwrap!(predicate.downcast::<ComponentPredicate<C>>());
+
+        table
+            .get_component_mut::<C>(entity)
+            .is_some_and(|component| predicate(component))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use crate::{entity::EntitySpawner, table::TableIdent};
+
+    use super::{ComponentRemoveModifier, EntityComponents};
+
+    #[test]
+    fn test_reserve_slots_single_allocation() {
+        let mut components = EntityComponents::new();
+        let spawner = components.spawner();
+
+        let entities = (0..10_000).map(|_| spawner.reserve()).collect::<Vec<_>>();
+
+        // grown once for all reservations
+        components.reserve_slots();
+        let slots = components.entities.slots.as_ptr();
+        assert_eq!(components.entities.len(), 10_000_usize.next_power_of_two());
+
+        for entity in entities {
+            assert!(components.activate_entity(entity));
+        }
+
+        // no reallocation while activating
+        assert_eq!(slots, components.entities.slots.as_ptr());
+    }
+
+    #[test]
+    fn test_slots_start_at_range() {
+        let start = 1 << 30;
+        let mut components =
+            EntityComponents::with_spawner(EntitySpawner::with_range(start..start + 100));
+
+        let entity = components.spawn_entity();
+        assert_eq!(entity.to_raw().0, start);
+        assert_eq!(components.entities.len(), 1);
+        assert!(components.is_alive(&entity));
+
+        let spawner = components.spawner();
+        for _ in 0..99 {
+            spawner.reserve();
+        }
+        components.reserve_slots();
+        assert_eq!(components.entities.len(), 100_usize.next_power_of_two());
+    }
+
+    #[test]
+    fn test_add_components_transition_cache() {
+        let mut components = EntityComponents::new();
+        let spawner = components.spawner();
+
+        let entities = (0..10_000).map(|_| spawner.reserve()).collect::<Vec<_>>();
+        for entity in &entities {
+            assert!(components.activate_entity(*entity));
+        }
+
+        // build up entities one component at a time
+        for (i, entity) in entities.iter().enumerate() {
+            components.add_components(entity, i as u32);
+        }
+        for (i, entity) in entities.iter().enumerate() {
+            components.add_components(entity, -(i as i32));
+        }
+
+        // resolved once, reused for all other entities
+        assert_eq!(components.transitions.entries.len(), 1);
+        assert_eq!(
+            components.transitions.get(u32::table_id(), i32::table_id()),
+            Some(<(u32, i32)>::table_id())
+        );
+
+        let table = components.table_of(&entities[42]).unwrap();
+        assert_eq!(table.id(), <(u32, i32)>::table_id());
+        assert_eq!(table.len(), 10_000);
+        assert!(
+            components
+                .tables
+                .iter()
+                .all(|t| t.id() == table.id() || t.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_remove_absent_component() {
+        let mut components = EntityComponents::new();
+        let entity = components.spawn_entity();
+
+        components.add_components(&entity, 7_u32);
+        assert_eq!(components.tables.len(), 1);
+
+        // typed and untyped removal of a component the entity doesn't have
+        components.remove_component::<i32>(&entity);
+        components.remove_components_untyped(&entity, ComponentRemoveModifier::new::<i32>());
+
+        assert_eq!(components.tables.len(), 1);
+        let table = components.table_of(&entity).unwrap();
+        assert_eq!(table.id(), u32::table_id());
+        assert_eq!(table.len(), 1);
+    }
+
+    #[test]
+    fn test_table_index_after_swap_remove() {
+        use super::Component;
+
+        struct A;
+        impl Component for A {}
+        struct B;
+        impl Component for B {}
+        struct C;
+        impl Component for C {}
+        struct D;
+        impl Component for D {}
+
+        let mut components = EntityComponents::new();
+
+        // one archetype per entity
+        let entities = (0..16u32)
+            .map(|i| {
+                let entity = components.spawn_entity();
+                components.add_components(&entity, i);
+                if i % 2 == 0 {
+                    components.add_components(&entity, A);
+                }
+                if i & 2 != 0 {
+                    components.add_components(&entity, B);
+                }
+                if i & 4 != 0 {
+                    components.add_components(&entity, C);
+                }
+                if i & 8 != 0 {
+                    components.add_components(&entity, D);
+                }
+                entity
+            })
+            .collect::<Vec<_>>();
+
+        let check_index = |components: &EntityComponents| {
+            assert_eq!(
+                components.table_index.positions.len(),
+                components.tables.len()
+            );
+            for (i, table) in components.tables.iter().enumerate() {
+                assert_eq!(components.table_index.get(table.id()), Some(i));
+            }
+        };
+        check_index(&components);
+
+        // empties tables at the front, moving the last table in their place
+        for entity in &entities[..4] {
+            assert!(components.delete_entity(*entity));
+            check_index(&components);
+        }
+
+        // structural changes still find the right tables
+        for (i, entity) in entities.iter().enumerate().skip(4) {
+            components.add_components(entity, i as i32);
+            components.remove_component::<u32>(entity);
+            check_index(&components);
+
+            let table = components.table_of(entity).unwrap();
+            assert!(table.contains_one(TypeId::of::<i32>()));
+            assert!(!table.contains_one(TypeId::of::<u32>()));
+            assert!(table.entities.contains(entity));
+        }
+
+        components.delete_entities_with(TypeId::of::<A>());
+        check_index(&components);
+        for (i, entity) in entities.iter().enumerate().skip(4) {
+            assert_eq!(components.table_of(entity).is_some(), i % 2 != 0);
+        }
+    }
+
+    #[test]
+    fn test_defragment_duplicate_tables() {
+        use crate::table::Table;
+
+        let mut components = EntityComponents::new();
+
+        let entities = (0..4)
+            .map(|i| {
+                let entity = components.spawn_entity();
+                components.add_components(&entity, i as u32);
+                entity
+            })
+            .collect::<Vec<_>>();
+
+        // create a second table with the same id by hand
+        let mut duplicate = Table::new::<u32>();
+        let moved = components.spawn_entity();
+        duplicate.push(moved, 10_u32);
+        components.entities.get_mut(&moved).unwrap().1 = duplicate.id();
+        components.tables.push(duplicate);
+
+        assert_eq!(components.tables.len(), 2);
+        assert_eq!(components.defragment(), 1);
+        assert_eq!(components.defragment(), 0);
+
+        assert_eq!(components.tables.len(), 1);
+        let table = &components.tables[0];
+        assert_eq!(table.len(), 5);
+
+        for (i, entity) in entities.iter().chain([&moved]).enumerate() {
+            let table = components.table_of(entity).unwrap();
+            let position = table.entities.iter().position(|e| e == entity).unwrap();
+            let value = table
+                .try_get_row_ref::<u32>()
+                .unwrap()
+                .at(position)
+                .copied();
+
+            let expected = if *entity == moved { 10 } else { i as u32 };
+            assert_eq!(value, Some(expected));
+        }
+    }
+}
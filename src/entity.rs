@@ -1,4 +1,7 @@
-use std::sync::{Arc, atomic::AtomicU32};
+use std::{
+    ops::Range,
+    sync::{Arc, atomic::AtomicU32},
+};
 
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
 
@@ -22,6 +25,21 @@ impl Entity {
         }
     }
 
+    #[inline]
+    /// Recreates an `Entity` from its raw position and generation, e.g. when deserializing.
+    pub const fn from_raw(position: u32, generation: u32) -> Self {
+        Self {
+            position,
+            generation: Generation(generation),
+        }
+    }
+
+    #[inline]
+    /// Returns the raw position and generation of this `Entity`.
+    pub const fn to_raw(&self) -> (u32, u32) {
+        (self.position, self.generation.0)
+    }
+
     #[inline]
     pub(crate) const fn generation(&self) -> Generation {
         self.generation
@@ -86,6 +104,8 @@ impl Generation {
 /// Wraps a position counter and a free-list of entities.
 pub struct EntitySpawner {
     latest_entity: Arc<AtomicU32>,
+    // fresh positions are only handed out within this range
+    range: Range<u32>,
 
     input: Sender<Entity>,
     output: Receiver<Entity>,
@@ -94,19 +114,37 @@ pub struct EntitySpawner {
 impl EntitySpawner {
     #[inline]
     pub fn new() -> Self {
+        Self::with_range(0..u32::MAX)
+    }
+
+    /// Creates a spawner only handing out positions within `range`.
+    ///
+    /// Lets multiple worlds (e.g. shards of a server) allocate non-overlapping entities, by giving each of them its own range.
+    #[inline]
+    pub fn with_range(range: Range<u32>) -> Self {
         let (tx, rx) = crossbeam_channel::unbounded();
 
         Self {
-            latest_entity: Arc::new(AtomicU32::new(0)),
+            latest_entity: Arc::new(AtomicU32::new(range.start)),
+            range,
             input: tx,
             output: rx,
         }
     }
 
+    #[inline]
+    /// Returns the range of positions this spawner hands out.
+    pub fn range(&self) -> Range<u32> {
+        self.range.clone()
+    }
+
     /// Reserves a `Entity`.
     ///
     /// Creates a completly new `Entity`, or reuses a `Entity` that was deleted.
     /// This only reserves the entity, to active it, call `Entities::active_entity`!
+    ///
+    /// # Panics
+    /// - if every position of the range is in use
     pub fn reserve(&self) -> Entity {
         // try to receive already used entity
         match self.output.try_recv() {
@@ -120,12 +158,120 @@ impl EntitySpawner {
                 let position = self
                     .latest_entity
                     .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                assert!(
+                    position < self.range.end,
+                    "Entity range exhausted: {:?}",
+                    self.range
+                );
 
                 Entity::new(position, Generation::new())
             }
         }
     }
 
+    /// Returns the number of positions handed out so far.
+    ///
+    /// Every reserved `Entity` has a position below this value.
+    #[inline]
+    pub fn reserved_len(&self) -> usize {
+        let latest = self
+            .latest_entity
+            .load(std::sync::atomic::Ordering::Relaxed);
+
+        // failed reservations past the end don't count
+        latest.min(self.range.end) as usize
+    }
+
+    /// Claims the position of a given `Entity`, so it will not be handed out by `reserve`.
+    ///
+    /// Returns `false`, if the position is currently in use (or reserved), or outside of the range.
+    pub fn claim(&self, entity: &Entity) -> bool {
+        let position = entity.position;
+        if !self.range.contains(&position) {
+            return false;
+        }
+
+        // fresh position, free all skipped positions in between
+        let latest = self
+            .latest_entity
+            .fetch_max(position + 1, std::sync::atomic::Ordering::Relaxed);
+        if latest <= position {
+            for skipped in latest..position {
+                self.free(Entity::new(skipped, Generation::new()));
+            }
+
+            return true;
+        }
+
+        // position was handed out before, can only be claimed if it is in the free-list
+        let mut found = false;
+        // only check the currently queued entities, as non-matching ones get queued again
+        for _ in 0..self.output.len() {
+            let Ok(free) = self.output.try_recv() else {
+                break;
+            };
+
+            if free.position == position {
+                found = true;
+            } else {
+                _ = self.input.send(free);
+            }
+        }
+
+        found
+    }
+
+    /// Creates an independent spawner, that continues where this one currently is.
+    ///
+    /// Both hand out the same entities afterwards, e.g. for a cloned scene.
+    pub fn snapshot(&self) -> Self {
+        let spawner = Self::with_range(self.range());
+        spawner.latest_entity.store(
+            self.latest_entity
+                .load(std::sync::atomic::Ordering::Relaxed),
+            std::sync::atomic::Ordering::Relaxed,
+        );
+
+        // requeue every free entity, keeping their order
+        for _ in 0..self.output.len() {
+            let Ok(free) = self.output.try_recv() else {
+                break;
+            };
+
+            _ = self.input.send(free);
+            _ = spawner.input.send(free);
+        }
+
+        spawner
+    }
+
+    #[inline]
+    /// Returns the number of entities in the free-list.
+    pub fn free_count(&self) -> usize {
+        self.output.len()
+    }
+
+    /// Reorders the free-list, so lower positions are reused first.
+    ///
+    /// This changes which slots get reused by `reserve`, keeping the used positions dense in long-running worlds.
+    pub fn compact(&self) {
+        // only take the currently queued entities, concurrently freed ones stay behind them
+        let mut free = Vec::with_capacity(self.output.len());
+        for _ in 0..self.output.len() {
+            let Ok(ent) = self.output.try_recv() else {
+                break;
+            };
+
+            free.push(ent);
+        }
+
+        free.sort_unstable_by_key(|ent| ent.position);
+
+        for ent in free {
+            _ = self.input.send(ent);
+        }
+    }
+
     /// Adds a given `Entity` to the free-list.
     pub fn free(&self, mut ent: Entity) {
         ent.generation.set_invalid();
@@ -140,3 +286,60 @@ impl Default for EntitySpawner {
         Self::new()
     }
 }
+
+#[derive(Debug, Clone)]
+/// A system parameter reserving entities right away, without waiting for commands to be applied.
+///
+/// Reserving is lock-free, so it can be used from parallel systems.
+/// A reserved `Entity` is not alive yet, it still has to be activated with `Commands::spawn_reserved` (or a `&mut World`).
+pub struct Spawner {
+    pub(crate) spawner: EntitySpawner,
+}
+
+impl Spawner {
+    #[inline]
+    /// Reserves a new or reused `Entity`, see `EntitySpawner::reserve`.
+    pub fn reserve(&self) -> Entity {
+        self.spawner.reserve()
+    }
+
+    #[inline]
+    /// Returns the number of positions handed out so far.
+    pub fn reserved_len(&self) -> usize {
+        self.spawner.reserved_len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An error returned when spawning an `Entity` with a given id fails.
+pub enum SpawnError {
+    /// The position of the `Entity` is already in use by a live (or reserved) `Entity`.
+    Occupied(Entity),
+
+    /// The generation of the `Entity` is marked as invalid.
+    InvalidGeneration(Entity),
+
+    /// The slot of the `Entity` is live with a different generation.
+    GenerationMismatch(Entity),
+
+    /// The position of the `Entity` is outside of the range of the spawner, see `EntitySpawner::with_range`.
+    OutOfRange(Entity),
+}
+
+impl std::error::Error for SpawnError {}
+
+impl std::fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Occupied(entity) => write!(f, "Entity slot already occupied: {entity:?}"),
+            Self::InvalidGeneration(entity) => write!(f, "Invalid entity generation: {entity:?}"),
+            Self::GenerationMismatch(entity) => {
+                write!(
+                    f,
+                    "Entity slot is live with a different generation: {entity:?}"
+                )
+            }
+            Self::OutOfRange(entity) => write!(f, "Entity position out of range: {entity:?}"),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::resources::{FrameUpdateFn, HandleMut, HandleRef, Resource};
+
+/// A double-buffered queue of events of type `E`, stored as a global resource.
+///
+/// Events sent during a frame become readable in the next frame, and are dropped after that.
+/// The buffers of every global `Events` resource are rotated once per frame, however it was inserted.
+pub struct Events<E> {
+    previous: Vec<E>,
+    current: Vec<E>,
+}
+
+impl<E: Send + Sync + 'static> Resource for Events<E> {
+    #[inline]
+    fn frame_update() -> Option<FrameUpdateFn> {
+        Some(|resources| {
+            if let Some(mut events) = resources.get_resource_mut::<Self>() {
+                events.update();
+            }
+        })
+    }
+}
+
+impl<E> Events<E> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+        }
+    }
+
+    #[inline]
+    /// Queues an event, to be read in the next frame.
+    pub fn send(&mut self, event: E) {
+        self.current.push(event);
+    }
+
+    #[inline]
+    /// Returns the events sent during the last frame.
+    pub fn read(&self) -> std::slice::Iter<'_, E> {
+        self.previous.iter()
+    }
+
+    #[inline]
+    /// Returns the number of readable events.
+    pub const fn len(&self) -> usize {
+        self.previous.len()
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.previous.is_empty()
+    }
+
+    /// Makes the events of this frame readable, dropping the previously readable ones.
+    pub fn update(&mut self) {
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+    }
+}
+
+impl<E> Default for Events<E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reads the events of type `E` sent during the last frame.
+pub struct EventReader<'a, E: Send + Sync + 'static> {
+    pub(crate) handle: HandleRef<'a, Events<E>>,
+}
+
+impl<E: Send + Sync + 'static> EventReader<'_, E> {
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, E> {
+        self.handle.read()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.handle.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.handle.is_empty()
+    }
+}
+
+/// Sends events of type `E`, readable in the next frame.
+pub struct EventWriter<'a, E: Send + Sync + 'static> {
+    pub(crate) handle: HandleMut<'a, Events<E>>,
+}
+
+impl<E: Send + Sync + 'static> EventWriter<'_, E> {
+    #[inline]
+    pub fn send(&mut self, event: E) {
+        self.handle.send(event);
+    }
+
+    #[inline]
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = E>) {
+        self.handle.current.extend(events);
+    }
+}
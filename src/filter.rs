@@ -51,6 +51,11 @@ impl<C: Component> Filter for WithOut<C> {
     }
 }
 
+/// Matches tables that pass either `F1` or `F2`.
+///
+/// A `Query` still requires every non-optional extracted component,
+/// so tables matching the filter but missing an extracted component are skipped.
+/// Referencing an extracted component inside the filter is rejected by the `Query` validation.
 pub struct Or<F1: Filter, F2: Filter> {
     _p: (F1, F2),
 }
@@ -58,21 +63,17 @@ pub struct Or<F1: Filter, F2: Filter> {
 impl<F1: Filter, F2: Filter> Filter for Or<F1, F2> {
     #[inline]
     fn types() -> Vec<FilterType> {
-        let f1 = F1::types();
-        let f2 = F2::types();
-
-        let cap = f1.len() + f2.len();
-        let mut out = Vec::with_capacity(cap);
-
-        out.extend_from_slice(&f1);
-        out.extend_from_slice(&f2);
-
-        out
+        vec![FilterType::Or(vec![
+            FilterType::And(F1::types()),
+            FilterType::And(F2::types()),
+        ])]
     }
 
     #[cfg(feature = "runtime-checks")]
     fn validate() {
-        // TODO
+        // both sides are alternatives, so they can't contradict each other
+        F1::validate();
+        F2::validate();
     }
 
     #[inline]
@@ -81,7 +82,56 @@ impl<F1: Filter, F2: Filter> Filter for Or<F1, F2> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Default)]
+/// A filter built at runtime from `TypeId`s, e.g. by an editor or a scripting layer.
+///
+/// Checks tables the same way as `With`/`WithOut`, see `Query::new_dyn`.
+pub struct DynFilter {
+    types: Vec<FilterType>,
+}
+
+impl DynFilter {
+    #[inline]
+    pub const fn new() -> Self {
+        Self { types: Vec::new() }
+    }
+
+    #[inline]
+    /// Requires tables to contain the component with the given `TypeId`.
+    pub fn with(mut self, type_id: TypeId) -> Self {
+        self.types.push(FilterType::new_has_untyped(type_id));
+
+        self
+    }
+
+    #[inline]
+    /// Requires tables to NOT contain the component with the given `TypeId`.
+    pub fn without(mut self, type_id: TypeId) -> Self {
+        self.types.push(FilterType::new_not_untyped(type_id));
+
+        self
+    }
+
+    #[inline]
+    pub fn push(&mut self, filter: FilterType) {
+        self.types.push(filter);
+    }
+
+    #[inline]
+    pub fn types(&self) -> &[FilterType] {
+        &self.types
+    }
+
+    /// Returns `true`, if the table passes all filters.
+    pub fn check(&self, table: &Table) -> bool {
+        self.types.iter().all(|filter| filter.check(table))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A single filter term.
+///
+/// A list of terms (e.g. the filter of a `Query`) requires all of them to pass.
 pub enum FilterType {
     Has(
         TypeId,
@@ -93,6 +143,10 @@ pub enum FilterType {
         #[cfg(feature = "debug-utils")] &'static str,
         #[cfg(not(feature = "debug-utils"))] (),
     ),
+    /// Passes, if all of the terms pass.
+    And(Vec<Self>),
+    /// Passes, if any of the terms passes, e.g. created by `Or`.
+    Or(Vec<Self>),
 }
 
 impl FilterType {
@@ -119,29 +173,143 @@ impl FilterType {
     }
 
     #[inline]
+    /// Creates a `Has` filter from a runtime `TypeId`, without a type name.
+    pub const fn new_has_untyped(type_id: TypeId) -> Self {
+        Self::Has(
+            type_id,
+            #[cfg(feature = "debug-utils")]
+            "<dynamic>",
+            #[cfg(not(feature = "debug-utils"))]
+            (),
+        )
+    }
+
+    #[inline]
+    /// Creates a `Not` filter from a runtime `TypeId`, without a type name.
+    pub const fn new_not_untyped(type_id: TypeId) -> Self {
+        Self::Not(
+            type_id,
+            #[cfg(feature = "debug-utils")]
+            "<dynamic>",
+            #[cfg(not(feature = "debug-utils"))]
+            (),
+        )
+    }
+
+    #[inline]
+    /// Returns the `TypeId` of a `Has` or `Not` term.
+    ///
+    /// # Panics
+    /// - for `And` and `Or` groups, see `FilterType::literals`
     pub const fn raw_type(&self) -> TypeId {
         match self {
             Self::Has(type_id, _) | Self::Not(type_id, _) => *type_id,
+            Self::And(_) | Self::Or(_) => panic!("Filter groups don't have a single type"),
         }
     }
 
-    #[inline]
+    /// Returns `true`, if the table passes this term.
+    pub fn check(&self, table: &Table) -> bool {
+        match self {
+            Self::Has(type_id, _) => table.contains_one(*type_id),
+            Self::Not(type_id, _) => !table.contains_one(*type_id),
+            Self::And(terms) => terms.iter().all(|term| term.check(table)),
+            Self::Or(terms) => terms.iter().any(|term| term.check(table)),
+        }
+    }
+
+    /// Returns all `Has` and `Not` terms, including the ones nested in groups.
+    pub fn literals(types: &[Self]) -> Vec<&Self> {
+        let mut out = Vec::new();
+        for term in types {
+            match term {
+                Self::Has(..) | Self::Not(..) => out.push(term),
+                Self::And(terms) | Self::Or(terms) => out.extend(Self::literals(terms)),
+            }
+        }
+        out
+    }
+
+    /// Returns the terms without the `Has` terms of the given types, that have to pass (not nested in an `Or`).
+    ///
+    /// Returns `None`, if no term was removed.
+    pub fn without_required(types: &[Self], required: &[TypeId]) -> Option<Vec<Self>> {
+        let mut removed = false;
+        let out = Self::remove_required(types, required, &mut removed);
+
+        removed.then_some(out)
+    }
+
+    fn remove_required(types: &[Self], required: &[TypeId], removed: &mut bool) -> Vec<Self> {
+        let mut out = Vec::with_capacity(types.len());
+        for term in types {
+            match term {
+                Self::Has(type_id, _) if required.contains(type_id) => *removed = true,
+                Self::And(terms) => {
+                    out.push(Self::And(Self::remove_required(terms, required, removed)))
+                }
+                _ => out.push(term.clone()),
+            }
+        }
+        out
+    }
+
+    /// Returns `true`, if no table can pass both lists of terms.
     pub fn prevents_overlapping(a: &[Self], b: &[Self]) -> bool {
-        for x in a {
-            for y in b {
-                match (x, y) {
-                    (Self::Has(t1, _), Self::Not(t2, _)) | (Self::Not(t1, _), Self::Has(t2, _)) => {
-                        if t1 == t2 {
-                            return true;
-                        }
-                    }
-                    (Self::Has(_, _), Self::Has(_, _)) | (Self::Not(_, _), Self::Not(_, _)) => {
-                        continue;
+        // every way to pass `a` has to contradict every way to pass `b`
+        let a = Self::disjunctive(a);
+        let b = Self::disjunctive(b);
+
+        a.iter().all(|x| {
+            b.iter().all(|y| {
+                x.iter()
+                    .any(|t1| y.iter().any(|t2| Self::contradicts(t1, t2)))
+            })
+        })
+    }
+
+    /// Expands the terms into alternatives, where each alternative only contains `Has` and `Not` terms, that all have to pass.
+    fn disjunctive(types: &[Self]) -> Vec<Vec<&Self>> {
+        let mut alternatives = vec![Vec::new()];
+        for term in types {
+            match term {
+                Self::Has(..) | Self::Not(..) => {
+                    for alternative in &mut alternatives {
+                        alternative.push(term);
                     }
                 }
+                Self::And(terms) => {
+                    let nested = Self::disjunctive(terms);
+                    alternatives = Self::combine(&alternatives, &nested);
+                }
+                Self::Or(terms) => {
+                    let nested = terms
+                        .iter()
+                        .flat_map(|term| Self::disjunctive(std::slice::from_ref(term)))
+                        .collect::<Vec<_>>();
+                    alternatives = Self::combine(&alternatives, &nested);
+                }
             }
         }
-        false
+        alternatives
+    }
+
+    fn combine<'a>(a: &[Vec<&'a Self>], b: &[Vec<&'a Self>]) -> Vec<Vec<&'a Self>> {
+        let mut out = Vec::with_capacity(a.len() * b.len());
+        for x in a {
+            for y in b {
+                out.push(x.iter().chain(y).copied().collect());
+            }
+        }
+        out
+    }
+
+    #[inline]
+    fn contradicts(a: &Self, b: &Self) -> bool {
+        match (a, b) {
+            (Self::Has(t1, _), Self::Not(t2, _)) | (Self::Not(t1, _), Self::Has(t2, _)) => t1 == t2,
+            _ => false,
+        }
     }
 
     #[cfg(feature = "debug-utils")]
@@ -149,29 +317,39 @@ impl FilterType {
     pub const fn name(&self) -> &'static str {
         match self {
             Self::Has(_, name) | Self::Not(_, name) => name,
+            Self::And(_) => "And",
+            Self::Or(_) => "Or",
         }
     }
 
     #[cfg(feature = "runtime-checks")]
+    /// Checks the `Has` and `Not` terms of the list (not nested in an `Or`) for contradictions.
     pub fn validate(types: &[Self]) -> Result<(), FilterError> {
-        for (i, f1) in types.iter().enumerate() {
-            for (j, f2) in types.iter().enumerate() {
-                if i != j {
-                    match (f1, f2) {
-                        (Self::Has(t1, _), Self::Not(t2, _))
-                        | (Self::Not(t1, _), Self::Has(t2, _)) => {
-                            if t1 == t2 {
-                                return Err(FilterError(*f1, *f2));
-                            }
-                        }
-                        _ => continue,
-                    }
+        let required = Self::required(types);
+        for (i, f1) in required.iter().enumerate() {
+            for f2 in &required[i + 1..] {
+                if Self::contradicts(f1, f2) {
+                    return Err(FilterError((*f1).clone(), (*f2).clone()));
                 }
             }
         }
 
         Ok(())
     }
+
+    #[cfg(feature = "runtime-checks")]
+    /// Returns the `Has` and `Not` terms, that have to pass (not nested in an `Or`).
+    pub fn required(types: &[Self]) -> Vec<&Self> {
+        let mut out = Vec::new();
+        for term in types {
+            match term {
+                Self::Has(..) | Self::Not(..) => out.push(term),
+                Self::And(terms) => out.extend(Self::required(terms)),
+                Self::Or(_) => continue,
+            }
+        }
+        out
+    }
 }
 
 #[cfg(feature = "runtime-checks")]
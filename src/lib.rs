@@ -5,6 +5,7 @@ mod cells;
 mod commands;
 mod components;
 mod entity;
+mod events;
 mod filter;
 mod macros;
 mod query;
@@ -17,18 +18,31 @@ mod thread_pool;
 mod trait_impl;
 mod world;
 
-pub use cells::AtomicRefCell;
-pub use commands::Commands;
+pub use cells::{AtomicRefCell, BorrowState};
+pub use commands::{CommandStats, Commands};
 pub use components::Component;
-pub use entity::Entity;
-pub use filter::{Or, With, WithOut};
-pub use query::Query;
+pub use entity::{Entity, SpawnError, Spawner};
+pub use events::{EventReader, EventWriter, Events};
+pub use filter::{DynFilter, FilterType, Or, With, WithOut};
+pub use query::{PrevAndCurr, Query, QueryLens, QueryState, Single};
 pub use resources::{
-    GlobalRes, GlobalResMut, GlobalUnsendRef, NoSend, Res, ResMut, Resource, UnsendMut, UnsendRef,
+    FrameUpdateFn, GlobalRes, GlobalResMut, GlobalUnsendRef, NoSend, Res, ResInit, ResMut,
+    Resource, ResourceSet, ResourceStorageModifier, SceneRes, SceneResMut, ScopedRes, UnsendMut,
+    UnsendRef,
 };
-pub use scene::Scene;
-pub use schedule::{PostUpdate, PreUpdate, Schedule, ScheduleBuilder, Setup, Shutdown, Update};
-pub use world::World;
+pub use scene::{EntityMut, Scene};
+pub use schedule::{
+    IntoSystemSet, PostUpdate, PreUpdate, RunCondition, Schedule, ScheduleBuilder, ScheduleError,
+    Setup, Shutdown, StageStats, StuckFn, StuckSystems, SystemLabel, SystemSet, Update,
+    global_resource_changed, global_resource_equals, global_resource_exists, resource_changed,
+    resource_equals, resource_exists,
+};
+pub use system::{
+    Fallible, InitFn, Local, LocalState, NestedLocalState, ParamType, SystemOutput, SystemParam,
+    nested_local, nested_local_field, retrieved_field,
+};
+pub use table::TableId;
+pub use world::{EntityWorldMut, FromWorld, SendWorld, World};
 
 #[cfg(feature = "derive")]
 pub use eonix_derive::*;
@@ -41,7 +41,7 @@ macro_rules! component_set_impl {
                 where
                     Self: Sized,
                 {
-                    debug_assert_eq!(table.rows.len(), 2);
+                    debug_assert_eq!(table.rows.len(), Self::row_count());
 
                     let ($($ty,)+) = self;
 
@@ -53,7 +53,7 @@ macro_rules! component_set_impl {
                 }
 
                 fn update_rows(self, table: &mut Table, position: usize) {
-                    debug_assert!(table.rows.len() >= 2);
+                    debug_assert!(table.rows.len() >= Self::row_count());
 
                     let ($($ty,)+) = self;
 
@@ -64,7 +64,7 @@ macro_rules! component_set_impl {
                 }
 
                 fn push_or_update(self, table: &mut Table, position: usize) {
-                    debug_assert_eq!(table.rows.len(), 2);
+                    debug_assert!(table.rows.len() >= Self::row_count());
 
                     let ($($ty,)+) = self;
 
@@ -73,6 +73,17 @@ macro_rules! component_set_impl {
                         .push_or_update::<$ty>(position, $ty);
                     )+
                 }
+
+                fn push_missing(self, table: &mut Table, position: usize) {
+                    debug_assert!(table.rows.len() >= Self::row_count());
+
+                    let ($($ty,)+) = self;
+
+                    $(
+                        unwrap!(table.rows.iter_mut().find(|x| x.tid() == TypeId::of::<$ty>()))
+                        .push_missing::<$ty>(position, $ty);
+                    )+
+                }
             }
         };
     };
@@ -189,6 +200,26 @@ macro_rules! row_access_impl {
                         )+
                     )
                 }
+
+                #[inline]
+                fn row<R: Component>(&self) -> Option<&[R]> {
+                    let ($($ty),+) = self;
+
+                    None
+                    $(
+                        .or_else(|| $ty.row())
+                    )+
+                }
+
+                #[inline]
+                fn row_mut<R: Component>(&mut self) -> Option<&mut [R]> {
+                    let ($($ty),+) = self;
+
+                    None
+                    $(
+                        .or_else(|| $ty.row_mut())
+                    )+
+                }
             }
         };
     };
@@ -221,6 +252,12 @@ macro_rules! extract_impl {
                     vec
                 }
 
+                fn required_types() -> Vec<TypeId> {
+                    [$($ty::raw_unit_type()),+]
+                        .into_iter()
+                        .filter_map(|(type_id, required)| required.then_some(type_id))
+                        .collect()
+                }
 
                 #[cfg(feature = "runtime-checks")]
                 fn validate() {
@@ -257,16 +294,23 @@ macro_rules! extract_impl {
 
 macro_rules! system_impl {
     ($($comp:ident),+) => {
+        system_impl!(@impl [] ($($comp,)+), (), $($comp),+);
+
+        // systems returning a result, errors are logged
+        system_impl!(@impl [EE: std::fmt::Debug + 'static,] (Fallible<EE>, ($($comp,)+)), Result<(), EE>, $($comp),+);
+    };
+
+    (@impl [$($gen:tt)*] $marker:ty, $out:ty, $($comp:ident),+) => {
         #[allow(non_snake_case)]
         #[allow(clippy::too_many_arguments)]
         const _: () = {
-            impl<FF: Fn($($comp,)+), $($comp:SystemParam,)+> IntoSystem<($($comp,)+)> for FF
+            impl<$($gen)* FF: Fn($($comp,)+) -> $out, $($comp:SystemParam,)+> IntoSystem<$marker> for FF
             where
                 for<'a, 'b> &'a FF:
-                    Fn($($comp,)+) + Fn($(<$comp as SystemParam>::Item<'b>,)+),
+                    Fn($($comp,)+) -> $out + Fn($(<$comp as SystemParam>::Item<'b>,)+) -> $out,
                     FF: Send + Sync
             {
-                type System = FunctionSystem<($($comp,)+), Self>;
+                type System = FunctionSystem<$marker, Self>;
 
                 fn into_system(self) -> Self::System {
                     #[cfg(feature = "runtime-checks")]
@@ -279,14 +323,15 @@ macro_rules! system_impl {
                     FunctionSystem {
                         f: self,
                         marker: Default::default(),
+                        locals: Box::new([$($comp::init_local(),)+]),
                     }
                 }
             }
 
-            impl<FF, $($comp: SystemParam,)+> System for FunctionSystem<($($comp,)+), FF>
+            impl<$($gen)* FF, $($comp: SystemParam,)+> System for FunctionSystem<$marker, FF>
             where
                 for<'a, 'b> &'a FF:
-                    Fn($($comp,)+) + Fn($(<$comp as SystemParam>::Item<'b>,)+),
+                    Fn($($comp,)+) -> $out + Fn($(<$comp as SystemParam>::Item<'b>,)+) -> $out,
                     FF: Send + Sync
             {
 
@@ -342,43 +387,63 @@ macro_rules! system_impl {
                     std::any::type_name::<FF>()
                 }
 
+                #[inline]
+                fn get_inits(&self) -> Vec<InitFn> {
+                    [$($comp::init(),)+].into_iter().flatten().collect()
+                }
+
                 fn run(&self, world: WorldCellSend) -> Result<(), ()> {
                     debug_assert!(!self.local());
-                    fn call_inner<$($comp,)+>(f: impl Fn($($comp,)+), $($comp: $comp,)+) {
+                    fn call_inner<Out, $($comp,)+>(f: impl Fn($($comp,)+) -> Out, $($comp: $comp,)+) -> Out {
                         f($($comp,)+)
                     }
 
                     let borrow = *world.borrow();
+                    let mut locals = self.locals.iter();
 
                     $(
                         let world = borrow.send_world();
-                        let $comp = $comp::retrieve(world).ok_or(())?;
+                        let local = unwrap!(locals.next()).as_ref();
+                        let $comp = retrieved::<$comp, _>(
+                            $comp::retrieve_with_local(world, local),
+                            std::any::type_name::<FF>(),
+                        )?;
                     )+
 
 
-                    call_inner(&self.f, $($comp,)+);
+                    let out = call_inner(&self.f, $($comp,)+);
+                    SystemOutput::handle(out, std::any::type_name::<FF>());
 
                     Ok(())
                 }
 
 
                 fn run_on_main(&self, world: WorldCellComplete) -> Result<(), ()> {
-                    fn call_inner<$($comp,)+>(f: impl Fn($($comp,)+), $($comp: $comp,)+) {
+                    fn call_inner<Out, $($comp,)+>(f: impl Fn($($comp,)+) -> Out, $($comp: $comp,)+) -> Out {
                         f($($comp,)+)
                     }
 
                     let world = *world.borrow();
+                    let mut locals = self.locals.iter();
 
                     $(
+                        let local = unwrap!(locals.next()).as_ref();
                         let $comp = if $comp::local() {
-                            $comp::retrieve_local(world).ok_or(())?
+                            retrieved::<$comp, _>(
+                                $comp::retrieve_local_with_local(world, local),
+                                std::any::type_name::<FF>(),
+                            )?
                         } else {
                             let send_world = world.send_world();
-                            $comp::retrieve(send_world).ok_or(())?
+                            retrieved::<$comp, _>(
+                                $comp::retrieve_with_local(send_world, local),
+                                std::any::type_name::<FF>(),
+                            )?
                         };
                     )+
 
-                    call_inner(&self.f, $($comp,)+);
+                    let out = call_inner(&self.f, $($comp,)+);
+                    SystemOutput::handle(out, std::any::type_name::<FF>());
 
                     Ok(())
                 }
@@ -460,6 +525,26 @@ macro_rules! into_system_set_impl {
     };
 }
 
+macro_rules! resource_set_impl {
+    ($($res:ident),+) => {
+        impl<$($res: Resource,)+> ResourceSet for ($($res,)+) {
+            type Handles<'a> = ($(GlobalResMut<'a, $res>,)+);
+
+            fn get_mut(resources: &Resources<dyn Resource>) -> Option<Self::Handles<'_>> {
+                // the same resource can't be borrowed twice
+                let ids = [$(TypeId::of::<$res>(),)+];
+                for (i, id) in ids.iter().enumerate() {
+                    if ids[i + 1..].contains(id) {
+                        return None;
+                    }
+                }
+
+                Some(($(resources.try_get_resource_mut::<$res>()?.into(),)+))
+            }
+        }
+    };
+}
+
 macro_rules! catch_system_failure {
     ($expr: expr) => {
         if cfg!(feature = "runtime-checks") {
@@ -511,6 +596,7 @@ pub(crate) use component_set_impl;
 pub(crate) use extract_impl;
 pub(crate) use filter_impl;
 pub(crate) use into_system_set_impl;
+pub(crate) use resource_set_impl;
 pub(crate) use row_access_impl;
 pub(crate) use system_impl;
 pub(crate) use table_ident_impl;
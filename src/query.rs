@@ -1,10 +1,10 @@
-use std::{any::TypeId, marker::PhantomData};
+use std::{any::TypeId, marker::PhantomData, sync::Mutex};
 
 use crate::{
-    Scene,
-    components::EntityComponents,
-    entity::{Entity, Generation},
-    filter::Filter,
+    Component, Scene,
+    components::{EntityComponents, EntitySlots},
+    entity::Entity,
+    filter::{DynFilter, Filter, FilterType},
     macros::unwrap,
     system::ParamType,
     table::{Table, TableId},
@@ -12,7 +12,9 @@ use crate::{
 
 pub struct Query<'a, E: Extract, F: Filter = ()> {
     pub tables: Vec<E::Extracted<'a>>,
-    entities: &'a [(Generation, TableId)],
+    entities: &'a EntitySlots,
+    // all tables of the scene, to extract again (see `transmute_lens`)
+    source: &'a [Table],
     _f: PhantomData<F>,
 }
 
@@ -24,33 +26,66 @@ impl<'a, E: Extract, F: Filter> Query<'a, E, F> {
         Self::new_internal(entitie_components)
     }
 
+    #[inline]
     pub(crate) fn new_internal(entitie_components: &'a EntityComponents) -> Option<Self> {
         #[cfg(feature = "runtime-checks")]
         Self::validate();
 
-        let extracted_tables = Self::extract_tables(&entitie_components.tables)?;
+        Self::new_pruned(entitie_components, None)
+    }
+
+    /// Creates a query, checking the tables against the filter returned by `pruned_filter` (if any), instead of `F`.
+    ///
+    /// Doesn't validate the query, that already happened when the filter was pruned.
+    pub(crate) fn new_pruned(
+        entitie_components: &'a EntityComponents,
+        pruned: Option<&[FilterType]>,
+    ) -> Option<Self> {
+        let tables = &entitie_components.tables;
+        let extracted_tables = match pruned {
+            Some(filter) => {
+                Self::extract_tables(tables, |table| filter.iter().all(|term| term.check(table)))?
+            }
+            None => Self::extract_tables(tables, F::check)?,
+        };
 
         debug_assert!(!extracted_tables.is_empty());
 
         Some(Self {
             tables: extracted_tables,
             entities: &entitie_components.entities,
+            source: &entitie_components.tables,
             _f: PhantomData,
         })
     }
 
+    /// Returns the filter without the `With` terms, that are already required by the Extract, if it has any.
+    ///
+    /// Allocates, so it is only worked out once, e.g. per system or `QueryState`.
+    /// Validates the query, so redundant terms are only reported once.
+    pub(crate) fn pruned_filter() -> Option<Vec<FilterType>> {
+        #[cfg(feature = "runtime-checks")]
+        Self::validate();
+
+        FilterType::without_required(&F::types(), &E::required_types())
+    }
+
     #[inline]
-    fn extract_tables(tables: &'a [Table]) -> Option<Vec<E::Extracted<'a>>> {
+    fn extract_tables(
+        tables: &'a [Table],
+        check: impl Fn(&Table) -> bool,
+    ) -> Option<Vec<E::Extracted<'a>>> {
         if tables.is_empty() {
             return None;
         }
 
         let mut out = Vec::with_capacity(tables.len());
         for table in tables {
-            if table.is_empty() || !F::check(table) {
+            if table.is_empty() || !check(table) {
                 continue;
             }
 
+            // tables passing the filter still need all extracted (non-optional) components
             if let Ok(access) = E::extract(table) {
                 out.push(access);
             }
@@ -65,21 +100,54 @@ impl<'a, E: Extract, F: Filter> Query<'a, E, F> {
 
     #[cfg(feature = "runtime-checks")]
     fn validate() {
-        E::validate();
         F::validate();
 
+        Self::validate_extract(&F::types());
+    }
+
+    #[cfg(feature = "runtime-checks")]
+    fn validate_extract(f_types: &[FilterType]) {
+        E::validate();
+
         let e_types = E::types();
-        let f_types = F::types();
+        let required = E::required_types();
+        let f_required = FilterType::required(f_types);
 
         for e_t in e_types.iter() {
-            for f_t in f_types.iter() {
-                if e_t.raw_type() == f_t.raw_type() {
-                    panic!(
-                        "Extract and Filter conflict: Extract: [{}]  <-> [{}] :Filter",
-                        e_t.name(),
-                        f_t.name()
-                    );
+            for f_t in FilterType::literals(f_types) {
+                if e_t.raw_type() != f_t.raw_type() {
+                    continue;
+                }
+
+                // a term already requires the table to have the component, the filter is skipped
+                if matches!(f_t, FilterType::Has(..))
+                    && required.contains(&f_t.raw_type())
+                    && f_required.iter().any(|term| std::ptr::eq(*term, f_t))
+                {
+                    #[cfg(feature = "log")]
+                    {
+                        log::warn!(
+                            "Redundant Filter: [{}] is already a term of the Extract",
+                            f_t.name()
+                        )
+                    }
+
+                    #[cfg(not(feature = "log"))]
+                    {
+                        println!(
+                            "[WARN] Redundant Filter: [{}] is already a term of the Extract",
+                            f_t.name()
+                        )
+                    }
+
+                    continue;
                 }
+
+                panic!(
+                    "Extract and Filter conflict: Extract: [{}]  <-> [{}] :Filter",
+                    e_t.name(),
+                    f_t.name()
+                );
             }
         }
     }
@@ -93,7 +161,7 @@ impl<'a, E: Extract, F: Filter> Query<'a, E, F> {
         &mut self,
         entity: &Entity,
     ) -> Option<<E::Extracted<'a> as GetComponentAccess>::Item<'_>> {
-        let (generation, table_id) = self.entities.get(entity.id())?;
+        let (generation, table_id) = self.entities.get(entity)?;
 
         if *generation != entity.generation() {
             return None;
@@ -111,15 +179,477 @@ impl<'a, E: Extract, F: Filter> Query<'a, E, F> {
         table.get_entity(entity)
     }
 
+    /// Returns the ids of all tables matched by this query.
+    pub fn table_ids(&self) -> impl Iterator<Item = TableId> + '_ {
+        self.tables.iter().map(GetComponentAccess::table_id)
+    }
+
+    /// Iterates only over the table with the given `TableId`.
+    ///
+    /// If the table is not part of this query, the iterator is empty.
+    pub fn iter_table(
+        &mut self,
+        id: TableId,
+    ) -> impl Iterator<Item = <E::Extracted<'a> as GetComponentAccess>::Item<'_>> {
+        self.tables
+            .iter_mut()
+            .find(|table| table.table_id() == id)
+            .map(GetComponentAccess::iter)
+            .into_iter()
+            .flatten()
+    }
+
+    /// Returns the only item of this query.
+    ///
+    /// Returns `None` if more than one entity matches.
+    pub fn single(&mut self) -> Option<<E::Extracted<'a> as GetComponentAccess>::Item<'_>> {
+        if self
+            .tables
+            .iter()
+            .map(GetComponentAccess::len)
+            .sum::<usize>()
+            != 1
+        {
+            return None;
+        }
+
+        self.iter().next()
+    }
+
+    /// Iterates over the matched tables, yielding each `TableId` with an iterator over that table's items.
+    ///
+    /// Exposes the archetype grouping that `iter` flattens away.
+    pub fn iter_by_table(
+        &mut self,
+    ) -> impl Iterator<Item = (TableId, <E::Extracted<'a> as GetComponentAccess>::Iter<'_>)> {
+        self.tables
+            .iter_mut()
+            .map(|table| (table.table_id(), table.iter()))
+    }
+
+    /// Counts the items for which `f` returns `true`.
+    pub fn count_matching<'b>(
+        &'b mut self,
+        f: impl FnMut(<QueryIter<'a, 'b, E> as Iterator>::Item) -> bool,
+    ) -> usize {
+        self.iter().map(f).filter(|matched| *matched).count()
+    }
+
+    /// Returns `true` if `f` returns `true` for any item.
+    ///
+    /// Stops at the first match.
+    pub fn any<'b>(
+        &'b mut self,
+        f: impl FnMut(<QueryIter<'a, 'b, E> as Iterator>::Item) -> bool,
+    ) -> bool {
+        self.iter().any(f)
+    }
+
+    /// Returns `true` if `f` returns `true` for all items.
+    ///
+    /// Stops at the first mismatch.
+    pub fn all<'b>(
+        &'b mut self,
+        f: impl FnMut(<QueryIter<'a, 'b, E> as Iterator>::Item) -> bool,
+    ) -> bool {
+        self.iter().all(f)
+    }
+
+    /// Returns the whole row of `C` for each matched table.
+    ///
+    /// Tables without the row (e.g. for optional terms) are skipped.
+    ///
+    /// # Panics
+    /// - if `C` is not a term of this query
+    pub fn table_slices<C: Component>(&self) -> impl Iterator<Item = &[C]> + '_ {
+        assert!(
+            E::types()
+                .iter()
+                .any(|t| !t.is_world() && t.raw_type() == TypeId::of::<C>()),
+            "[{}] is not a term of this query",
+            std::any::type_name::<C>()
+        );
+
+        self.tables.iter().filter_map(GetComponentAccess::row)
+    }
+
+    /// Returns the whole mutable row of `C` for each matched table.
+    ///
+    /// Tables without the row (e.g. for optional terms) are skipped.
+    ///
+    /// # Panics
+    /// - if `C` is not a mutable term of this query
+    pub fn table_slices_mut<C: Component>(&mut self) -> impl Iterator<Item = &mut [C]> + '_ {
+        assert!(
+            E::types()
+                .iter()
+                .any(|t| matches!(t, ParamType::Mut(type_id, _) if *type_id == TypeId::of::<C>())),
+            "[{}] is not a mutable term of this query",
+            std::any::type_name::<C>()
+        );
+
+        self.tables
+            .iter_mut()
+            .filter_map(GetComponentAccess::row_mut)
+    }
+
+    /// Returns the whole row of `A` together with the whole mutable row of `B`, for each matched table.
+    ///
+    /// Tables without one of the rows (e.g. for optional terms) are skipped.
+    ///
+    /// # Panics
+    /// - if `A` and `B` are the same type
+    /// - if `A` is not a term, or `B` is not a mutable term of this query
+    pub fn table_columns<A: Component, B: Component>(
+        &mut self,
+    ) -> impl Iterator<Item = (&[A], &mut [B])> + '_ {
+        assert_ne!(
+            TypeId::of::<A>(),
+            TypeId::of::<B>(),
+            "[{}] can't be read and written at once",
+            std::any::type_name::<A>()
+        );
+
+        let types = E::types();
+        assert!(
+            types
+                .iter()
+                .any(|t| !t.is_world() && t.raw_type() == TypeId::of::<A>()),
+            "[{}] is not a term of this query",
+            std::any::type_name::<A>()
+        );
+        assert!(
+            types
+                .iter()
+                .any(|t| matches!(t, ParamType::Mut(type_id, _) if *type_id == TypeId::of::<B>())),
+            "[{}] is not a mutable term of this query",
+            std::any::type_name::<B>()
+        );
+
+        self.tables.iter_mut().filter_map(|table| {
+            let a: *const [A] = table.row::<A>()?;
+            let b = table.row_mut::<B>()?;
+
+            // # SAFETY
+            // `A` and `B` are different types, so they are stored in different rows, each behind its own guard.
+            // Both guards are held by the table access, which is borrowed for as long as the slices.
+            Some((unsafe { &*a }, b))
+        })
+    }
+
     pub fn iter(&mut self) -> QueryIter<'a, '_, E> {
+        let len = self.tables.iter().map(GetComponentAccess::len).sum();
+
         let mut iter = self.tables.iter_mut();
         let current = unwrap!(iter.next()).iter();
 
         QueryIter::<'_, '_, E> {
             tables: iter,
             current_table: current,
+            position: 0,
+            len,
         }
     }
+
+    /// Narrows this query to a subset of its terms, e.g. `Query<(&A, &mut B)>` to `Query<&A>`, without searching the scene again.
+    ///
+    /// The lens matches the same tables (minus the ones missing a non-optional term of `E2`).
+    /// This query is restored, when the lens is dropped.
+    /// Returns `None` (leaving this query untouched), if the lens would match no table.
+    ///
+    /// # Panics
+    /// - if a term of `E2` is not a term of this query, or upgrades shared to mutable access
+    pub fn transmute_lens<E2: Extract>(&mut self) -> Option<QueryLens<'_, 'a, E, F, E2>> {
+        let types = E::types();
+        for term in E2::types() {
+            let covered = types.iter().any(|t| {
+                !t.is_world()
+                    && t.raw_type() == term.raw_type()
+                    && (matches!(t, ParamType::Mut(..)) || matches!(term, ParamType::Shared(..)))
+            });
+
+            assert!(
+                covered,
+                "[{}] is not covered by the terms of this query",
+                std::any::type_name::<E2>()
+            );
+        }
+
+        let table_ids = self.table_ids().collect::<Vec<_>>();
+
+        // release the borrows of this query, before extracting the subset
+        self.tables.clear();
+
+        let tables = self
+            .source
+            .iter()
+            .filter(|table| table_ids.contains(&table.id()))
+            .filter_map(|table| E2::extract(table).ok())
+            .collect::<Vec<_>>();
+
+        // a query always has at least one table
+        if tables.is_empty() {
+            self.restore_tables(&table_ids);
+            return None;
+        }
+
+        let lens = Query {
+            tables,
+            entities: self.entities,
+            source: self.source,
+            _f: PhantomData,
+        };
+
+        Some(QueryLens {
+            parent: self,
+            lens: Some(lens),
+            table_ids,
+        })
+    }
+
+    // extracts the given tables again, see `transmute_lens`
+    fn restore_tables(&mut self, table_ids: &[TableId]) {
+        self.tables = self
+            .source
+            .iter()
+            .filter(|table| table_ids.contains(&table.id()))
+            .filter_map(|table| E::extract(table).ok())
+            .collect();
+    }
+
+    /// Iterates over all items, together with their `Entity`.
+    pub fn iter_with_entities(
+        &mut self,
+    ) -> impl Iterator<Item = (Entity, <E::Extracted<'a> as GetComponentAccess>::Item<'_>)> {
+        self.tables
+            .iter_mut()
+            .flat_map(GetComponentAccess::iter_with_entities)
+    }
+
+    /// Collects the `Entity` of every item.
+    ///
+    /// Allows acting on the entities after the query borrow ended, e.g. deleting them with `Commands::delete_entity`.
+    pub fn collect_entities(&mut self) -> Vec<Entity> {
+        self.iter_with_entities()
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    /// Iterates over all items, except the ones of the given entities.
+    ///
+    /// Checks every item against all excluded entities, so this is meant for a few of them (e.g. the one being processed).
+    pub fn iter_excluding<'e>(
+        &mut self,
+        exclude: &'e [Entity],
+    ) -> impl Iterator<Item = <E::Extracted<'a> as GetComponentAccess>::Item<'_>> {
+        self.iter_with_entities()
+            .filter(move |(entity, _)| !exclude.contains(entity))
+            .map(|(_, item)| item)
+    }
+
+    /// Iterates over all items, together with their position in the iteration (0, 1, 2, ...).
+    ///
+    /// The order is the same as for `iter`, so the position can be used to index into an external buffer.
+    pub fn iter_with_index(&mut self) -> IndexedQueryIter<'a, '_, E> {
+        IndexedQueryIter { iter: self.iter() }
+    }
+}
+
+impl<'a, E: Extract> Query<'a, E> {
+    #[inline]
+    /// Creates a query over all tables passing a filter built at runtime.
+    ///
+    /// The filter is only applied here, so the query has no compile-time filter.
+    pub fn new_dyn(scene: &'a Scene, filter: &DynFilter) -> Option<Self> {
+        Self::new_internal_dyn(&scene.entities, filter)
+    }
+
+    fn new_internal_dyn(
+        entitie_components: &'a EntityComponents,
+        filter: &DynFilter,
+    ) -> Option<Self> {
+        #[cfg(feature = "runtime-checks")]
+        {
+            if let Err(err) = FilterType::validate(filter.types()) {
+                panic!("{err}");
+            }
+
+            Self::validate_extract(filter.types());
+        }
+
+        let extracted_tables =
+            Self::extract_tables(&entitie_components.tables, |table| filter.check(table))?;
+
+        Some(Self {
+            tables: extracted_tables,
+            entities: &entitie_components.entities,
+            source: &entitie_components.tables,
+            _f: PhantomData,
+        })
+    }
+}
+
+/// The matching tables of a query, cached over multiple frames.
+///
+/// Validation runs once when created, and only tables added since the last `get` are checked against the query.
+pub struct QueryState<E: Extract, F: Filter = ()> {
+    cache: Mutex<QueryCache>,
+    // the filter without redundant terms, see `Query::pruned_filter`
+    pruned: Option<Vec<FilterType>>,
+    _p: PhantomData<fn() -> (E, F)>,
+}
+
+#[derive(Default)]
+struct QueryCache {
+    // `None` before the first `get`
+    table_generation: Option<u64>,
+    // every table of the scene (by position), and if it matches
+    tables: Vec<(TableId, bool)>,
+}
+
+impl<E: Extract, F: Filter> QueryState<E, F> {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(QueryCache::default()),
+            pruned: Query::<E, F>::pruned_filter(),
+            _p: PhantomData,
+        }
+    }
+
+    /// Creates a query over the matching tables of the scene.
+    ///
+    /// Tables are only checked again, after tables were added to or removed from the scene.
+    pub fn get<'a>(&self, scene: &'a Scene) -> Option<Query<'a, E, F>> {
+        let entitie_components = &scene.entities;
+        let tables = &entitie_components.tables;
+
+        let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+
+        let generation = entitie_components.table_generation();
+        if cache.table_generation != Some(generation) {
+            // keep the result of already checked tables
+            let checked = tables
+                .iter()
+                .map(|table| {
+                    let id = table.id();
+                    let matches = cache
+                        .tables
+                        .iter()
+                        .find(|(t, _)| *t == id)
+                        .map_or_else(|| self.matches(table), |(_, matches)| *matches);
+
+                    (id, matches)
+                })
+                .collect();
+
+            cache.tables = checked;
+            cache.table_generation = Some(generation);
+        }
+
+        let extracted_tables = tables
+            .iter()
+            .zip(&cache.tables)
+            .filter(|(table, (_, matches))| *matches && !table.is_empty())
+            .filter_map(|(table, _)| E::extract(table).ok())
+            .collect::<Vec<_>>();
+        drop(cache);
+
+        if extracted_tables.is_empty() {
+            return None;
+        }
+
+        Some(Query {
+            tables: extracted_tables,
+            entities: &entitie_components.entities,
+            source: tables,
+            _f: PhantomData,
+        })
+    }
+
+    #[inline]
+    fn matches(&self, table: &Table) -> bool {
+        let passes = self.pruned.as_ref().map_or_else(
+            || F::check(table),
+            |filter| filter.iter().all(|term| term.check(table)),
+        );
+
+        passes && E::extract(table).is_ok()
+    }
+}
+
+impl<E: Extract, F: Filter> Default for QueryState<E, F> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A query narrowed to a subset of the terms of another query, see `Query::transmute_lens`.
+pub struct QueryLens<'q, 'a, E: Extract, F: Filter, E2: Extract> {
+    parent: &'q mut Query<'a, E, F>,
+    lens: Option<Query<'a, E2>>,
+    // tables matched by the parent query
+    table_ids: Vec<TableId>,
+}
+
+impl<'a, E: Extract, F: Filter, E2: Extract> std::ops::Deref for QueryLens<'_, 'a, E, F, E2> {
+    type Target = Query<'a, E2>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unwrap!(self.lens.as_ref())
+    }
+}
+
+impl<E: Extract, F: Filter, E2: Extract> std::ops::DerefMut for QueryLens<'_, '_, E, F, E2> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unwrap!(self.lens.as_mut())
+    }
+}
+
+impl<E: Extract, F: Filter, E2: Extract> Drop for QueryLens<'_, '_, E, F, E2> {
+    fn drop(&mut self) {
+        // release the borrows of the lens, before restoring the parent
+        self.lens = None;
+
+        self.parent.restore_tables(&self.table_ids);
+    }
+}
+
+/// A query term yielding the value of `C` at the last frame boundary, together with its current value.
+///
+/// Requires `World::track_previous::<C>()`, which stores a copy of every `C` once per frame.
+/// Components without such a copy (e.g. added or moved to another archetype this frame) yield their current value twice.
+pub struct PrevAndCurr<C: Component + Clone>(PhantomData<C>);
+
+/// A system parameter for a query matching exactly one entity, e.g. the camera.
+///
+/// If no entity or more than one entity matches, the system is skipped instead of panicking.
+/// More than one match is reported under `debug-utils`.
+pub struct Single<'a, E: Extract, F: Filter = ()> {
+    pub(crate) query: Query<'a, E, F>,
+}
+
+impl<'a, E: Extract, F: Filter> Single<'a, E, F> {
+    /// Wraps the query, if exactly one entity matches.
+    #[inline]
+    pub fn new(mut query: Query<'a, E, F>) -> Option<Self> {
+        query.single()?;
+        Some(Self { query })
+    }
+
+    /// Returns the item of the matched entity.
+    #[inline]
+    pub fn get(&mut self) -> <E::Extracted<'a> as GetComponentAccess>::Item<'_> {
+        unwrap!(self.query.single())
+    }
+
+    /// Returns the matched entity.
+    #[inline]
+    pub fn entity(&mut self) -> Entity {
+        unwrap!(self.query.iter_with_entities().next()).0
+    }
 }
 
 pub struct TableAccess<'a, Rows: RowAccess> {
@@ -131,6 +661,19 @@ pub struct TableAccess<'a, Rows: RowAccess> {
 pub struct QueryIter<'a, 'b, E: Extract> {
     tables: std::slice::IterMut<'b, <E as Extract>::Extracted<'a>>,
     current_table: <E::Extracted<'a> as GetComponentAccess>::Iter<'b>,
+
+    // number of items returned so far
+    position: usize,
+    // number of items over all tables
+    len: usize,
+}
+
+impl<E: Extract> QueryIter<'_, '_, E> {
+    #[inline]
+    /// Returns the number of items returned so far, which is also the position of the next item.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
 }
 
 impl<'a, 'b, E: Extract> Iterator for QueryIter<'a, 'b, E> {
@@ -143,7 +686,10 @@ impl<'a, 'b, E: Extract> Iterator for QueryIter<'a, 'b, E> {
 
             match next_item {
                 // return item
-                Some(item) => return Some(item),
+                Some(item) => {
+                    self.position += 1;
+                    return Some(item);
+                }
 
                 // table is finished
                 None => {
@@ -163,8 +709,39 @@ impl<'a, 'b, E: Extract> Iterator for QueryIter<'a, 'b, E> {
             }
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<E: Extract> ExactSizeIterator for QueryIter<'_, '_, E> {}
+
+pub struct IndexedQueryIter<'a, 'b, E: Extract> {
+    iter: QueryIter<'a, 'b, E>,
 }
 
+impl<'a, 'b, E: Extract> Iterator for IndexedQueryIter<'a, 'b, E> {
+    type Item = (usize, <QueryIter<'a, 'b, E> as Iterator>::Item);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let position = self.iter.position();
+        let item = self.iter.next()?;
+
+        Some((position, item))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<E: Extract> ExactSizeIterator for IndexedQueryIter<'_, '_, E> {}
+
 pub enum NoneIter<I: Iterator> {
     Iter(I),
     None,
@@ -194,6 +771,13 @@ pub trait Extract {
 
     fn types() -> Vec<ParamType>;
 
+    #[inline]
+    /// Returns the components every extracted table has to contain, e.g. not the one of an `Option<&C>`.
+    fn required_types() -> Vec<TypeId> {
+        let (type_id, required) = Self::raw_unit_type();
+        if required { vec![type_id] } else { Vec::new() }
+    }
+
     #[cfg(feature = "runtime-checks")]
     fn validate();
 
@@ -205,6 +789,9 @@ pub trait Extract {
     }
 }
 
+/// An iterator yielding the `Entity` of every item.
+pub type EntityIter<'a, I> = std::iter::Zip<std::iter::Copied<std::slice::Iter<'a, Entity>>, I>;
+
 pub trait GetComponentAccess {
     type Item<'a>
     where
@@ -216,9 +803,19 @@ pub trait GetComponentAccess {
 
     fn table_id(&self) -> TableId;
 
+    /// Returns the number of entities in the table.
+    fn len(&self) -> usize;
+
     fn get_entity(&mut self, entity: &Entity) -> Option<Self::Item<'_>>;
 
     fn iter(&mut self) -> Self::Iter<'_>;
+
+    /// Iterates over all items, together with their `Entity`.
+    fn iter_with_entities(&mut self) -> EntityIter<'_, Self::Iter<'_>>;
+
+    fn row<C: Component>(&self) -> Option<&[C]>;
+
+    fn row_mut<C: Component>(&mut self) -> Option<&mut [C]>;
 }
 
 pub trait RowAccess {
@@ -233,6 +830,12 @@ pub trait RowAccess {
         Self: 'a;
 
     fn get_iter(&mut self) -> Self::Iter<'_>;
+
+    /// Returns the whole row of `C`, if it is part of this access.
+    fn row<C: Component>(&self) -> Option<&[C]>;
+
+    /// Returns the whole row of `C`, if it is mutably accessed.
+    fn row_mut<C: Component>(&mut self) -> Option<&mut [C]>;
 }
 
 #[cfg(feature = "runtime-checks")]
@@ -240,9 +843,9 @@ pub trait RowAccess {
 mod tests {
     use std::panic::{AssertUnwindSafe, catch_unwind};
 
-    use crate::Scene;
+    use crate::{Scene, filter::FilterType, table::TableIdent};
 
-    use super::Query;
+    use super::{Query, QueryState};
 
     #[test]
     fn test_invalid_same_type() {
@@ -282,4 +885,81 @@ mod tests {
 
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_redundant_filter() {
+        use crate::{With, WithOut};
+
+        let mut scene = Scene::new();
+        for i in 0..3 {
+            let entity = scene.spawn_entity();
+            scene.add_component(&entity, i as u32);
+        }
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, (3u32, 3i32));
+
+        // only warned about, the filter is skipped
+        assert!(
+            Query::<&u32, With<u32>>::pruned_filter()
+                .unwrap()
+                .is_empty()
+        );
+        let mut query = Query::<&u32, With<u32>>::new(&scene).unwrap();
+        assert_eq!(query.iter().copied().sum::<u32>(), 6);
+
+        // the other terms are kept
+        let filter = Query::<&u32, (With<u32>, WithOut<i32>)>::pruned_filter().unwrap();
+        assert_eq!(filter, [FilterType::new_not::<i32>()]);
+        let mut query = Query::<&u32, (With<u32>, WithOut<i32>)>::new(&scene).unwrap();
+        assert_eq!(query.iter().copied().sum::<u32>(), 3);
+
+        assert!(Query::<&u32, WithOut<i32>>::pruned_filter().is_none());
+
+        // worked out once, when the state is created
+        let state = QueryState::<&u32, (With<u32>, WithOut<i32>)>::new();
+        assert_eq!(state.pruned, Some(vec![FilterType::new_not::<i32>()]));
+        assert_eq!(state.get(&scene).unwrap().iter().copied().sum::<u32>(), 3);
+    }
+
+    #[test]
+    fn test_query_state_reuses_classification() {
+        let mut scene = Scene::new();
+        let state = QueryState::<&u32>::new();
+
+        for i in 0..5 {
+            let entity = scene.spawn_entity();
+            scene.add_component(&entity, i as u32);
+        }
+        assert_eq!(state.get(&scene).unwrap().iter().count(), 5);
+
+        let generation = scene.entities.table_generation();
+        assert_eq!(
+            state.cache.lock().unwrap().table_generation,
+            Some(generation)
+        );
+
+        // no new table, no new generation
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, 5u32);
+        assert_eq!(scene.entities.table_generation(), generation);
+
+        // mark the table as not matching, to observe if it is checked again
+        state.cache.lock().unwrap().tables[0].1 = false;
+
+        // a new table changes the generation
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, (6u32, 6i32));
+        assert_ne!(scene.entities.table_generation(), generation);
+
+        // only the new table was checked, the old one kept its (stale) classification
+        let mut query = state.get(&scene).unwrap();
+        assert_eq!(query.table_count(), 1);
+        assert_eq!(query.iter().count(), 1);
+        drop(query);
+
+        assert_eq!(
+            state.cache.lock().unwrap().tables,
+            [(u32::table_id(), false), (<(u32, i32)>::table_id(), true)]
+        );
+    }
 }
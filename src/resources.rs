@@ -2,21 +2,70 @@ use std::{
     any::{Any, TypeId},
     collections::{HashMap, hash_map::Entry},
     marker::PhantomData,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
 };
 
-use crate::cells::{AtomicRefCell, MutGuard, RefGuard};
+use crate::{
+    cells::{AtomicRefCell, MutGuard, RefGuard},
+    macros::unwrap,
+};
 
 /// A trait representing a type erased resource.
 pub type UntypedResource = dyn Any + Send + Sync;
 
-pub trait Resource: Any + Send + Sync + 'static {}
+/// Called once per frame on the global resources, see `Resource::frame_update`.
+pub type FrameUpdateFn = fn(&Resources<dyn Resource>);
+
+pub trait Resource: Any + Send + Sync + 'static {
+    #[inline]
+    /// Returns a function, that `World::clear_trackers` calls once per frame after this resource was inserted as a global resource.
+    ///
+    /// Used by `Events` to rotate its buffers, no matter how it was inserted.
+    fn frame_update() -> Option<FrameUpdateFn>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
 
 pub trait NoSend: Any {}
 
+/// A stored resource, together with its change tracking.
+struct ResourceCell {
+    value: AtomicRefCell<Box<dyn Any>>,
+
+    /// Bumped every time the resource is mutably dereferenced (or replaced).
+    version: AtomicU64,
+    /// `version` at the last frame boundary, see `Resources::clear_changed`.
+    frame_version: u64,
+}
+
+impl ResourceCell {
+    #[inline]
+    fn new(value: Box<dyn Any>) -> Self {
+        Self {
+            value: AtomicRefCell::new(value),
+            // counts as changed, until the next frame boundary
+            version: AtomicU64::new(1),
+            frame_version: 0,
+        }
+    }
+
+    #[inline]
+    fn replace(&mut self, value: Box<dyn Any>) {
+        self.value = AtomicRefCell::new(value);
+        *self.version.get_mut() += 1;
+    }
+}
+
 #[derive(Default)]
 pub struct Resources<T: ?Sized + Any> {
     #[allow(clippy::non_send_fields_in_send_ty)]
-    resources: HashMap<TypeId, AtomicRefCell<Box<dyn Any>>>,
+    resources: HashMap<TypeId, ResourceCell>,
 
     /// Marks if these resources can be send
     _p: PhantomData<T>,
@@ -36,23 +85,35 @@ impl<T: ?Sized + Any> Resources<T> {
     pub fn insert_resource<R: Any>(&mut self, res: R) {
         let type_id = TypeId::of::<R>();
         let boxed: Box<dyn Any> = Box::new(res);
-        let cell = AtomicRefCell::new(boxed);
 
         match self.resources.entry(type_id) {
-            Entry::Occupied(mut e) => {
-                _ = e.insert(cell);
-            }
-            Entry::Vacant(e) => {
-                _ = e.insert(cell);
-            }
+            Entry::Occupied(mut e) => e.get_mut().replace(boxed),
+            Entry::Vacant(e) => _ = e.insert(ResourceCell::new(boxed)),
         }
     }
 
-    pub fn get_resource_ref<R: Any>(&self) -> Option<HandleRef<R>> {
+    #[inline]
+    pub fn contains_resource<R: Any>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<R>())
+    }
+
+    /// Returns the `TypeId` of every stored resource, in no particular order.
+    pub fn type_ids(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.resources.keys().copied()
+    }
+
+    pub fn get_resource_ref<R: Any>(&self) -> Option<HandleRef<'_, R>> {
         let type_id = TypeId::of::<R>();
         let res = self.resources.get(&type_id)?;
 
-        let guard = res.borrow();
+        #[cfg(feature = "debug-utils")]
+        let guard = match res.value.try_borrow() {
+            Ok(guard) => guard,
+            Err(err) => panic!("{err} Resource: [{}]", std::any::type_name::<R>()),
+        };
+
+        #[cfg(not(feature = "debug-utils"))]
+        let guard = res.value.borrow();
 
         Some(HandleRef {
             _p: PhantomData,
@@ -60,18 +121,84 @@ impl<T: ?Sized + Any> Resources<T> {
         })
     }
 
-    pub fn get_resource_mut<R: Any>(&self) -> Option<HandleMut<R>> {
+    #[inline]
+    /// Returns a copy of a resource, releasing the borrow right away.
+    pub fn get_cloned<R: Clone + Any>(&self) -> Option<R> {
+        let handle = self.get_resource_ref::<R>()?;
+        Some(R::clone(&handle))
+    }
+
+    /// Returns a reference to a resource, without updating its borrow counter.
+    ///
+    /// # Safety
+    /// The resource must not be borrowed mutably, while the returned reference is alive.
+    pub unsafe fn get_resource_ref_unchecked<R: Any>(&self) -> Option<&R> {
+        let type_id = TypeId::of::<R>();
+        let res = self.resources.get(&type_id)?;
+
+        let boxed = unsafe { res.value.get_unchecked() };
+
+        Some(unwrap!(boxed.downcast_ref::<R>()))
+    }
+
+    pub fn get_resource_mut<R: Any>(&self) -> Option<HandleMut<'_, R>> {
+        let type_id = TypeId::of::<R>();
+        let res = self.resources.get(&type_id)?;
+
+        #[cfg(feature = "debug-utils")]
+        let guard = match res.value.try_borrow_mut() {
+            Ok(guard) => guard,
+            Err(err) => panic!("{err} Resource: [{}]", std::any::type_name::<R>()),
+        };
+
+        #[cfg(not(feature = "debug-utils"))]
+        let guard = res.value.borrow_mut();
+
+        Some(HandleMut {
+            _p: PhantomData,
+            guard,
+            version: &res.version,
+            frame_version: res.frame_version,
+        })
+    }
+
+    /// Returns an exclusive handle to a resource, or `None` if it is missing or already borrowed.
+    pub fn try_get_resource_mut<R: Any>(&self) -> Option<HandleMut<'_, R>> {
         let type_id = TypeId::of::<R>();
         let res = self.resources.get(&type_id)?;
 
-        let guard = res.borrow_mut();
+        let guard = res.value.try_borrow_mut().ok()?;
 
         Some(HandleMut {
             _p: PhantomData,
             guard,
+            version: &res.version,
+            frame_version: res.frame_version,
         })
     }
 
+    #[inline]
+    /// Returns the change version of a resource, bumped every time it is mutably dereferenced or replaced.
+    pub fn version<R: Any>(&self) -> Option<u64> {
+        let res = self.resources.get(&TypeId::of::<R>())?;
+        Some(res.version.load(Ordering::Relaxed))
+    }
+
+    #[inline]
+    /// Returns `true`, if the resource was changed since the last frame boundary, see `World::clear_trackers`.
+    pub fn is_changed<R: Any>(&self) -> bool {
+        self.resources
+            .get(&TypeId::of::<R>())
+            .is_some_and(|res| res.version.load(Ordering::Relaxed) != res.frame_version)
+    }
+
+    /// Marks all resources as unchanged, starting a new frame.
+    pub fn clear_changed(&mut self) {
+        for res in self.resources.values_mut() {
+            res.frame_version = *res.version.get_mut();
+        }
+    }
+
     pub fn insert_resource_untyped(
         &mut self,
         resource: Box<dyn Any>,
@@ -80,11 +207,9 @@ impl<T: ?Sized + Any> Resources<T> {
         let type_id = (modifier.0)();
 
         match self.resources.entry(type_id) {
-            Entry::Occupied(mut occupied_entry) => {
-                *occupied_entry.get_mut() = AtomicRefCell::new(resource)
-            }
+            Entry::Occupied(mut occupied_entry) => occupied_entry.get_mut().replace(resource),
 
-            Entry::Vacant(vacant_entry) => _ = vacant_entry.insert(AtomicRefCell::new(resource)),
+            Entry::Vacant(vacant_entry) => _ = vacant_entry.insert(ResourceCell::new(resource)),
         }
     }
 
@@ -108,17 +233,39 @@ impl<T: ?Sized + Any> std::fmt::Debug for Resources<T> {
 }
 
 #[derive(Debug)]
-/// A mini v-table to get the TypeId of a type erased resource.
+/// A mini v-table to get the TypeId (and frame update) of a type erased resource.
 ///
 /// Mainly used by commands.
-pub struct ResourceStorageModifier(fn() -> TypeId);
+pub struct ResourceStorageModifier(fn() -> TypeId, fn() -> Option<FrameUpdateFn>);
 
 impl ResourceStorageModifier {
     #[inline]
     /// Creates a new mini v-table.
     pub const fn new<R: Resource>() -> Self {
-        Self(TypeId::of::<R>)
+        Self(TypeId::of::<R>, R::frame_update)
     }
+
+    #[inline]
+    /// Returns the `TypeId` of the resource this v-table was created for.
+    pub fn resource_type(&self) -> TypeId {
+        (self.0)()
+    }
+
+    #[inline]
+    /// Returns the frame update of the resource this v-table was created for, see `Resource::frame_update`.
+    pub fn frame_update(&self) -> Option<FrameUpdateFn> {
+        (self.1)()
+    }
+}
+
+/// A tuple of distinct global resources, that can be borrowed mutably at once.
+pub trait ResourceSet {
+    type Handles<'a>;
+
+    /// Returns exclusive handles to all resources of the set.
+    ///
+    /// Returns `None`, if the set contains a resource twice, or any of them is missing or already borrowed.
+    fn get_mut(resources: &Resources<dyn Resource>) -> Option<Self::Handles<'_>>;
 }
 
 pub struct HandleRef<'a, R: 'static> {
@@ -138,6 +285,16 @@ impl<R: 'static> std::ops::Deref for HandleRef<'_, R> {
 pub struct HandleMut<'a, R> {
     _p: PhantomData<R>,
     guard: MutGuard<'a, Box<dyn Any>>,
+    version: &'a AtomicU64,
+    frame_version: u64,
+}
+
+impl<R> HandleMut<'_, R> {
+    #[inline]
+    /// Returns `true`, if the resource was changed since the last frame boundary, see `World::clear_trackers`.
+    pub fn is_changed(&self) -> bool {
+        self.version.load(Ordering::Relaxed) != self.frame_version
+    }
 }
 
 impl<R: 'static> std::ops::Deref for HandleMut<'_, R> {
@@ -152,13 +309,18 @@ impl<R: 'static> std::ops::Deref for HandleMut<'_, R> {
 impl<R: 'static> std::ops::DerefMut for HandleMut<'_, R> {
     #[inline]
     fn deref_mut(&mut self) -> &mut Self::Target {
+        // the resource is borrowed exclusively, so nobody else bumps the version
+        self.version
+            .store(self.version.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+
         unsafe { self.guard.downcast_mut::<R>().unwrap_unchecked() }
     }
 }
 
 macro_rules! impl_res {
     // Ref
-    ($ident: ident, $handle: ident, $bound: ident, -) => {
+    ($(#[$meta: meta])* $ident: ident, $handle: ident, $bound: ident, -) => {
+        $(#[$meta])*
         pub struct $ident<'a, R: $bound> {
             handle: $handle<'a, R>,
         }
@@ -181,7 +343,8 @@ macro_rules! impl_res {
     };
 
     // Mut
-    ($ident: ident, $handle: ident, $bound: ident, !) => {
+    ($(#[$meta: meta])* $ident: ident, $handle: ident, $bound: ident, !) => {
+        $(#[$meta])*
         pub struct $ident<'a, R: $bound> {
             pub handle: $handle<'a, R>,
         }
@@ -202,6 +365,14 @@ macro_rules! impl_res {
             }
         }
 
+        impl<R: $bound> $ident<'_, R> {
+            #[inline]
+            /// Returns `true`, if the resource was changed since the last frame boundary, see `World::clear_trackers`.
+            pub fn is_changed(&self) -> bool {
+                self.handle.is_changed()
+            }
+        }
+
         impl<'a, R: $bound> From<$handle<'a, R>> for $ident<'a, R> {
             #[inline]
             fn from(value: $handle<'a, R>) -> Self {
@@ -211,12 +382,127 @@ macro_rules! impl_res {
     };
 }
 
-impl_res!(Res, HandleRef, Resource, -);
-impl_res!(ResMut, HandleMut, Resource, !);
-impl_res!(UnsendRef, HandleRef, NoSend, -);
-impl_res!(UnsendMut, HandleMut, NoSend, !);
+impl_res!(
+    /// Shared access to a resource of the current scene.
+    Res, HandleRef, Resource, -
+);
+impl_res!(
+    /// Exclusive access to a resource of the current scene.
+    ResMut, HandleMut, Resource, !
+);
+impl_res!(
+    /// Shared access to a `NoSend` resource of the current scene.
+    UnsendRef, HandleRef, NoSend, -
+);
+impl_res!(
+    /// Exclusive access to a `NoSend` resource of the current scene.
+    UnsendMut, HandleMut, NoSend, !
+);
+
+impl_res!(
+    /// Shared access to a global resource, independent of the current scene.
+    GlobalRes, HandleRef, Resource, -
+);
+impl_res!(
+    /// Exclusive access to a global resource, independent of the current scene.
+    GlobalResMut, HandleMut, Resource, !
+);
+impl_res!(
+    /// Exclusive access to a global resource, created with `FromWorld` (e.g. `R::default()`) if it is missing.
+    ///
+    /// The resource is inserted at the start of `Schedule::run`, before any stage runs, as systems can't add resources while running.
+    /// If it is removed during a run, systems using this parameter are skipped until the next run.
+    ResInit, HandleMut, Resource, !
+);
+impl_res!(
+    /// Shared access to a global `NoSend` resource, independent of the current scene.
+    GlobalUnsendRef, HandleRef, NoSend, -
+);
+impl_res!(
+    /// Exclusive access to a global `NoSend` resource, independent of the current scene.
+    GlobalUnsendMut, HandleMut, NoSend, !
+);
+
+/// Shared access to a resource of the current scene.
+///
+/// Only an alias of `Res`, to make the distinction to `GlobalRes` explicit in a signature; it is not a separate type.
+/// Scene and global resources of the same type report the same parameter type,
+/// so `SceneRes<R>` conflicts with `GlobalResMut<R>` in the same system.
+pub type SceneRes<'a, R> = Res<'a, R>;
+
+/// Exclusive access to a resource of the current scene.
+///
+/// Only an alias of `ResMut`, to make the distinction to `GlobalResMut` explicit in a signature; it is not a separate type.
+/// Scene and global resources of the same type report the same parameter type,
+/// so `SceneResMut<R>` conflicts with `GlobalRes<R>` and `GlobalResMut<R>` in the same system.
+pub type SceneResMut<'a, R> = ResMut<'a, R>;
+
+/// Values borrowed for the duration of `World::with_scoped_resource`, stored type erased.
+///
+/// Shared with the scope that inserted a value, so the scope can remove it again,
+/// even if the `World` was moved somewhere else in the meantime.
+#[derive(Debug, Clone, Default)]
+pub struct ScopedResources {
+    values: Arc<RwLock<HashMap<TypeId, ScopedPtr>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScopedPtr(*const ());
+
+// SAFETY: Only created from a `&R` with `R: Sync`, and only turned back into a `&R`.
+unsafe impl Send for ScopedPtr {}
+unsafe impl Sync for ScopedPtr {}
+
+impl ScopedResources {
+    /// Stores a pointer to `value`, returning the pointer it replaced.
+    pub fn insert<R: Sync + 'static>(&self, value: &R) -> Option<ScopedPtr> {
+        let ptr = ScopedPtr(std::ptr::from_ref(value).cast());
+
+        self.values
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(TypeId::of::<R>(), ptr)
+    }
+
+    /// Restores the pointer replaced by `insert`, or removes the value.
+    pub fn restore(&self, type_id: TypeId, previous: Option<ScopedPtr>) {
+        let mut values = self.values.write().unwrap_or_else(|err| err.into_inner());
+
+        match previous {
+            Some(ptr) => values.insert(type_id, ptr),
+            None => values.remove(&type_id),
+        };
+    }
+
+    /// Returns the value of type `R` of the running scope.
+    ///
+    /// # Safety
+    /// Has to be called, and the returned reference dropped, while the scope that inserted the value is running.
+    pub unsafe fn get<R: Sync + 'static>(&self) -> Option<&R> {
+        let ptr = *self
+            .values
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(&TypeId::of::<R>())?;
+
+        // # SAFETY
+        // The pointer was created from a `&R`, which outlives the scope.
+        Some(unsafe { &*ptr.0.cast::<R>() })
+    }
+}
+
+/// Shared access to a value borrowed for the duration of `World::with_scoped_resource`.
+///
+/// Systems using this parameter are skipped outside of such a scope.
+pub struct ScopedRes<'a, R: Sync + 'static> {
+    pub(crate) value: &'a R,
+}
 
-impl_res!(GlobalRes, HandleRef, Resource, -);
-impl_res!(GlobalResMut, HandleMut, Resource, !);
-impl_res!(GlobalUnsendRef, HandleRef, NoSend, -);
-impl_res!(GlobalUnsendMut, HandleMut, NoSend, !);
+impl<R: Sync + 'static> std::ops::Deref for ScopedRes<'_, R> {
+    type Target = R;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
@@ -1,17 +1,21 @@
 use std::{
     any::{Any, TypeId},
     marker::PhantomData,
+    ops::Range,
 };
 
 use crate::{
+    Component,
     components::{
-        ComponentAddModifier, ComponentRemoveModifier, ComponentSet, EntityComponents,
-        UntypedComponentSet,
+        ComponentAddModifier, ComponentBatchModifier, ComponentMutateModifier,
+        ComponentPredicateModifier, ComponentRemoveModifier, ComponentSet, EntityComponents,
+        UntypedComponentMutation, UntypedComponentPredicate, UntypedComponentSet,
     },
-    entity::{Entity, EntitySpawner},
+    entity::{Entity, EntitySpawner, SpawnError},
     resources::{
         NoSend, Res, ResMut, Resource, ResourceStorageModifier, Resources, UnsendMut, UnsendRef,
     },
+    table::{Row, Table},
 };
 
 pub struct Scene {
@@ -42,8 +46,108 @@ impl Scene {
         }
     }
 
+    /// Creates a scene whose entities are only spawned within `range`, see `EntitySpawner::with_range`.
+    pub fn with_entity_range(range: Range<u32>) -> Self {
+        Self {
+            resources: Resources::new(),
+            unsend: Resources::new(),
+            entities: EntityComponents::with_spawner(EntitySpawner::with_range(range)),
+        }
+    }
+
+    /// Clones all entities and their components, using `clone_row` for every row of components.
+    ///
+    /// Resources are not cloned, the returned scene has none. Returns `None`, if a row can't be cloned.
+    pub fn clone_state(&self, clone_row: impl Fn(&Row) -> Option<Row>) -> Option<Self> {
+        Some(Self {
+            resources: Resources::new(),
+            unsend: Resources::new(),
+            entities: self.entities.try_clone(clone_row)?,
+        })
+    }
+
+    #[cfg(feature = "debug-utils")]
+    /// Returns an overview over all tables (archetypes) of this scene, listing their component types and entity counts.
+    pub fn debug_archetypes(&self) -> String {
+        use std::fmt::Write;
+
+        let tables = &self.entities.tables;
+        let entity_count = tables.iter().map(|table| table.len()).sum::<usize>();
+
+        let mut out = String::with_capacity(1024);
+        _ = writeln!(
+            &mut out,
+            "Scene: {} archetypes, {} entities",
+            tables.len(),
+            entity_count
+        );
+
+        for table in tables {
+            _ = write!(&mut out, "{table:?}");
+        }
+
+        out
+    }
+
+    #[inline]
+    /// Returns the number of tables (archetypes), not the number of entities.
+    ///
+    /// Can include tables that became empty.
+    pub const fn table_count(&self) -> usize {
+        self.entities.tables.len()
+    }
+
+    #[inline]
+    /// Returns `true`, if no table holds an `Entity`.
+    ///
+    /// Entities without any component are not stored in a table, see `iter_entities` to count them.
+    pub fn is_empty(&self) -> bool {
+        self.entities.tables.iter().all(Table::is_empty)
+    }
+
+    #[inline]
+    /// Returns all live entities, with or without components, see `EntityComponents::iter_entities`.
+    pub fn iter_entities(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter_entities()
+    }
+
+    #[inline]
+    /// Merges tables with the same id, see `EntityComponents::defragment`.
+    pub fn defragment(&mut self) -> usize {
+        self.entities.defragment()
+    }
+
+    /// Returns the component types of an `Entity`.
+    ///
+    /// Returns `None` for dead entities, or entities without components.
+    pub fn archetype_of(&self, entity: &Entity) -> Option<Vec<TypeId>> {
+        let table = self.entities.table_of(entity)?;
+        Some(table.types().collect())
+    }
+
+    /// Returns the component names of an `Entity`, see `Component::name`.
+    ///
+    /// Returns `None` for dead entities, or entities without components.
+    pub fn archetype_names_of(&self, entity: &Entity) -> Option<Vec<&'static str>> {
+        let table = self.entities.table_of(entity)?;
+        Some(table.component_type_names().collect())
+    }
+
     #[inline]
-    pub const fn send_scene(&self) -> SendScene {
+    /// Returns `true`, if the `Entity` is alive.
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        self.entities.is_alive(entity)
+    }
+
+    /// Returns `true` if the `Entity` is alive and has a component of type `C`.
+    pub fn contains_component<C: Component>(&self, entity: &Entity) -> bool {
+        self.entities
+            .table_of(entity)
+            .is_some_and(|table| table.contains_one(TypeId::of::<C>()))
+    }
+
+    #[inline]
+    pub const fn send_scene(&self) -> SendScene<'_> {
         SendScene {
             resources: &self.resources,
             entities: &self.entities,
@@ -51,7 +155,7 @@ impl Scene {
     }
 
     #[inline]
-    pub const fn send_scene2(&self) -> SendScene2 {
+    pub const fn send_scene2(&self) -> SendScene2<'_> {
         SendScene2 {
             resources: &self.resources,
             entities: &self.entities,
@@ -68,8 +172,79 @@ impl Scene {
         self.entities.spawn_entity()
     }
 
-    pub fn delete_entity(&mut self, entity: Entity) {
-        self.entities.delete_entity(entity);
+    /// Spawns a specific `Entity`, e.g. to reconstruct a saved scene.
+    ///
+    /// # Errors
+    /// Returns a `SpawnError`, if the slot of the `Entity` is already occupied or the `Entity` is invalid.
+    pub fn spawn_entity_with_id(&mut self, entity: Entity) -> Result<(), SpawnError> {
+        self.entities.activate_entity_with_id(entity)
+    }
+
+    /// Returns a handle to a specific `Entity`, spawning it at its exact id if the slot is free.
+    ///
+    /// Used to resolve entities referenced before being spawned, e.g. while loading a saved scene.
+    ///
+    /// # Errors
+    /// Returns a `SpawnError`, if the slot is live with a different generation, reserved, or the `Entity` is invalid.
+    pub fn get_or_spawn(&mut self, entity: Entity) -> Result<EntityMut<'_>, SpawnError> {
+        // would otherwise match the generation of a dead slot
+        if entity.generation().is_invalid() {
+            return Err(SpawnError::InvalidGeneration(entity));
+        }
+
+        match self.entities.entities.get(&entity) {
+            // already alive
+            Some((generation, _)) if *generation == entity.generation() => {}
+
+            // slot is used by another entity
+            Some((generation, _)) if !generation.is_invalid() => {
+                return Err(SpawnError::GenerationMismatch(entity));
+            }
+
+            _ => self.entities.activate_entity_with_id(entity)?,
+        }
+
+        Ok(EntityMut {
+            scene: self,
+            entity,
+        })
+    }
+
+    /// Adds the components to each `Entity`, spawning it at its exact id if the slot is free, see `Scene::get_or_spawn`.
+    ///
+    /// Already existing components are updated. Used to restore a saved scene.
+    ///
+    /// # Errors
+    /// Returns a `SpawnError` for every `Entity` that could not be spawned, its components are skipped.
+    pub fn insert_or_spawn_batch<C: ComponentSet>(
+        &mut self,
+        iter: impl IntoIterator<Item = (Entity, C)>,
+    ) -> Result<(), Vec<SpawnError>> {
+        let mut errors = Vec::new();
+        for (entity, components) in iter {
+            match self.get_or_spawn(entity) {
+                Ok(mut entity) => {
+                    entity.insert(components);
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Deletes an `Entity` and all its components, returning `false` if it was not alive.
+    pub fn delete_entity(&mut self, entity: Entity) -> bool {
+        self.entities.delete_entity(entity)
+    }
+
+    /// Deletes all entities that have the component `C`.
+    pub fn despawn_all_with<C: Component>(&mut self) {
+        self.entities.delete_entities_with(TypeId::of::<C>());
     }
 
     pub fn add_component<C: ComponentSet>(&mut self, entity: &Entity, components: C) {
@@ -78,6 +253,38 @@ impl Scene {
         self.entities.add_components(entity, components);
     }
 
+    #[inline]
+    /// Adds a clone of `components` to an `Entity`, so the same template can be used again.
+    pub fn add_component_cloned<C: ComponentSet + Clone>(
+        &mut self,
+        entity: &Entity,
+        components: &C,
+    ) {
+        self.add_component(entity, components.clone());
+    }
+
+    /// Spawns `count` entities, each with its own clone of `template`.
+    pub fn spawn_cloned<C: ComponentSet + Clone>(
+        &mut self,
+        template: &C,
+        count: usize,
+    ) -> Vec<Entity> {
+        (0..count)
+            .map(|_| {
+                let entity = self.spawn_entity();
+                self.add_component_cloned(&entity, template);
+                entity
+            })
+            .collect()
+    }
+
+    /// Adds components to an `Entity`, keeping the components it already has.
+    pub fn add_component_if_new<C: ComponentSet>(&mut self, entity: &Entity, components: C) {
+        #[cfg(feature = "runtime-checks")]
+        C::validate();
+        self.entities.add_components_if_new(entity, components);
+    }
+
     #[inline]
     pub fn add_component_untyped(
         &mut self,
@@ -89,12 +296,51 @@ impl Scene {
             .add_component_untyped(entity, components, modifier);
     }
 
+    pub fn add_batch_untyped(
+        &mut self,
+        entities: &[Entity],
+        components: Box<UntypedComponentSet>,
+        modifier: ComponentBatchModifier,
+    ) {
+        self.entities
+            .add_batch_untyped(entities, components, modifier);
+    }
+
+    pub fn mutate_component_untyped(
+        &mut self,
+        entity: &Entity,
+        type_id: TypeId,
+        mutation: Box<UntypedComponentMutation>,
+        modifier: ComponentMutateModifier,
+    ) {
+        self.entities
+            .mutate_component_untyped(entity, type_id, mutation, modifier);
+    }
+
+    pub fn despawn_if_untyped(
+        &mut self,
+        entity: Entity,
+        type_id: TypeId,
+        predicate: Box<UntypedComponentPredicate>,
+        modifier: ComponentPredicateModifier,
+    ) -> bool {
+        self.entities
+            .despawn_if_untyped(entity, type_id, predicate, modifier)
+    }
+
     pub fn remove_components<C: ComponentSet>(&mut self, entity: &Entity) {
         #[cfg(feature = "runtime-checks")]
         C::validate();
         self.entities.remove_component::<C>(entity);
     }
 
+    /// Removes the components `C` from all given entities, see `EntityComponents::remove_components_batch`.
+    pub fn remove_components_batch<C: ComponentSet>(&mut self, entities: &[Entity]) {
+        #[cfg(feature = "runtime-checks")]
+        C::validate();
+        self.entities.remove_components_batch::<C>(entities);
+    }
+
     #[inline]
     pub fn remove_components_untyped(&mut self, entity: Entity, modifier: ComponentRemoveModifier) {
         self.entities.remove_components_untyped(&entity, modifier);
@@ -114,14 +360,33 @@ impl Scene {
         self.resources.insert_resource_untyped(resource, modifier);
     }
 
+    /// Inserts multiple type erased resources at once, e.g. when restoring a snapshot.
+    ///
+    /// Existing resources of the same type are replaced.
+    pub fn insert_resources(
+        &mut self,
+        resources: impl IntoIterator<Item = (TypeId, Box<dyn Any>, ResourceStorageModifier)>,
+    ) {
+        for (type_id, resource, modifier) in resources {
+            debug_assert_eq!(type_id, modifier.resource_type());
+            self.insert_resource_untyped(resource, modifier);
+        }
+    }
+
+    #[inline]
+    /// Returns the `TypeId` of every resource of this scene, in no particular order.
+    pub fn resource_type_ids(&self) -> impl Iterator<Item = TypeId> + '_ {
+        self.resources.type_ids()
+    }
+
     #[inline]
-    pub fn get_resource_ref<R: Resource>(&self) -> Option<Res<R>> {
+    pub fn get_resource_ref<R: Resource>(&self) -> Option<Res<'_, R>> {
         let handle = self.resources.get_resource_ref::<R>()?.into();
         Some(handle)
     }
 
     #[inline]
-    pub fn get_resource_mut<R: Resource>(&self) -> Option<ResMut<R>> {
+    pub fn get_resource_mut<R: Resource>(&self) -> Option<ResMut<'_, R>> {
         let handle = self.resources.get_resource_mut::<R>()?.into();
         Some(handle)
     }
@@ -137,13 +402,13 @@ impl Scene {
     }
 
     #[inline]
-    pub fn get_nosend_resource_ref<R: NoSend>(&self) -> Option<UnsendRef<R>> {
+    pub fn get_nosend_resource_ref<R: NoSend>(&self) -> Option<UnsendRef<'_, R>> {
         let handle = self.unsend.get_resource_ref::<R>()?.into();
         Some(handle)
     }
 
     #[inline]
-    pub fn get_nosend_resource_mut<R: NoSend>(&mut self) -> Option<UnsendMut<R>> {
+    pub fn get_nosend_resource_mut<R: NoSend>(&mut self) -> Option<UnsendMut<'_, R>> {
         let handle = self.unsend.get_resource_mut::<R>()?.into();
         Some(handle)
     }
@@ -186,10 +451,50 @@ pub struct SendScene2<'a> {
 
 impl SendScene2<'_> {
     #[inline]
-    pub const fn send_scene(&self) -> SendScene {
+    pub const fn send_scene(&self) -> SendScene<'_> {
         SendScene {
             resources: unsafe { self.resources.as_ref() }.unwrap(),
             entities: unsafe { self.entities.as_ref() }.unwrap(),
         }
     }
 }
+
+/// Exclusive access to a live `Entity` of a `Scene`, to add or remove its components.
+pub struct EntityMut<'a> {
+    scene: &'a mut Scene,
+    entity: Entity,
+}
+
+impl EntityMut<'_> {
+    #[inline]
+    pub const fn id(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    pub fn insert<C: ComponentSet>(&mut self, components: C) -> &mut Self {
+        self.scene.add_component(&self.entity, components);
+
+        self
+    }
+
+    #[inline]
+    /// Adds components, keeping the components the `Entity` already has.
+    pub fn insert_if_new<C: ComponentSet>(&mut self, components: C) -> &mut Self {
+        self.scene.add_component_if_new(&self.entity, components);
+
+        self
+    }
+
+    #[inline]
+    pub fn remove<C: ComponentSet>(&mut self) -> &mut Self {
+        self.scene.remove_components::<C>(&self.entity);
+
+        self
+    }
+
+    #[inline]
+    pub fn contains<C: Component>(&self) -> bool {
+        self.scene.contains_component::<C>(&self.entity)
+    }
+}
@@ -1,10 +1,13 @@
-use std::any::TypeId;
+use std::{
+    any::{Any, TypeId},
+    time::Duration,
+};
 
 use crate::{macros::unwrap, thread_pool::ThreadPool};
 
 use super::{
-    IntoSystemSet, PostUpdate, PreUpdate, Schedule, SetInfo, Setup, Shutdown, Stage, SystemSet,
-    SystemStage, Update,
+    IntoSystemSet, PostUpdate, PreUpdate, Schedule, ScheduleError, SetInfo, Setup, Shutdown, Stage,
+    StuckFn, SystemHandle, SystemSet, SystemStage, Update,
     graph::{ExecutionGraph, Node},
 };
 
@@ -13,6 +16,14 @@ use super::{
 pub struct ScheduleBuilder {
     thread_count: usize,
     max_tail: usize,
+    join_timeout: Option<Duration>,
+    on_stuck: Option<StuckFn>,
+    thread_affinity: bool,
+    fixed_timestep: Option<Duration>,
+    warn_on_duplicates: bool,
+
+    // id of the next added set
+    next_id: usize,
 
     setup: BStage,
     start: BStage,
@@ -27,6 +38,12 @@ impl ScheduleBuilder {
         Self {
             thread_count: 4,
             max_tail: 8,
+            join_timeout: None,
+            on_stuck: None,
+            thread_affinity: false,
+            fixed_timestep: None,
+            warn_on_duplicates: false,
+            next_id: 0,
             setup: BStage::default(),
             start: BStage::default(),
             update: BStage::default(),
@@ -36,22 +53,127 @@ impl ScheduleBuilder {
     }
 
     #[inline]
-    pub fn build(self) -> Schedule {
+    /// Builds the execution graphs of every stage.
+    ///
+    /// Returns a `ScheduleError`, if a graph doesn't have exactly one root per thread (including the main thread).
+    pub fn build(self) -> Result<Schedule, ScheduleError> {
+        #[cfg(feature = "debug-utils")]
+        for (stage, a, b) in self.exclusive_pairs() {
+            #[cfg(feature = "log")]
+            log::warn!("[{stage}] Systems [{a}] and [{b}] both require exclusive world access");
+
+            #[cfg(not(feature = "log"))]
+            println!(
+                "[WARN] [{stage}] Systems [{a}] and [{b}] both require exclusive world access"
+            );
+        }
+
+        #[cfg(feature = "debug-utils")]
+        if self.warn_on_duplicates {
+            for (stage, name) in self.duplicate_systems() {
+                #[cfg(feature = "log")]
+                log::warn!("[{stage}] System [{name}] was added more than once");
+
+                #[cfg(not(feature = "log"))]
+                println!("[WARN] [{stage}] System [{name}] was added more than once");
+            }
+        }
+
         // include main thread as well
         let thread_count = self.thread_count + 1;
 
         // use a cached graph builder
         let mut graph_builder = GraphBuilder::new(thread_count, self.max_tail);
 
-        Schedule {
-            thread_pool: ThreadPool::new(self.thread_count),
+        let mut thread_pool = ThreadPool::new(self.thread_count, self.thread_affinity);
+        thread_pool.set_join_timeout(self.join_timeout);
+
+        let schedule = Schedule {
+            thread_count,
+            max_tail: self.max_tail,
+            thread_pool,
+            fixed_timestep: self.fixed_timestep,
+            on_stuck: self.on_stuck,
 
             setup: self.setup.build(&mut graph_builder),
             pre_update: self.start.build(&mut graph_builder),
             update: self.update.build(&mut graph_builder),
             post_update: self.finish.build(&mut graph_builder),
             shutdown: self.shutdown.build(&mut graph_builder),
+        };
+
+        schedule.validate()?;
+
+        Ok(schedule)
+    }
+
+    #[cfg(feature = "debug-utils")]
+    /// Returns all pairs of system sets within a stage, that both require exclusive world access (e.g. `&mut World`).
+    ///
+    /// Nothing can run in parallel to such sets, so each pair serializes the stage. Reported by `build` as well.
+    pub fn exclusive_pairs(&self) -> Vec<(&'static str, String, String)> {
+        let mut pairs = Vec::new();
+        for (stage, systems) in self.named_stages() {
+            let exclusive = systems
+                .systems
+                .iter()
+                .filter(|(_, set)| set.get_info().exclusive())
+                .map(|(_, set)| set.name())
+                .collect::<Vec<_>>();
+
+            for (i, a) in exclusive.iter().enumerate() {
+                for b in &exclusive[i + 1..] {
+                    pairs.push((stage, a.clone(), b.clone()));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    #[cfg(feature = "debug-utils")]
+    /// Returns every system set, that was added more than once to the same stage.
+    ///
+    /// Sets are compared by name, each duplicate is reported once. Reported by `build`, if enabled with `warn_on_duplicate_systems`.
+    pub fn duplicate_systems(&self) -> Vec<(&'static str, String)> {
+        let mut duplicates = Vec::new();
+        for (stage, systems) in self.named_stages() {
+            let names = systems
+                .systems
+                .iter()
+                .map(|(_, set)| set.name())
+                .collect::<Vec<_>>();
+
+            for (i, name) in names.iter().enumerate() {
+                let first = names.iter().position(|other| other == name) == Some(i);
+                if first && names[i + 1..].contains(name) {
+                    duplicates.push((stage, name.clone()));
+                }
+            }
         }
+
+        duplicates
+    }
+
+    #[cfg(feature = "debug-utils")]
+    const fn named_stages(&self) -> [(&'static str, &BStage); 5] {
+        [
+            ("Setup", &self.setup),
+            ("PreUpdate", &self.start),
+            ("Update", &self.update),
+            ("PostUpdate", &self.finish),
+            ("Shutdown", &self.shutdown),
+        ]
+    }
+
+    #[inline]
+    /// Warns about systems added more than once to the same stage, when building the schedule.
+    ///
+    /// Duplicates are still allowed. Requires the `debug-utils` feature, otherwise this is a no-op.
+    pub const fn warn_on_duplicate_systems(mut self, warn: bool) -> Self {
+        self.warn_on_duplicates = warn;
+
+        self
     }
 
     #[inline]
@@ -68,46 +190,150 @@ impl ScheduleBuilder {
         self
     }
 
-    pub fn add_system<T: SystemStage, M>(mut self, _: T, system: impl IntoSystemSet<M>) -> Self {
-        let set = system.into_set();
-        let stage_id = TypeId::of::<T>();
+    #[inline]
+    /// Sets how long a stage waits for its systems, before reporting them as stuck.
+    ///
+    /// Stuck systems still borrow the world, so the stage keeps waiting for them after reporting.
+    /// They are logged as an error, unless a callback is set with `on_stuck`.
+    pub const fn set_join_timeout(mut self, timeout: Duration) -> Self {
+        self.join_timeout = Some(timeout);
+
+        self
+    }
+
+    #[inline]
+    /// Calls `on_stuck` (on the thread running the schedule), when systems did not finish within the join timeout.
+    ///
+    /// Lets the caller react to stuck systems, e.g. by releasing what they wait for, or by aborting.
+    /// The stage keeps waiting for the systems after `on_stuck` returned, see `set_join_timeout`.
+    pub const fn on_stuck(mut self, on_stuck: StuckFn) -> Self {
+        self.on_stuck = Some(on_stuck);
+
+        self
+    }
+
+    #[inline]
+    /// Sets the timestep of `Update`, when running the schedule with `Schedule::run_fixed`.
+    ///
+    /// # Panics
+    /// - if the timestep is zero
+    pub fn set_fixed_timestep(mut self, timestep: Duration) -> Self {
+        assert!(!timestep.is_zero(), "Fixed timestep must not be zero");
+        self.fixed_timestep = Some(timestep);
+
+        self
+    }
+
+    #[inline]
+    /// Pins each pool thread to a distinct core, leaving the first core for the main thread.
+    ///
+    /// Requires the `thread-affinity` feature, otherwise (and on unsupported platforms) this is a no-op.
+    pub const fn set_thread_affinity(mut self, pin_threads: bool) -> Self {
+        self.thread_affinity = pin_threads;
+
+        self
+    }
+
+    pub fn add_system<T: SystemStage, M>(self, stage: T, system: impl IntoSystemSet<M>) -> Self {
+        self.add_set(stage, system.into_set())
+    }
+
+    /// Same as `add_system`, but also returns a handle to remove the system from the built `Schedule`.
+    pub fn add_system_with_handle<T: SystemStage, M>(
+        self,
+        stage: T,
+        system: impl IntoSystemSet<M>,
+    ) -> (Self, SystemHandle) {
+        let handle = SystemHandle(self.next_id);
 
+        (self.add_set(stage, system.into_set()), handle)
+    }
+
+    /// Adds a system (set) that runs on the main thread, without any other system running concurrently.
+    ///
+    /// Behaves like a system requiring `&mut World`, but still retrieves its parameters as usual.
+    pub fn add_exclusive_system<T: SystemStage, M>(
+        self,
+        stage: T,
+        system: impl IntoSystemSet<M>,
+    ) -> Self {
+        self.add_set(stage, system.into_set().exclusive())
+    }
+
+    /// Only applies commands after the given stages, instead of after every stage.
+    ///
+    /// Commands issued in other stages are applied after the next selected stage, or at the start of the next `Schedule::run`.
+    /// A selected stage flushes even without systems, e.g. to apply the commands of `Update` after an empty `PostUpdate`.
+    /// Only affects the stages run by `Schedule::run`, commands are always applied after `Setup` and `Shutdown`.
+    pub fn apply_commands_after(mut self, stages: &[&dyn SystemStage]) -> Self {
+        for stage in [&mut self.start, &mut self.update, &mut self.finish] {
+            stage.apply_commands = false;
+        }
+
+        for stage in stages {
+            let stage_id = (*stage as &dyn Any).type_id();
+            self.stage_mut(stage_id).apply_commands = true;
+        }
+
+        self
+    }
+
+    fn add_set<T: SystemStage>(mut self, _: T, set: SystemSet) -> Self {
+        let set_id = self.next_id;
+        self.next_id += 1;
+
+        self.stage_mut(TypeId::of::<T>()).add_system(set_id, set);
+
+        self
+    }
+
+    fn stage_mut(&mut self, stage_id: TypeId) -> &mut BStage {
         match stage_id {
-            id if id == TypeId::of::<Setup>() => self.setup.add_system(set),
-            id if id == TypeId::of::<PreUpdate>() => self.start.add_system(set),
-            id if id == TypeId::of::<Update>() => self.update.add_system(set),
-            id if id == TypeId::of::<PostUpdate>() => self.finish.add_system(set),
-            id if id == TypeId::of::<Shutdown>() => self.shutdown.add_system(set),
+            id if id == TypeId::of::<Setup>() => &mut self.setup,
+            id if id == TypeId::of::<PreUpdate>() => &mut self.start,
+            id if id == TypeId::of::<Update>() => &mut self.update,
+            id if id == TypeId::of::<PostUpdate>() => &mut self.finish,
+            id if id == TypeId::of::<Shutdown>() => &mut self.shutdown,
             _ => {
                 // find substage with id
                 unreachable!()
             }
         }
-
-        self
     }
 }
 
-#[derive(Default)]
 #[cfg_attr(feature = "debug-utils", derive(Debug))]
 struct BStage {
     // build execution tree from these
-    systems: Vec<SystemSet>,
+    systems: Vec<(usize, SystemSet)>,
+    // apply commands after this stage ran
+    apply_commands: bool,
+}
+
+impl Default for BStage {
+    fn default() -> Self {
+        Self {
+            systems: Vec::new(),
+            apply_commands: true,
+        }
+    }
 }
 
 impl BStage {
     fn build(self, graph_builder: &mut GraphBuilder) -> Stage {
         Stage {
+            inits: Stage::collect_inits(&self.systems),
             systems: graph_builder.build_graph_from(self.systems),
+            apply_commands: self.apply_commands,
         }
     }
 
-    fn add_system(&mut self, set: SystemSet) {
-        self.systems.push(set);
+    fn add_system(&mut self, id: usize, set: SystemSet) {
+        self.systems.push((id, set));
     }
 }
 
-struct GraphBuilder {
+pub(super) struct GraphBuilder {
     thread_count: usize,
     max_tail: usize,
 
@@ -119,7 +345,7 @@ struct GraphBuilder {
     // stores threads that have to use a set based on their parameters (pref only one)
     conflicts: Vec<usize>,
 
-    leftovers: Vec<SystemSet>,
+    leftovers: Vec<(usize, SystemSet)>,
 }
 
 impl GraphBuilder {
@@ -144,7 +370,8 @@ impl GraphBuilder {
         }
     }
 
-    pub fn build_graph_from(&mut self, mut systems: Vec<SystemSet>) -> ExecutionGraph {
+    /// Builds a graph from the given sets, each tagged with an id to find it again later.
+    pub fn build_graph_from(&mut self, mut systems: Vec<(usize, SystemSet)>) -> ExecutionGraph {
         if systems.is_empty() {
             return ExecutionGraph::new_empty();
         }
@@ -157,7 +384,7 @@ impl GraphBuilder {
 
         while first || !systems.is_empty() {
             #[allow(clippy::iter_with_drain)]
-            'inner: for system in systems.drain(..) {
+            'inner: for (id, system) in systems.drain(..) {
                 //
                 let set = system.get_info();
 
@@ -182,11 +409,11 @@ impl GraphBuilder {
 
                         // make sure not all systems get pushed into one thread
                         if self.check_tail_too_long(thread_i) {
-                            self.leftovers.push(system);
+                            self.leftovers.push((id, system));
                         }
                         //
                         else {
-                            self.threads_current[thread_i].push(Node::new_system(system));
+                            self.threads_current[thread_i].push(Node::new_system(id, system));
                             self.threads_since_sync[thread_i] += 1;
 
                             // add types to reserved types for this thread
@@ -206,9 +433,9 @@ impl GraphBuilder {
 
                         // make sure not all systems get pushed into one thread
                         if self.check_tail_too_long(thread_i) {
-                            self.leftovers.push(system);
+                            self.leftovers.push((id, system));
                         } else {
-                            self.threads_current[thread_i].push(Node::new_system(system));
+                            self.threads_current[thread_i].push(Node::new_system(id, system));
                             self.threads_since_sync[thread_i] += 1;
 
                             // add types to reserved types for this thread
@@ -220,7 +447,7 @@ impl GraphBuilder {
                     _ => {
                         // system does not fit
                         // store system for next round, try next system
-                        self.leftovers.push(system);
+                        self.leftovers.push((id, system));
                     }
                 }
 
@@ -260,6 +487,7 @@ impl GraphBuilder {
             debug_assert!(tc.is_empty());
         }
 
+        tree.sort_dispatch_order();
         tree
     }
 
@@ -385,10 +613,86 @@ mod tests {
             assert_eq!(schedule.update.systems.len(), 1);
             assert_eq!(schedule.finish.systems.len(), 1);
         }
+
+        fn sys_a(_: crate::Query<&mut u32>) {}
+        fn sys_b(_: crate::Query<(&mut i32, &u32)>) {}
+        fn sys_c(_: crate::Query<&u32>) {}
+        fn sys_d(_: crate::Query<&i32>) {}
+
+        fn assert_chained(set: SystemSet, expected: &[&str]) {
+            let SystemSet::Chained { systems } = set else {
+                panic!("expected a chained set");
+            };
+
+            assert_eq!(systems.len(), expected.len());
+
+            #[cfg(feature = "debug-utils")]
+            for (system, name) in systems.iter().zip(expected) {
+                assert!(system.name().ends_with(name));
+            }
+        }
+
+        #[test]
+        fn test_chained_set() {
+            assert_chained((sys_a, sys_b).into_set(), &["sys_a", "sys_b"]);
+
+            assert_chained(
+                (sys_d, sys_c, sys_b, sys_a).into_set(),
+                &["sys_d", "sys_c", "sys_b", "sys_a"],
+            );
+
+            assert_chained(
+                (sys_a, sys_b, sys_c, sys_d, sys_a, sys_b, sys_c, sys_d).into_set(),
+                &[
+                    "sys_a", "sys_b", "sys_c", "sys_d", "sys_a", "sys_b", "sys_c", "sys_d",
+                ],
+            );
+        }
+
+        fn sys_spawn(commands: crate::Commands) {
+            let entity = commands.reserve_entity();
+            commands.add_component(&entity, 0u32);
+        }
+
+        #[test]
+        fn test_update_only_stages() {
+            let schedule = ScheduleBuilder::new()
+                .add_system(Update, sys_spawn)
+                .build()
+                .unwrap();
+
+            assert!(schedule.setup.is_empty());
+            assert!(schedule.pre_update.is_empty());
+            assert!(!schedule.update.is_empty());
+            assert!(schedule.post_update.is_empty());
+            assert!(schedule.shutdown.is_empty());
+
+            let mut world = crate::World::new();
+            for _ in 0..1000 {
+                schedule.run(&mut world);
+            }
+
+            // every run flushed after update, nothing is left for the skipped stages
+            assert_eq!(world.current_scene().iter_entities().count(), 1000);
+            assert!(world.apply_commands().is_empty());
+
+            // the only flushing stage is empty, but still applies the commands of update
+            let schedule = ScheduleBuilder::new()
+                .add_system(Update, sys_spawn)
+                .apply_commands_after(&[&PostUpdate])
+                .build()
+                .unwrap();
+            assert!(schedule.post_update.is_empty());
+            assert!(schedule.post_update.apply_commands);
+
+            schedule.run(&mut world);
+            assert_eq!(world.current_scene().iter_entities().count(), 1001);
+            assert!(world.apply_commands().is_empty());
+        }
     }
 
     mod builder {
-        use crate::{Query, With, WithOut};
+        use crate::{Component, Or, Query, With, WithOut};
 
         pub use super::super::*;
 
@@ -411,12 +715,23 @@ mod tests {
 
         fn sys_mut_u32_with_i32(_: Query<&mut u32, With<i32>>) {}
 
+        struct Marker;
+        impl Component for Marker {}
+
+        fn sys_ref_marker(_: Query<&Marker>) {}
+
+        fn sys_mut_marker(_: Query<&mut Marker>) {}
+
+        fn sys_mut_u32_or(_: Query<&mut u32, Or<With<i32>, With<Marker>>>) {}
+
+        fn sys_mut_u32_neither(_: Query<&mut u32, (WithOut<i32>, WithOut<Marker>)>) {}
+
         #[test]
         fn test_builder_empty() {
             let builder = ScheduleBuilder::new().set_thread_count(THREAD_COUNT);
             assert_eq!(builder.thread_count, THREAD_COUNT);
 
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.len(), 0);
         }
@@ -434,7 +749,7 @@ mod tests {
             // [SYNC]
             // [SYNC]
             let builder = builder.add_system(Update, sys_ref_i32);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 2);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 1);
@@ -457,7 +772,7 @@ mod tests {
             let builder = builder
                 .add_system(Update, sys_ref_i32)
                 .add_system(Update, sys_mut_i32);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 3);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 1);
@@ -482,7 +797,7 @@ mod tests {
                 .add_system(Update, sys_ref_i32)
                 .add_system(Update, sys_ref_u32)
                 .add_system(Update, sys_ref_u32);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 2);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 2);
@@ -507,7 +822,7 @@ mod tests {
                 .add_system(Update, sys_mut_i32)
                 .add_system(Update, sys_ref_u32)
                 .add_system(Update, sys_mut_u32);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 3);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 3);
@@ -533,7 +848,7 @@ mod tests {
                 .add_system(Update, sys_ref_u32)
                 .add_system(Update, sys_mut_u32)
                 .add_system(Update, sys_ref_shared);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 5);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 4);
@@ -559,7 +874,7 @@ mod tests {
                 .add_system(Update, sys_mut_u32)
                 .add_system(Update, sys_ref_i32)
                 .add_system(Update, sys_ref_u32);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 6);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 3);
@@ -567,6 +882,37 @@ mod tests {
             assert_eq!(schedule.update.systems.node_tree[3].node_count, 2);
         }
 
+        #[test]
+        fn test_builder_stage_stats() {
+            // [sys_ref_shared, sys_mut_i32, sys_mut_u32, SYNC1, sys_ref_i32, SYNC2]
+            // [                                          SYNC1, sys_ref_u32, SYNC2]
+            // [                                          SYNC1,              SYNC2]
+            // [                                          SYNC1,              SYNC2]
+            let schedule = ScheduleBuilder::new()
+                .set_thread_count(THREAD_COUNT)
+                .set_max_tail(MAX_TAIL)
+                .add_system(Update, sys_ref_shared)
+                .add_system(Update, sys_mut_i32)
+                .add_system(Update, sys_mut_u32)
+                .add_system(Update, sys_ref_i32)
+                .add_system(Update, sys_ref_u32)
+                .build()
+                .unwrap();
+
+            let stats = schedule.stage_stats();
+            assert_eq!(stats.len(), 5);
+
+            let update = stats.iter().find(|s| s.stage == "Update").unwrap();
+            assert_eq!(update.sync_points, 2);
+            assert_eq!(update.max_systems_per_thread, 4);
+            assert_eq!(update.min_systems_per_thread, 0);
+
+            // empty stages
+            let setup = stats.iter().find(|s| s.stage == "Setup").unwrap();
+            assert_eq!(setup.sync_points, 0);
+            assert_eq!(setup.max_systems_per_thread, 0);
+        }
+
         #[test]
         fn test_builder_system_world() {
             let builder = ScheduleBuilder::new()
@@ -585,7 +931,7 @@ mod tests {
                 .add_system(Update, sys_world)
                 .add_system(Update, sys_ref_i32)
                 .add_system(Update, sys_ref_u32);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 5);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 4);
@@ -593,6 +939,93 @@ mod tests {
             assert_eq!(schedule.update.systems.node_tree[3].node_count, 2);
         }
 
+        #[test]
+        fn test_builder_dispatch_order() {
+            // [sys_mut_i32, SYNC]
+            // [sys_mut_u32, SYNC]
+            // [sys_mut_marker, sys_ref_marker, SYNC]
+            // [SYNC]
+            let schedule = ScheduleBuilder::new()
+                .set_thread_count(THREAD_COUNT)
+                .set_max_tail(MAX_TAIL)
+                .add_system(Update, sys_mut_i32)
+                .add_system(Update, sys_mut_u32)
+                .add_system(Update, sys_mut_marker)
+                .add_system(Update, sys_ref_marker)
+                .build()
+                .unwrap();
+
+            // sorted once when building, the heaviest pool root first
+            // every pool root gets its own thread, so the order only decides which one starts first,
+            // too small of a difference to test by timing
+            assert_eq!(schedule.update.systems.dispatch_order(), [2, 1, 3, 4]);
+            assert!(schedule.setup.systems.dispatch_order().is_empty());
+        }
+
+        #[cfg(feature = "debug-utils")]
+        #[test]
+        fn test_builder_exclusive_pairs() {
+            let builder = ScheduleBuilder::new()
+                .add_system(Update, sys_world)
+                .add_system(Update, sys_ref_u32)
+                .add_exclusive_system(Update, sys_ref_i32)
+                .add_system(PostUpdate, sys_world);
+
+            let pairs = builder.exclusive_pairs();
+            assert_eq!(pairs.len(), 1);
+
+            let (stage, a, b) = &pairs[0];
+            assert_eq!(*stage, "Update");
+            assert!(a.contains("sys_world"));
+            assert!(b.contains("sys_ref_i32"));
+
+            builder.build().unwrap();
+        }
+
+        #[cfg(feature = "debug-utils")]
+        #[test]
+        fn test_builder_duplicate_systems() {
+            let builder = ScheduleBuilder::new()
+                .warn_on_duplicate_systems(true)
+                .add_system(Update, sys_ref_i32)
+                .add_system(Update, sys_ref_i32)
+                .add_system(Update, sys_ref_i32)
+                .add_system(Update, sys_ref_u32)
+                .add_system(PostUpdate, sys_ref_u32);
+
+            let duplicates = builder.duplicate_systems();
+            assert_eq!(duplicates.len(), 1);
+
+            let (stage, name) = &duplicates[0];
+            assert_eq!(*stage, "Update");
+            assert!(name.contains("sys_ref_i32"));
+
+            // duplicates are only warned about
+            builder.build().unwrap();
+        }
+
+        #[test]
+        fn test_builder_exclusive_system() {
+            let builder = ScheduleBuilder::new()
+                .set_thread_count(THREAD_COUNT)
+                .set_max_tail(MAX_TAIL);
+
+            // [sys_ref_u32, SYNC1, sys_ref_shared, SYNC2]
+            // [sys_ref_i32, SYNC1,                 SYNC2]
+            // [             SYNC1,                 SYNC2]
+            // [             SYNC1,                 SYNC2]
+            let builder = builder
+                .add_system(Update, sys_ref_u32)
+                .add_system(Update, sys_ref_i32)
+                .add_exclusive_system(Update, sys_ref_shared);
+            let schedule = builder.build().unwrap();
+
+            assert_eq!(schedule.update.systems.node_tree[0].node_count, 4);
+            assert_eq!(schedule.update.systems.node_tree[1].node_count, 3);
+            assert_eq!(schedule.update.systems.node_tree[2].node_count, 2);
+            assert_eq!(schedule.update.systems.node_tree[3].node_count, 2);
+        }
+
         #[test]
         fn test_builder_mix_shared_depend_filter() {
             let builder = ScheduleBuilder::new()
@@ -608,7 +1041,7 @@ mod tests {
             let builder = builder
                 .add_system(Update, sys_mut_u32_not_i32)
                 .add_system(Update, sys_mut_u32_with_i32);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 2);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 2);
@@ -633,12 +1066,130 @@ mod tests {
                 .add_system(Update, sys_mut_u32_with_i32)
                 .add_system(Update, sys_mut_u32_not_i32)
                 .add_system(Update, sys_world);
-            let schedule = builder.build();
+            let schedule = builder.build().unwrap();
 
             assert_eq!(schedule.update.systems.node_tree[0].node_count, 5);
             assert_eq!(schedule.update.systems.node_tree[1].node_count, 3);
             assert_eq!(schedule.update.systems.node_tree[2].node_count, 2);
             assert_eq!(schedule.update.systems.node_tree[3].node_count, 2);
         }
+
+        #[test]
+        fn test_builder_or_filter_overlapping() {
+            let builder = ScheduleBuilder::new()
+                .set_thread_count(THREAD_COUNT)
+                .set_max_tail(MAX_TAIL);
+
+            // tables with `Marker`, but without `i32`, pass both filters
+            // [sys_mut_u32_or, sys_mut_u32_not_i32, SYNC]
+            // [                                     SYNC]
+            // [                                     SYNC]
+            // [                                     SYNC]
+            let builder = builder
+                .add_system(Update, sys_mut_u32_or)
+                .add_system(Update, sys_mut_u32_not_i32);
+            let schedule = builder.build().unwrap();
+
+            assert_eq!(schedule.update.systems.node_tree[0].node_count, 3);
+            assert_eq!(schedule.update.systems.node_tree[1].node_count, 1);
+            assert_eq!(schedule.update.systems.node_tree[2].node_count, 1);
+            assert_eq!(schedule.update.systems.node_tree[3].node_count, 1);
+        }
+
+        #[test]
+        fn test_builder_or_filter_disjoint() {
+            let builder = ScheduleBuilder::new()
+                .set_thread_count(THREAD_COUNT)
+                .set_max_tail(MAX_TAIL);
+
+            // [sys_mut_u32_or     , SYNC1]
+            // [sys_mut_u32_neither, SYNC1]
+            // [                     SYNC1]
+            // [                     SYNC1]
+            let builder = builder
+                .add_system(Update, sys_mut_u32_or)
+                .add_system(Update, sys_mut_u32_neither);
+            let schedule = builder.build().unwrap();
+
+            assert_eq!(schedule.update.systems.node_tree[0].node_count, 2);
+            assert_eq!(schedule.update.systems.node_tree[1].node_count, 2);
+            assert_eq!(schedule.update.systems.node_tree[2].node_count, 1);
+            assert_eq!(schedule.update.systems.node_tree[3].node_count, 1);
+        }
+
+        #[test]
+        fn test_graph_dispatch_order() {
+            // skewed roots, the local root is never dispatched
+            let mut graph = ExecutionGraph::new(THREAD_COUNT + 1);
+            for (root, count) in graph.node_tree.iter_mut().zip([9, 1, 4, 2, 6]) {
+                root.node_count = count;
+            }
+
+            graph.sort_dispatch_order();
+            assert_eq!(graph.dispatch_order(), [4, 2, 3, 1]);
+        }
+
+        #[test]
+        fn test_builder_validate_roots() {
+            for thread_count in [1, 2, THREAD_COUNT] {
+                let schedule = ScheduleBuilder::new()
+                    .set_thread_count(thread_count)
+                    .add_system(Update, sys_ref_u32)
+                    .build()
+                    .unwrap();
+                assert_eq!(schedule.update.systems.node_tree.len(), thread_count + 1);
+            }
+
+            let mut schedule = ScheduleBuilder::new()
+                .set_thread_count(THREAD_COUNT)
+                .add_system(Update, sys_ref_u32)
+                .build()
+                .unwrap();
+
+            // graph and pool disagree
+            schedule.update.systems =
+                GraphBuilder::new(2, MAX_TAIL).build_graph_from(vec![(0, sys_ref_u32.into_set())]);
+            assert_eq!(
+                schedule.validate(),
+                Err(ScheduleError::ThreadCountMismatch {
+                    roots: 2,
+                    threads: THREAD_COUNT + 1
+                })
+            );
+        }
+
+        #[test]
+        fn test_world_shared_conflicts() {
+            use crate::{schedule::SystemInfo, system::ParamType};
+
+            let info = |types: Vec<ParamType>| SetInfo {
+                systems: vec![SystemInfo {
+                    types,
+                    filter: Vec::new(),
+                    exclusive: false,
+                }],
+                local: false,
+                label: None,
+            };
+
+            let world = info(vec![ParamType::World]);
+            let world_shared = info(vec![ParamType::WorldShared]);
+            let shared = info(vec![ParamType::new_shared::<u32>()]);
+            let mutable = info(vec![ParamType::new_mut::<u32>()]);
+
+            // read-only world access
+            assert!(!world_shared.conflicts(&world_shared));
+            assert!(!world_shared.conflicts(&shared));
+            assert!(!shared.conflicts(&world_shared));
+            assert!(world_shared.conflicts(&mutable));
+            assert!(mutable.conflicts(&world_shared));
+            assert!(world_shared.conflicts(&world));
+            assert!(world.conflicts(&world_shared));
+
+            // exclusive world access
+            assert!(world.conflicts(&world));
+            assert!(world.conflicts(&shared));
+            assert!(world.conflicts(&mutable));
+        }
     }
 }
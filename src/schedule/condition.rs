@@ -0,0 +1,109 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{Resource, resources::Resources, system::ParamType, world::SendWorld};
+
+type ConditionFn = dyn Fn(SendWorld<'_>) -> bool + Send + Sync;
+
+/// A predicate, deciding if a system (set) runs.
+///
+/// Evaluated every time the set would run, right before it.
+pub struct RunCondition {
+    types: Vec<ParamType>,
+    check: Box<ConditionFn>,
+}
+
+impl RunCondition {
+    /// Creates a new `RunCondition`.
+    ///
+    /// `types` has to contain every resource `check` reads, so conflicting systems are not run concurrently.
+    pub fn new(
+        types: Vec<ParamType>,
+        check: impl Fn(SendWorld<'_>) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            types,
+            check: Box::new(check),
+        }
+    }
+
+    #[inline]
+    pub fn types(&self) -> &[ParamType] {
+        &self.types
+    }
+
+    #[inline]
+    pub fn check(&self, world: SendWorld<'_>) -> bool {
+        (self.check)(world)
+    }
+}
+
+#[cfg(feature = "debug-utils")]
+impl std::fmt::Debug for RunCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunCondition")
+            .field("types", &self.types)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs only if the resource `R` exists in the current scene.
+pub fn resource_exists<R: Resource>() -> RunCondition {
+    RunCondition::new(Vec::new(), |world| {
+        world.scene.resources.contains_resource::<R>()
+    })
+}
+
+/// Runs only if the resource `R` exists in the current scene and equals `value`.
+pub fn resource_equals<R: Resource + PartialEq>(value: R) -> RunCondition {
+    RunCondition::new(vec![ParamType::new_shared::<R>()], move |world| {
+        world
+            .scene
+            .resources
+            .get_resource_ref::<R>()
+            .is_some_and(|res| *res == value)
+    })
+}
+
+/// Runs only if the global resource `R` exists.
+pub fn global_resource_exists<R: Resource>() -> RunCondition {
+    RunCondition::new(Vec::new(), |world| {
+        world.global_resource.contains_resource::<R>()
+    })
+}
+
+/// Runs only if the global resource `R` exists and equals `value`.
+pub fn global_resource_equals<R: Resource + PartialEq>(value: R) -> RunCondition {
+    RunCondition::new(vec![ParamType::new_shared::<R>()], move |world| {
+        world
+            .global_resource
+            .get_resource_ref::<R>()
+            .is_some_and(|res| *res == value)
+    })
+}
+
+/// Runs only if the resource `R` of the current scene changed since the condition was last checked.
+///
+/// A resource counts as changed, when it was inserted or mutably dereferenced (e.g. through `ResMut`).
+pub fn resource_changed<R: Resource>() -> RunCondition {
+    let last_seen = AtomicU64::new(0);
+    RunCondition::new(vec![ParamType::new_shared::<R>()], move |world| {
+        changed_since::<R>(world.scene.resources, &last_seen)
+    })
+}
+
+/// Runs only if the global resource `R` changed since the condition was last checked.
+///
+/// A resource counts as changed, when it was inserted or mutably dereferenced (e.g. through `GlobalResMut`).
+pub fn global_resource_changed<R: Resource>() -> RunCondition {
+    let last_seen = AtomicU64::new(0);
+    RunCondition::new(vec![ParamType::new_shared::<R>()], move |world| {
+        changed_since::<R>(world.global_resource, &last_seen)
+    })
+}
+
+#[inline]
+fn changed_since<R: Resource>(resources: &Resources<dyn Resource>, last_seen: &AtomicU64) -> bool {
+    resources
+        .version::<R>()
+        .is_some_and(|version| last_seen.swap(version, Ordering::Relaxed) != version)
+}
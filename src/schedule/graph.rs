@@ -1,4 +1,7 @@
-use std::sync::{Arc, Barrier};
+use std::{
+    sync::{Arc, Condvar, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
 use crate::{
     cells::{WorldCellComplete, WorldCellSend},
@@ -6,12 +9,14 @@ use crate::{
     thread_pool::ThreadPool,
 };
 
-use super::SystemSet;
+use super::{StageStats, StuckFn, StuckSystems, SystemSet};
 
 #[derive(Default)]
 pub struct ExecutionGraph {
     pub(super) node_tree: Box<[Root]>,
     pub(super) nodes: Vec<Node>,
+    // roots run by the pool threads, see `dispatch_order`
+    dispatch_order: Box<[usize]>,
 }
 
 #[cfg(feature = "debug-utils")]
@@ -48,6 +53,7 @@ impl ExecutionGraph {
         Self {
             node_tree: vec![Root::new(); thread_count].into_boxed_slice(),
             nodes: Vec::new(),
+            dispatch_order: Box::new([]),
         }
     }
 
@@ -55,6 +61,7 @@ impl ExecutionGraph {
         Self {
             node_tree: Box::new([]),
             nodes: Vec::new(),
+            dispatch_order: Box::new([]),
         }
     }
 
@@ -68,17 +75,89 @@ impl ExecutionGraph {
         self.nodes.len()
     }
 
-    pub fn run(&self, complete: WorldCellComplete, send: WorldCellSend, pool: &ThreadPool) {
+    #[inline]
+    pub fn contains(&self, id: usize) -> bool {
+        self.nodes
+            .iter()
+            .any(|node| matches!(node, Node::System { id: node_id, .. } if *node_id == id))
+    }
+
+    pub fn stats(&self, stage: &'static str) -> StageStats {
+        // every thread waits at every sync point
+        let sync_points = self
+            .nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Sync { .. }))
+            .count()
+            / self.node_tree.len().max(1);
+
+        let systems_per_thread = self
+            .node_tree
+            .iter()
+            .map(|root| root.node_count - sync_points);
+
+        StageStats {
+            stage,
+            sync_points,
+            max_systems_per_thread: systems_per_thread.clone().max().unwrap_or(0),
+            min_systems_per_thread: systems_per_thread.min().unwrap_or(0),
+        }
+    }
+
+    /// Takes the graph apart, returning all sets ordered by their id.
+    pub fn into_sets(self) -> Vec<(usize, SystemSet)> {
+        let mut sets = self
+            .nodes
+            .into_iter()
+            .filter_map(|node| match node {
+                Node::System { id, systems, .. } => Some((id, systems)),
+                Node::Sync { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        sets.sort_by_key(|(id, _)| *id);
+        sets
+    }
+
+    /// Returns the roots run by the pool threads (all except the first), the ones with the most nodes first.
+    ///
+    /// The heaviest roots are dispatched first, so they start as early as possible.
+    #[inline]
+    pub fn dispatch_order(&self) -> &[usize] {
+        &self.dispatch_order
+    }
+
+    /// Sorts the roots for `dispatch_order`, called once the graph is built.
+    pub(super) fn sort_dispatch_order(&mut self) {
+        let mut order = (1..self.node_tree.len()).collect::<Box<_>>();
+        order.sort_by_key(|root_i| std::cmp::Reverse(self.node_tree[*root_i].node_count));
+        self.dispatch_order = order;
+    }
+
+    pub fn run(
+        &self,
+        complete: WorldCellComplete,
+        send: WorldCellSend,
+        pool: &ThreadPool,
+        on_stuck: Option<StuckFn>,
+    ) {
         if self.is_empty() {
             return;
         }
 
+        let stuck = pool
+            .join_timeout()
+            .map(|timeout| StuckReport { timeout, on_stuck });
+
         let handle = pool.scope(|s| {
-            // skip first element here, as it has to run localy
-            let iter = self.node_tree.iter().skip(1);
+            // first element is skipped here, as it has to run localy
+            let iter = self
+                .dispatch_order()
+                .iter()
+                .map(|root_i| &self.node_tree[*root_i]);
 
             // send every root node to a thread to execute
-            // number of threads and number of root nodes should match
+            // number of threads and number of root nodes should match, validated when building the schedule
             debug_assert_eq!(self.node_tree.len(), s.thread_count() + 1);
             for (root, thread) in iter.zip(s.threads()) {
                 let world = send.clone();
@@ -87,10 +166,55 @@ impl ExecutionGraph {
                 });
             }
 
-            self.node_tree[0].run_local(complete, &self.nodes);
+            // the main thread waits at every sync point, so it is the one noticing stuck systems
+            self.node_tree[0].run_local(complete, &self.nodes, stuck.as_ref());
         });
 
-        handle.join();
+        let Some(stuck) = stuck else {
+            handle.join();
+            return;
+        };
+
+        if let Err(err) = handle.join_timeout(stuck.timeout) {
+            stuck.report(err.pending());
+
+            // systems still borrow the world, so keep waiting
+            err.wait();
+        }
+    }
+}
+
+/// Reports systems that did not finish within the join timeout.
+struct StuckReport {
+    timeout: Duration,
+    on_stuck: Option<StuckFn>,
+}
+
+impl StuckReport {
+    fn report(&self, pending: usize) {
+        if let Some(on_stuck) = self.on_stuck {
+            on_stuck(StuckSystems {
+                pending,
+                timeout: self.timeout,
+            });
+            return;
+        }
+
+        #[cfg(feature = "log")]
+        {
+            log::error!(
+                "Systems are stuck: {pending} threads did not finish within {:?}",
+                self.timeout
+            )
+        }
+
+        #[cfg(not(feature = "log"))]
+        {
+            println!(
+                "[ERROR] Systems are stuck: {pending} threads did not finish within {:?}",
+                self.timeout
+            )
+        }
     }
 }
 
@@ -148,13 +272,13 @@ impl Root {
         }
     }
 
-    fn run_local(&self, world: WorldCellComplete, nodes: &[Node]) {
+    fn run_local(&self, world: WorldCellComplete, nodes: &[Node], stuck: Option<&StuckReport>) {
         // get first node
         let mut node: Option<&Node> = self.node.map(|i| &nodes[i]);
 
         // keep walking the linked-list
         while let Some(n) = node {
-            n.run_local(world.clone());
+            n.run_local(world.clone(), stuck);
 
             node = n.next(nodes);
         }
@@ -165,6 +289,8 @@ impl Root {
 pub enum Node {
     System {
         next: Option<usize>,
+        // id given by the schedule builder, see `SystemHandle`
+        id: usize,
         systems: SystemSet,
     },
     Sync {
@@ -175,9 +301,10 @@ pub enum Node {
 
 impl Node {
     #[inline]
-    pub(crate) const fn new_system(systems: SystemSet) -> Self {
+    pub(crate) const fn new_system(id: usize, systems: SystemSet) -> Self {
         Self::System {
             next: None,
+            id,
             systems,
         }
     }
@@ -204,10 +331,10 @@ impl Node {
     }
 
     #[inline]
-    fn run_local(&self, world: WorldCellComplete) {
+    fn run_local(&self, world: WorldCellComplete, stuck: Option<&StuckReport>) {
         match self {
             Self::System { systems, .. } => systems.run_local(world),
-            Self::Sync { barrier, .. } => barrier.wait(),
+            Self::Sync { barrier, .. } => barrier.wait_reporting(stuck),
         }
     }
 
@@ -229,17 +356,76 @@ impl Node {
 
 #[derive(Debug, Clone)]
 pub struct SyncPoint {
-    barrier: Arc<Barrier>,
+    inner: Arc<SyncInner>,
+}
+
+#[derive(Debug)]
+struct SyncInner {
+    threads: usize,
+    // (threads arrived, generation)
+    state: Mutex<(usize, u64)>,
+    cvar: Condvar,
 }
 
 impl SyncPoint {
     fn new(threads: usize) -> Self {
         Self {
-            barrier: Arc::new(Barrier::new(threads)),
+            inner: Arc::new(SyncInner {
+                threads,
+                state: Mutex::new((0, 0)),
+                cvar: Condvar::new(),
+            }),
         }
     }
 
+    #[inline]
     fn wait(&self) {
-        let _ = self.barrier.wait();
+        self.wait_reporting(None);
+    }
+
+    /// Like a `Barrier`, but reports the threads still missing once, if they don't arrive within the timeout.
+    // the guard is held while waiting on the condvar
+    #[allow(clippy::significant_drop_tightening)]
+    fn wait_reporting(&self, stuck: Option<&StuckReport>) {
+        let inner = &*self.inner;
+        let mut state = inner.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let generation = state.1;
+        state.0 += 1;
+
+        if state.0 == inner.threads {
+            // last thread to arrive releases all others
+            *state = (0, generation.wrapping_add(1));
+            drop(state);
+            inner.cvar.notify_all();
+            return;
+        }
+
+        let mut stuck = stuck.map(|stuck| (Instant::now() + stuck.timeout, stuck));
+        while state.1 == generation {
+            let Some((deadline, report)) = stuck else {
+                state = inner
+                    .cvar
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+                continue;
+            };
+
+            let now = Instant::now();
+            if now < deadline {
+                state = inner
+                    .cvar
+                    .wait_timeout(state, deadline - now)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0;
+                continue;
+            }
+
+            // report once without holding the lock, then keep waiting
+            let pending = inner.threads - state.0;
+            drop(state);
+            report.report(pending);
+            stuck = None;
+            state = inner.state.lock().unwrap_or_else(PoisonError::into_inner);
+        }
     }
 }
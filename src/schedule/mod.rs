@@ -1,22 +1,35 @@
 mod builder;
+mod condition;
 mod graph;
 
+use builder::GraphBuilder;
 use graph::ExecutionGraph;
 
 pub use builder::ScheduleBuilder;
+pub use condition::{
+    RunCondition, global_resource_changed, global_resource_equals, global_resource_exists,
+    resource_changed, resource_equals, resource_exists,
+};
+
+use std::{any::TypeId, time::Duration};
 
 use crate::{
     World,
     cells::{WorldCellComplete, WorldCellSend, split_world},
     filter::FilterType,
     macros::catch_system_failure,
-    system::{ParamType, StoredSystem},
+    system::{InitFn, NamedSystem, ParamType, StoredSystem},
     thread_pool::ThreadPool,
 };
 
 #[cfg_attr(feature = "debug-utils", derive(Debug))]
 pub struct Schedule {
+    // pool threads + main thread
+    thread_count: usize,
+    max_tail: usize,
     thread_pool: ThreadPool,
+    fixed_timestep: Option<Duration>,
+    on_stuck: Option<StuckFn>,
 
     pub(crate) setup: Stage,
     pub(crate) pre_update: Stage,
@@ -26,87 +39,485 @@ pub struct Schedule {
 }
 
 impl Schedule {
+    #[inline]
     pub fn run(&self, world: &mut World) {
-        let (complete, send) = split_world(world);
+        self.run_stages(world, 1);
+    }
 
-        complete.borrow_mut().apply_commands();
+    /// Runs the `Update` stage at the fixed timestep set by `ScheduleBuilder::set_fixed_timestep`.
+    ///
+    /// Adds `dt` to the `accumulator` and runs `Update` once per full timestep in it (possibly zero times),
+    /// keeping the remainder for the next call. `PreUpdate` and `PostUpdate` still run once.
+    /// Returns how often `Update` ran.
+    ///
+    /// # Panics
+    /// - if no fixed timestep was set
+    pub fn run_fixed(&self, world: &mut World, dt: Duration, accumulator: &mut Duration) -> usize {
+        let Some(timestep) = self.fixed_timestep else {
+            panic!("Schedule::run_fixed requires ScheduleBuilder::set_fixed_timestep");
+        };
+
+        *accumulator += dt;
+
+        let mut steps = 0;
+        while *accumulator >= timestep {
+            *accumulator -= timestep;
+            steps += 1;
+        }
 
-        // start
-        self.pre_update
-            .run(complete.clone(), send.clone(), &self.thread_pool);
+        self.run_stages(world, steps);
 
-        complete.borrow_mut().apply_commands();
+        steps
+    }
 
-        // update
-        self.update
-            .run(complete.clone(), send.clone(), &self.thread_pool);
+    fn run_stages(&self, world: &mut World, update_runs: usize) {
+        world.clear_trackers();
+        world.apply_commands();
 
-        complete.borrow_mut().apply_commands();
+        let stages = [
+            (&self.pre_update, 1),
+            (&self.update, update_runs),
+            (&self.post_update, 1),
+        ];
 
-        // finish
-        self.post_update
-            .run(complete.clone(), send.clone(), &self.thread_pool);
+        for (stage, _) in stages {
+            stage.init(world);
+        }
 
-        complete.borrow_mut().apply_commands();
-    }
+        // nothing to run, don't split the world
+        if stages
+            .iter()
+            .all(|(stage, runs)| stage.is_empty() || *runs == 0)
+        {
+            return;
+        }
 
-    pub fn run_setup(&self, world: &mut World) {
+        world.pin_scene(true);
         let (complete, send) = split_world(world);
 
-        self.setup.run(complete.clone(), send, &self.thread_pool);
+        // start, update, finish
+        for (stage, runs) in stages {
+            // empty stages can't issue any commands, but still flush the ones of earlier stages
+            if stage.is_empty() {
+                if stage.apply_commands && runs > 0 {
+                    complete.borrow_mut().apply_commands();
+                }
+                continue;
+            }
 
-        complete.borrow_mut().apply_commands();
+            for _ in 0..runs {
+                stage.run(
+                    complete.clone(),
+                    send.clone(),
+                    &self.thread_pool,
+                    self.on_stuck,
+                );
+                Self::recover_borrow(&complete);
+
+                // commands are kept until the next flushing stage (or run)
+                if stage.apply_commands {
+                    complete.borrow_mut().apply_commands();
+                }
+            }
+        }
+
+        world.pin_scene(false);
+    }
+
+    pub fn run_setup(&self, world: &mut World) {
+        self.run_single(&self.setup, world);
     }
 
     pub fn run_shutdown(&self, world: &mut World) {
+        self.run_single(&self.shutdown, world);
+    }
+
+    /// Runs only the systems of stage `S`, e.g. `Update` in a test or a custom main loop.
+    ///
+    /// Pending commands are applied before and after the stage.
+    pub fn run_stage<S: SystemStage>(&self, world: &mut World) {
+        world.apply_commands();
+        self.run_single(self.stage(TypeId::of::<S>()), world);
+    }
+
+    fn stage(&self, stage_id: TypeId) -> &Stage {
+        match stage_id {
+            id if id == TypeId::of::<Setup>() => &self.setup,
+            id if id == TypeId::of::<PreUpdate>() => &self.pre_update,
+            id if id == TypeId::of::<Update>() => &self.update,
+            id if id == TypeId::of::<PostUpdate>() => &self.post_update,
+            id if id == TypeId::of::<Shutdown>() => &self.shutdown,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Removes a system (set) added with `ScheduleBuilder::add_system_with_handle`.
+    ///
+    /// Rebuilds the graph of the affected stage. Returns `false`, if the system was already removed.
+    pub fn remove_system(&mut self, handle: SystemHandle) -> bool {
+        let stages = [
+            &mut self.setup,
+            &mut self.pre_update,
+            &mut self.update,
+            &mut self.post_update,
+            &mut self.shutdown,
+        ];
+
+        let Some(stage) = stages
+            .into_iter()
+            .find(|stage| stage.systems.contains(handle.0))
+        else {
+            return false;
+        };
+
+        let mut sets = std::mem::take(&mut stage.systems).into_sets();
+        sets.retain(|(id, _)| *id != handle.0);
+
+        stage.inits = Stage::collect_inits(&sets);
+
+        let mut graph_builder = GraphBuilder::new(self.thread_count, self.max_tail);
+        stage.systems = graph_builder.build_graph_from(sets);
+
+        true
+    }
+
+    /// Checks that every stage graph has exactly one root per thread (including the main thread).
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        if self.thread_count != self.thread_pool.thread_count() + 1 {
+            return Err(ScheduleError::ThreadCountMismatch {
+                roots: self.thread_count,
+                threads: self.thread_pool.thread_count() + 1,
+            });
+        }
+
+        let stages = [
+            &self.setup,
+            &self.pre_update,
+            &self.update,
+            &self.post_update,
+            &self.shutdown,
+        ];
+
+        for stage in stages {
+            // empty stages don't have any roots
+            if stage.is_empty() {
+                continue;
+            }
+
+            let roots = stage.systems.node_tree.len();
+            if roots != self.thread_count {
+                return Err(ScheduleError::ThreadCountMismatch {
+                    roots,
+                    threads: self.thread_count,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns how the systems of every stage are spread over the threads, in execution order.
+    ///
+    /// Only reads the built graphs, e.g. to spot stages where `max_tail` limits parallelism.
+    pub fn stage_stats(&self) -> Vec<StageStats> {
+        [
+            ("Setup", &self.setup),
+            ("PreUpdate", &self.pre_update),
+            ("Update", &self.update),
+            ("PostUpdate", &self.post_update),
+            ("Shutdown", &self.shutdown),
+        ]
+        .into_iter()
+        .map(|(name, stage)| stage.systems.stats(name))
+        .collect()
+    }
+
+    /// Releases borrows left behind by a failed system, so later stages can still access the world.
+    fn recover_borrow(complete: &WorldCellComplete) {
+        // # SAFETY
+        // The stage has finished, every system (and with it every guard) is done.
+        let leaked = unsafe { complete.reset_borrow() };
+
+        #[cfg(feature = "debug-utils")]
+        if leaked {
+            #[cfg(feature = "log")]
+            {
+                log::warn!("Stage left the world borrowed, borrow was reset")
+            }
+
+            #[cfg(not(feature = "log"))]
+            {
+                println!("[WARN] Stage left the world borrowed, borrow was reset")
+            }
+        }
+
+        #[cfg(not(feature = "debug-utils"))]
+        let _ = leaked;
+    }
+
+    fn run_single(&self, stage: &Stage, world: &mut World) {
+        stage.init(world);
+
+        if stage.is_empty() {
+            world.apply_commands();
+            return;
+        }
+
+        world.pin_scene(true);
         let (complete, send) = split_world(world);
 
-        self.shutdown.run(complete.clone(), send, &self.thread_pool);
+        stage.run(complete.clone(), send, &self.thread_pool, self.on_stuck);
+        Self::recover_borrow(&complete);
 
         complete.borrow_mut().apply_commands();
+        world.pin_scene(false);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Systems of a stage, that did not finish within the join timeout, see `ScheduleBuilder::on_stuck`.
+pub struct StuckSystems {
+    /// Number of threads still running systems.
+    pub pending: usize,
+    /// The timeout set by `ScheduleBuilder::set_join_timeout`.
+    pub timeout: Duration,
+}
+
+/// Called with the stuck systems of a stage, see `ScheduleBuilder::on_stuck`.
+pub type StuckFn = fn(StuckSystems);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How the systems of a stage are spread over the threads, see `Schedule::stage_stats`.
+pub struct StageStats {
+    pub stage: &'static str,
+    /// Number of points where all threads wait for each other.
+    pub sync_points: usize,
+    /// Most systems (sets) run by a single thread.
+    pub max_systems_per_thread: usize,
+    /// Fewest systems (sets) run by a single thread.
+    pub min_systems_per_thread: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Identifies a system (set) added to a `ScheduleBuilder`, to remove it from the built `Schedule`.
+pub struct SystemHandle(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// An error returned when building a `Schedule` fails.
+pub enum ScheduleError {
+    /// The number of root nodes of a stage graph doesn't match the number of threads (including the main thread).
+    ThreadCountMismatch { roots: usize, threads: usize },
+}
+
+impl std::error::Error for ScheduleError {}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ThreadCountMismatch { roots, threads } => write!(
+                f,
+                "Stage graph has {roots} root nodes, but the schedule runs on {threads} threads"
+            ),
+        }
     }
 }
 
-#[derive(Default)]
 #[cfg_attr(feature = "debug-utils", derive(Debug))]
 pub struct Stage {
     pub(crate) systems: ExecutionGraph,
+    pub(crate) apply_commands: bool,
+    // parameter initializers of all systems
+    pub(crate) inits: Vec<InitFn>,
 }
 
 impl Stage {
-    pub fn run(&self, complete: WorldCellComplete, send: WorldCellSend, pool: &ThreadPool) {
+    pub(crate) fn collect_inits(sets: &[(usize, SystemSet)]) -> Vec<InitFn> {
+        let mut inits = Vec::new();
+        for (_, set) in sets {
+            set.collect_inits(&mut inits);
+        }
+
+        inits
+    }
+
+    #[inline]
+    fn init(&self, world: &mut World) {
+        for init in &self.inits {
+            init(world);
+        }
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    pub fn run(
+        &self,
+        complete: WorldCellComplete,
+        send: WorldCellSend,
+        pool: &ThreadPool,
+        on_stuck: Option<StuckFn>,
+    ) {
         // run this stages systems
-        self.systems.run(complete.clone(), send.clone(), pool);
+        self.systems
+            .run(complete.clone(), send.clone(), pool, on_stuck);
     }
 }
 
 pub trait IntoSystemSet<Marker> {
     fn into_set(self) -> SystemSet;
+
+    #[inline]
+    /// Only runs this set, if `condition` holds.
+    fn run_if(self, condition: RunCondition) -> SystemSet
+    where
+        Self: Sized,
+    {
+        self.into_set().run_if(condition)
+    }
+
+    #[inline]
+    /// Labels this system, so it can be referred to by other systems.
+    fn label(self, label: SystemLabel) -> SystemSet
+    where
+        Self: Sized,
+    {
+        self.into_set().label(label)
+    }
+
+    #[inline]
+    /// Names this system, e.g. for closures, shown instead of its type name in logs and diagnostics.
+    fn named(self, name: &'static str) -> SystemSet
+    where
+        Self: Sized,
+    {
+        self.into_set().named(name)
+    }
 }
 
-pub enum SystemSet {
-    Single { system: StoredSystem },
+impl IntoSystemSet<()> for SystemSet {
+    #[inline]
+    fn into_set(self) -> SystemSet {
+        self
+    }
+}
 
-    Chained { systems: Box<[StoredSystem]> },
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A name given to a single system, see `IntoSystemSet::label`.
+pub struct SystemLabel(pub &'static str);
+
+pub enum SystemSet {
+    Single {
+        system: StoredSystem,
+        label: Option<SystemLabel>,
+    },
+
+    Chained {
+        systems: Box<[StoredSystem]>,
+    },
+
+    // has to run on the main thread, without any other system running concurrently
+    Exclusive {
+        set: Box<Self>,
+    },
+
+    // only runs, if the condition holds
+    Conditional {
+        set: Box<Self>,
+        condition: RunCondition,
+    },
 }
 
 #[cfg(feature = "debug-utils")]
 impl std::fmt::Debug for SystemSet {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Single { system } => f.debug_struct("Single").field("system", system).finish(),
+            Self::Single { system, label } => f
+                .debug_struct("Single")
+                .field("system", system)
+                .field("label", label)
+                .finish(),
             Self::Chained { systems } => {
                 f.debug_struct("Chained").field("systems", systems).finish()
             }
+            Self::Exclusive { set } => f.debug_struct("Exclusive").field("set", set).finish(),
+            Self::Conditional { set, condition } => f
+                .debug_struct("Conditional")
+                .field("set", set)
+                .field("condition", condition)
+                .finish(),
         }
     }
 }
 
 impl SystemSet {
+    #[inline]
+    /// Marks this set as exclusive.
+    ///
+    /// An exclusive set is treated like a system requiring `&mut World`.
+    pub fn exclusive(self) -> Self {
+        match self {
+            Self::Exclusive { .. } => self,
+            set => Self::Exclusive { set: Box::new(set) },
+        }
+    }
+
+    /// Labels the system of this set.
+    ///
+    /// # Panics
+    /// Panics if the set contains multiple systems.
+    pub fn label(self, label: SystemLabel) -> Self {
+        match self {
+            Self::Single { system, .. } => Self::Single {
+                system,
+                label: Some(label),
+            },
+            Self::Chained { .. } => panic!("Only single systems can be labeled: {label:?}"),
+            Self::Exclusive { set } => Self::Exclusive {
+                set: Box::new(set.label(label)),
+            },
+            Self::Conditional { set, condition } => Self::Conditional {
+                set: Box::new(set.label(label)),
+                condition,
+            },
+        }
+    }
+
+    /// Names the system of this set, replacing its type name in logs and diagnostics.
+    ///
+    /// # Panics
+    /// Panics if the set contains multiple systems.
+    pub fn named(self, name: &'static str) -> Self {
+        match self {
+            Self::Single { system, label } => Self::Single {
+                system: Box::new(NamedSystem::new(system, name)),
+                label,
+            },
+            Self::Chained { .. } => panic!("Only single systems can be named: {name}"),
+            Self::Exclusive { set } => Self::Exclusive {
+                set: Box::new(set.named(name)),
+            },
+            Self::Conditional { set, condition } => Self::Conditional {
+                set: Box::new(set.named(name)),
+                condition,
+            },
+        }
+    }
+
+    #[inline]
+    /// Only runs this set, if `condition` holds.
+    pub fn run_if(self, condition: RunCondition) -> Self {
+        Self::Conditional {
+            set: Box::new(self),
+            condition,
+        }
+    }
+
     pub fn run(&self, world: WorldCellSend) {
         match self {
             // single system
-            Self::Single { system } => {
+            Self::Single { system, .. } => {
                 #[cfg(feature = "debug-utils")]
                 catch_system_failure!(system.run(world), system.name());
 
@@ -124,13 +535,24 @@ impl SystemSet {
                     catch_system_failure!(system.run(world.clone()));
                 }
             }
+
+            // exclusive sets always run on the main thread
+            Self::Exclusive { .. } => unreachable!(),
+
+            Self::Conditional { set, condition } => {
+                let run = condition.check(world.borrow().send_world());
+
+                if run {
+                    set.run(world);
+                }
+            }
         }
     }
 
     pub fn run_local(&self, world: WorldCellComplete) {
         match self {
             // single system
-            Self::Single { system } => {
+            Self::Single { system, .. } => {
                 #[cfg(feature = "debug-utils")]
                 catch_system_failure!(system.run_on_main(world), system.name());
 
@@ -148,17 +570,57 @@ impl SystemSet {
                     catch_system_failure!(system.run_on_main(world.clone()));
                 }
             }
+
+            Self::Exclusive { set } => set.run_local(world),
+
+            Self::Conditional { set, condition } => {
+                let run = condition.check(world.borrow().send_world());
+
+                if run {
+                    set.run_local(world);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "debug-utils")]
+    /// Returns the names of all systems in this set.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Single { system, .. } => system.name().to_owned(),
+            Self::Chained { systems } => {
+                let names = systems
+                    .iter()
+                    .map(|system| system.name())
+                    .collect::<Vec<_>>();
+                format!("({})", names.join(", "))
+            }
+            Self::Exclusive { set } | Self::Conditional { set, .. } => set.name(),
+        }
+    }
+
+    fn collect_inits(&self, out: &mut Vec<InitFn>) {
+        match self {
+            Self::Single { system, .. } => out.extend(system.get_inits()),
+            Self::Chained { systems } => {
+                for system in systems {
+                    out.extend(system.get_inits());
+                }
+            }
+            Self::Exclusive { set } | Self::Conditional { set, .. } => set.collect_inits(out),
         }
     }
 
     pub fn get_info(&self) -> SetInfo {
         match self {
-            Self::Single { system } => SetInfo {
+            Self::Single { system, label } => SetInfo {
                 local: system.local(),
                 systems: vec![SystemInfo {
                     types: system.get_types(),
                     filter: system.get_filter(),
+                    exclusive: false,
                 }],
+                label: *label,
             },
             Self::Chained { systems } => {
                 let mut vec = Vec::with_capacity(systems.len());
@@ -169,6 +631,7 @@ impl SystemSet {
                     vec.push(SystemInfo {
                         types: system.get_types(),
                         filter: system.get_filter(),
+                        exclusive: false,
                     });
 
                     local |= system.local();
@@ -177,7 +640,28 @@ impl SystemSet {
                 SetInfo {
                     systems: vec,
                     local,
+                    label: None,
+                }
+            }
+            Self::Exclusive { set } => {
+                let mut info = set.get_info();
+
+                for system in &mut info.systems {
+                    system.exclusive = true;
                 }
+                info.local = true;
+
+                info
+            }
+            Self::Conditional { set, condition } => {
+                let mut info = set.get_info();
+
+                // the condition reads the world, before any of the systems run
+                for system in &mut info.systems {
+                    system.types.extend_from_slice(condition.types());
+                }
+
+                info
             }
         }
     }
@@ -188,6 +672,7 @@ impl SystemSet {
 pub struct SetInfo {
     systems: Vec<SystemInfo>,
     local: bool,
+    label: Option<SystemLabel>,
 }
 
 impl SetInfo {
@@ -196,6 +681,20 @@ impl SetInfo {
         self.local
     }
 
+    #[inline]
+    /// Returns the label of a single system, if it has one.
+    pub const fn label(&self) -> Option<SystemLabel> {
+        self.label
+    }
+
+    #[inline]
+    /// Returns `true`, if the set requires exclusive world access, so nothing can run in parallel to it.
+    pub fn exclusive(&self) -> bool {
+        self.systems.iter().any(|system| {
+            system.exclusive || system.types.iter().any(|t| matches!(t, ParamType::World))
+        })
+    }
+
     #[inline]
     pub fn conflicts(&self, other: &Self) -> bool {
         for system_a in &self.systems {
@@ -215,14 +714,22 @@ impl SetInfo {
 struct SystemInfo {
     types: Vec<ParamType>,
     filter: Vec<FilterType>,
+
+    /// Marks the system to conflict with every other system, like `&mut World`.
+    exclusive: bool,
 }
 
 impl SystemInfo {
     #[inline]
     fn conflicts(&self, other: &Self) -> bool {
+        if self.exclusive || other.exclusive {
+            return true;
+        }
+
         for type_a in &self.types {
             for type_b in &other.types {
                 if type_a.conflicts(type_b) {
+                    // filters can't narrow down world access
                     if type_a.is_world() || type_b.is_world() {
                         return true;
                     }
@@ -243,7 +750,7 @@ impl SystemInfo {
 
 // ################ Stages #####################
 
-pub trait SystemStage: 'static {}
+pub trait SystemStage: std::any::Any {}
 
 pub struct Setup;
 impl SystemStage for Setup {}
@@ -5,7 +5,7 @@ use std::{
 
 use crate::{
     World,
-    cells::{WorldCellComplete, WorldCellSend},
+    cells::{AtomicRefCell, MutGuard, WorldCellComplete, WorldCellSend},
     filter::FilterType,
     world::SendWorld,
 };
@@ -13,6 +13,48 @@ use crate::{
 pub struct FunctionSystem<Input, F> {
     pub(crate) f: F,
     pub(crate) marker: PhantomData<fn() -> Input>,
+
+    // per-system state of each parameter (by position), see `Local`
+    pub(crate) locals: Box<[Option<LocalState>]>,
+}
+
+/// The type erased per-system state of a parameter, see `SystemParam::init_local`.
+pub type LocalState = AtomicRefCell<Box<dyn Any + Send + Sync>>;
+
+/// Private state of a single system, persisting across runs.
+///
+/// Owned by the system (not the world), initialized with `T::default()` when the system is created.
+pub struct Local<'a, T: Default + Send + Sync + 'static> {
+    guard: MutGuard<'a, Box<dyn Any + Send + Sync>>,
+    _p: PhantomData<T>,
+}
+
+impl<'a, T: Default + Send + Sync + 'static> Local<'a, T> {
+    #[inline]
+    pub(crate) fn new(guard: MutGuard<'a, Box<dyn Any + Send + Sync>>) -> Self {
+        debug_assert!(guard.is::<T>());
+
+        Self {
+            guard,
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<T: Default + Send + Sync + 'static> std::ops::Deref for Local<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.guard.downcast_ref::<T>().unwrap_unchecked() }
+    }
+}
+
+impl<T: Default + Send + Sync + 'static> std::ops::DerefMut for Local<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.guard.downcast_mut::<T>().unwrap_unchecked() }
+    }
 }
 
 /// A trait allowing implementers to be called while automaticly extracting the needed parameters from a `World`.
@@ -38,6 +80,47 @@ pub trait System: Send + Sync {
     fn run(&self, world: WorldCellSend) -> Result<(), ()>;
 
     fn run_on_main(&self, world: WorldCellComplete) -> Result<(), ()>;
+
+    #[inline]
+    /// Returns the initializers of all parameters, run by the `Schedule` before any stage runs.
+    fn get_inits(&self) -> Vec<InitFn> {
+        Vec::new()
+    }
+}
+
+/// Prepares the `World` for a system parameter, see `SystemParam::init`.
+pub type InitFn = fn(&mut World);
+
+/// Marks systems returning a `Result`, see `SystemOutput`.
+pub struct Fallible<E>(PhantomData<E>);
+
+/// A trait for the return types of systems, handled after the system ran.
+pub trait SystemOutput {
+    fn handle(self, system: &'static str);
+}
+
+impl SystemOutput for () {
+    #[inline]
+    fn handle(self, _: &'static str) {}
+}
+
+impl<E: std::fmt::Debug> SystemOutput for Result<(), E> {
+    #[inline]
+    fn handle(self, system: &'static str) {
+        let Err(err) = self else {
+            return;
+        };
+
+        #[cfg(feature = "log")]
+        {
+            log::error!("System failed: {system}: {err:?}")
+        }
+
+        #[cfg(not(feature = "log"))]
+        {
+            println!("[ERROR] System failed: {system}: {err:?}")
+        }
+    }
 }
 
 /// A trait to transform a implementer into a `System`.
@@ -50,6 +133,58 @@ pub trait IntoSystem<Input> {
 /// A boxed and type erased system.
 pub type StoredSystem = Box<dyn System>;
 
+/// A system reporting a given name instead of its type name, see `SystemSet::named`.
+pub struct NamedSystem {
+    system: StoredSystem,
+    #[cfg_attr(not(feature = "debug-utils"), allow(dead_code))]
+    name: &'static str,
+}
+
+impl NamedSystem {
+    #[inline]
+    pub fn new(system: StoredSystem, name: &'static str) -> Self {
+        Self { system, name }
+    }
+}
+
+impl System for NamedSystem {
+    #[inline]
+    fn get_types(&self) -> Vec<ParamType> {
+        self.system.get_types()
+    }
+
+    #[inline]
+    fn get_filter(&self) -> Vec<FilterType> {
+        self.system.get_filter()
+    }
+
+    #[inline]
+    fn local(&self) -> bool {
+        self.system.local()
+    }
+
+    #[cfg(feature = "debug-utils")]
+    #[inline]
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    #[inline]
+    fn run(&self, world: WorldCellSend) -> Result<(), ()> {
+        self.system.run(world)
+    }
+
+    #[inline]
+    fn run_on_main(&self, world: WorldCellComplete) -> Result<(), ()> {
+        self.system.run_on_main(world)
+    }
+
+    #[inline]
+    fn get_inits(&self) -> Vec<InitFn> {
+        self.system.get_inits()
+    }
+}
+
 #[cfg(feature = "debug-utils")]
 impl std::fmt::Debug for dyn System {
     #[inline]
@@ -78,13 +213,130 @@ pub trait SystemParam {
         Vec::new()
     }
 
+    #[inline]
+    /// Returns a function preparing the `World` for this parameter, e.g. inserting a missing resource.
+    ///
+    /// It is called with exclusive access at the start of every `Schedule::run`, before any stage runs.
+    fn init() -> Option<InitFn> {
+        None
+    }
+
+    #[inline]
+    /// Creates the per-system state of this parameter, owned by the system using it, see `Local`.
+    fn init_local() -> Option<LocalState> {
+        None
+    }
+
     /// Retrives the implemented type from a `World`.
     fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>>;
 
+    #[inline]
+    /// Retrives the implemented type from a `World` and its per-system state, created by `init_local`.
+    fn retrieve_with_local<'a>(
+        world: SendWorld<'a>,
+        _local: Option<&'a LocalState>,
+    ) -> Option<Self::Item<'a>> {
+        Self::retrieve(world)
+    }
+
     #[inline]
     fn retrieve_local(_: &World) -> Option<Self::Item<'_>> {
         unimplemented!()
     }
+
+    #[inline]
+    /// Retrives the implemented type on the main thread, together with its per-system state, created by `init_local`.
+    fn retrieve_local_with_local<'a>(
+        world: &'a World,
+        _local: Option<&'a LocalState>,
+    ) -> Option<Self::Item<'a>> {
+        Self::retrieve_local(world)
+    }
+
+    #[inline]
+    /// Specifies if a failed retrieval is expected (e.g. a query without matching entities).
+    ///
+    /// Otherwise, the system skipped because of this parameter is reported under `debug-utils`.
+    fn skip_silently() -> bool {
+        false
+    }
+}
+
+/// The per-system state of a parameter made of other parameters, one entry per field, see `nested_local`.
+pub type NestedLocalState = Box<[Option<LocalState>]>;
+
+/// Combines the per-system state of the fields of a parameter, e.g. one created by `#[derive(SystemParam)]`.
+///
+/// Returns `None`, if no field has any state.
+pub fn nested_local(fields: Vec<Option<LocalState>>) -> Option<LocalState> {
+    if fields.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let fields: NestedLocalState = fields.into_boxed_slice();
+    Some(AtomicRefCell::new(Box::new(fields)))
+}
+
+/// Returns the per-system state of the field at `index`, out of the state created by `nested_local`.
+pub fn nested_local_field(local: Option<&LocalState>, index: usize) -> Option<&LocalState> {
+    // # SAFETY
+    // The combined state is never borrowed mutably, each field tracks the borrows of its own state.
+    let fields = unsafe { local?.get_unchecked() };
+
+    fields
+        .downcast_ref::<NestedLocalState>()?
+        .get(index)?
+        .as_ref()
+}
+
+/// Reports a field of a parameter made of other parameters, if it is missing, see `retrieved`.
+#[inline]
+pub fn retrieved_field<P: SystemParam, T>(item: Option<T>, param: &'static str) -> Option<T> {
+    retrieved::<P, T>(item, param).ok()
+}
+
+/// Turns a retrieved parameter into a `Result`, reporting the parameter if it is missing.
+#[inline]
+pub fn retrieved<P: SystemParam, T>(item: Option<T>, system: &'static str) -> Result<T, ()> {
+    let Some(item) = item else {
+        #[cfg(feature = "debug-utils")]
+        if let Some(message) = missing_param_message::<P>(system) {
+            #[cfg(feature = "log")]
+            {
+                log::warn!("{message}")
+            }
+
+            #[cfg(not(feature = "log"))]
+            {
+                println!("[WARN] {message}")
+            }
+        }
+
+        #[cfg(not(feature = "debug-utils"))]
+        let _ = system;
+
+        return Err(());
+    };
+
+    Ok(item)
+}
+
+#[cfg(feature = "debug-utils")]
+fn missing_param_message<P: SystemParam>(system: &'static str) -> Option<String> {
+    if P::skip_silently() {
+        return None;
+    }
+
+    let types = P::get_types()
+        .iter()
+        .map(ParamType::name)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Some(format!(
+        "System [{system}] skipped, parameter [{}] with [{types}] could not be retrieved",
+        std::any::type_name::<P>()
+    ))
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -102,6 +354,8 @@ pub enum ParamType {
     ),
 
     World,
+    // read-only access to the whole world
+    WorldShared,
 }
 
 #[cfg(feature = "debug-utils")]
@@ -111,6 +365,7 @@ impl std::fmt::Debug for ParamType {
             Self::Mut(_, arg1) => f.debug_tuple("Mut").field(arg1).finish(),
             Self::Shared(_, arg1) => f.debug_tuple("Shared").field(arg1).finish(),
             Self::World => write!(f, "World"),
+            Self::WorldShared => write!(f, "WorldShared"),
         }
     }
 }
@@ -139,16 +394,16 @@ impl ParamType {
     }
 
     #[inline]
-    pub fn raw_type(&self) -> TypeId {
+    pub const fn raw_type(&self) -> TypeId {
         match self {
             Self::Mut(type_id, _) | Self::Shared(type_id, _) => *type_id,
-            Self::World => TypeId::of::<World>(),
+            Self::World | Self::WorldShared => TypeId::of::<World>(),
         }
     }
 
     #[inline]
     pub const fn is_world(&self) -> bool {
-        matches!(self, Self::World)
+        matches!(self, Self::World | Self::WorldShared)
     }
 
     #[cfg(feature = "debug-utils")]
@@ -156,6 +411,7 @@ impl ParamType {
         match self {
             Self::Mut(_, name) | Self::Shared(_, name) => name,
             Self::World => std::any::type_name::<World>(),
+            Self::WorldShared => std::any::type_name::<&World>(),
         }
     }
 
@@ -168,9 +424,18 @@ impl ParamType {
 
             (Self::Shared(..), Self::Shared(..)) => false,
             (Self::World, _) | (_, Self::World) => true,
+
+            // reading the world only conflicts with writes
+            (Self::WorldShared, Self::Mut(..)) | (Self::Mut(..), Self::WorldShared) => true,
+            (Self::WorldShared, Self::Shared(..) | Self::WorldShared)
+            | (Self::Shared(..), Self::WorldShared) => false,
         }
     }
 
+    /// Panics, if two parameters conflict, e.g. `(Res<A>, ResMut<A>)`.
+    ///
+    /// Parameters for different types, e.g. `(Res<A>, ResMut<B>)`, never conflict.
+    /// The panic message names the conflicting type.
     #[cfg(feature = "runtime-checks")]
     pub fn validate(params: &[&[Self]]) {
         {
@@ -187,7 +452,10 @@ impl ParamType {
                 for (i, a) in param.iter().enumerate() {
                     for (j, b) in param.iter().enumerate() {
                         if i != j && a.conflicts(b) {
-                            panic!("Invalid parameter combination: [{a:?}] conflicts with [{b:?}]");
+                            panic!(
+                                "Invalid parameter combination: {}",
+                                Self::conflict_message(a, b)
+                            );
                         }
                     }
                 }
@@ -196,7 +464,10 @@ impl ParamType {
                 for a in &set {
                     for b in *param {
                         if a.conflicts(b) {
-                            panic!("Invalid parameter combination: [{a:?}] conflicts with [{b:?}]");
+                            panic!(
+                                "Invalid parameter combination: {}",
+                                Self::conflict_message(a, b)
+                            );
                         }
                     }
                 }
@@ -205,4 +476,49 @@ impl ParamType {
             }
         }
     }
+
+    #[cfg(feature = "runtime-checks")]
+    fn conflict_message(a: &Self, b: &Self) -> String {
+        match (a, b) {
+            (Self::Mut(..), Self::Mut(..)) => {
+                format!("[{}] is accessed mutably more than once", a.name())
+            }
+            (Self::Mut(..), Self::Shared(..)) | (Self::Shared(..), Self::Mut(..)) => {
+                format!("[{}] is accessed both shared and mutably", a.name())
+            }
+            _ => format!("[{a:?}] conflicts with [{b:?}]"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Commands;
+
+    #[test]
+    #[cfg(feature = "runtime-checks")]
+    #[should_panic(expected = "Invalid parameter combination")]
+    fn test_world_conflicts_with_commands() {
+        ParamType::validate(&[&Commands::get_types(), &[ParamType::World]]);
+    }
+
+    #[test]
+    #[cfg(feature = "debug-utils")]
+    fn test_missing_param_message() {
+        use crate::{Query, Res};
+
+        struct Missing;
+        impl crate::Resource for Missing {}
+
+        let message = missing_param_message::<Res<Missing>>("my_system").unwrap();
+        assert!(message.starts_with("System [my_system] skipped"));
+        assert!(message.contains(std::any::type_name::<Missing>()));
+
+        // no matching entities is expected, so not reported
+        assert!(missing_param_message::<Query<&u32>>("my_system").is_none());
+
+        assert_eq!(retrieved::<Res<Missing>, u32>(None, "my_system"), Err(()));
+        assert_eq!(retrieved::<Res<Missing>, _>(Some(1), "my_system"), Ok(1));
+    }
 }
@@ -28,9 +28,17 @@ pub trait TableIdent {
     fn rows() -> Box<[Row]>;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TableId(u64, u64);
 
+#[cfg(feature = "debug-utils")]
+type TableNames = std::collections::HashMap<TableId, Box<[&'static str]>>;
+
+#[cfg(feature = "debug-utils")]
+/// Sorted component names of every table created so far, see `TableId::describe`.
+static TABLE_NAMES: std::sync::LazyLock<std::sync::RwLock<TableNames>> =
+    std::sync::LazyLock::new(Default::default);
+
 impl TableId {
     #[inline]
     pub const fn invalid() -> Self {
@@ -56,6 +64,43 @@ impl TableId {
 
         builder.finish()
     }
+
+    #[cfg(feature = "debug-utils")]
+    /// Returns the component names of the table with this id, e.g. `Table[Position, Velocity]`.
+    ///
+    /// Returns `None`, if no such table was created yet.
+    pub fn describe(&self) -> Option<String> {
+        let joined = TABLE_NAMES
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(self)?
+            .join(", ");
+
+        Some(format!("Table[{joined}]"))
+    }
+
+    #[cfg(feature = "debug-utils")]
+    fn register(self, rows: &[Row]) {
+        let mut names = rows.iter().map(|row| row.name).collect::<Box<[_]>>();
+        names.sort_unstable();
+
+        TABLE_NAMES
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .entry(self)
+            .or_insert(names);
+    }
+}
+
+impl std::fmt::Display for TableId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        #[cfg(feature = "debug-utils")]
+        if let Some(description) = self.describe() {
+            return f.write_str(&description);
+        }
+
+        write!(f, "TableId({:x}, {:x})", self.0, self.1)
+    }
 }
 
 pub struct TableIdBuilder {
@@ -112,9 +157,15 @@ impl Table {
         #[cfg(feature = "runtime-checks")]
         C::validate();
 
+        let id = C::table_id();
+        let rows = C::rows();
+
+        #[cfg(feature = "debug-utils")]
+        id.register(&rows);
+
         Self {
-            id: C::table_id(),
-            rows: C::rows(),
+            id,
+            rows,
             entities: Vec::new(),
         }
     }
@@ -132,6 +183,23 @@ impl Table {
         self.entities.len()
     }
 
+    /// Clones the table, using `clone_row` for every row.
+    ///
+    /// Returns `None`, if a row can't be cloned.
+    pub fn try_clone(&self, clone_row: impl Fn(&Row) -> Option<Row>) -> Option<Self> {
+        let rows = self
+            .rows
+            .iter()
+            .map(clone_row)
+            .collect::<Option<Box<[Row]>>>()?;
+
+        Some(Self {
+            id: self.id,
+            rows,
+            entities: self.entities.clone(),
+        })
+    }
+
     #[inline]
     pub const fn is_empty(&self) -> bool {
         self.len() == 0
@@ -171,16 +239,9 @@ impl Table {
     pub fn delete_entity(&mut self, entity: Entity) {
         // find entity position
         let position = self.get_entity_position(&entity);
-        let ent = self.entities[position];
 
-        // remove all components
-        for row in &mut self.rows {
-            row.swap_remove(position);
-        }
-
-        // remove entity
-        let removed = self.entities.swap_remove(position);
-        debug_assert_eq!(removed, ent);
+        let removed = self.remove_at(position, None);
+        debug_assert_eq!(removed, entity);
     }
 
     pub fn push_missing_or_update<C: ComponentSet>(&mut self, entity: &Entity, components: C) {
@@ -188,24 +249,23 @@ impl Table {
         C::push_or_update(components, self, position);
     }
 
-    /// Moves an Entity from Self to dst, for every row that self has.
-    pub fn move_entity_up(&mut self, dst: &mut Self, entity: &Entity) {
+    /// Pushes the components the Entity is missing, keeping the already existing ones.
+    pub fn push_missing<C: ComponentSet>(&mut self, entity: &Entity, components: C) {
         let position = self.get_entity_position(entity);
+        C::push_missing(components, self, position);
+    }
 
-        'outer: for src_row in &mut self.rows {
-            for dst_row in &mut dst.rows {
-                if src_row.tid() == dst_row.tid() {
-                    src_row.move_push_entity(dst_row, position);
-                    continue 'outer;
-                }
-            }
+    /// Moves an Entity from Self to dst, for every row that self has.
+    pub fn move_entity_up(&mut self, dst: &mut Self, entity: &Entity) {
+        debug_assert!(
+            self.types().all(|t| dst.contains_one(t)),
+            "dst should have all rows that self has"
+        );
 
-            unreachable!("dst should have all rows that self has");
-        }
+        let position = self.get_entity_position(entity);
 
-        let removed = self.entities.swap_remove(position);
+        let removed = self.remove_at(position, Some(dst));
         debug_assert_eq!(removed, *entity);
-        dst.entities.push(*entity);
     }
 
     /// Moves an Entity from Self to dst, for every row that self has. Dropping Components from rows that are not in dst.
@@ -213,28 +273,52 @@ impl Table {
         // get entity position
         let position = self.get_entity_position(entity);
 
-        //
-        'outer: for current_row in &mut self.rows {
-            for dst_row in &mut dst.rows {
-                // check if there is a same row type to move to
-                if current_row.tid() == dst_row.tid() {
-                    current_row.move_push_entity(dst_row, position);
+        let removed = self.remove_at(position, Some(dst));
+        debug_assert_eq!(removed, *entity);
+    }
 
-                    continue 'outer;
-                }
-            }
+    /// Moves all entities and their components of `other` to the end of Self.
+    ///
+    /// Both tables have to have the same id.
+    pub fn merge(&mut self, mut other: Self) {
+        debug_assert_eq!(self.id, other.id);
 
-            // current row type is not existing in dest table
-            // drop component
-            current_row.swap_remove(position);
+        // taking the last entity never swaps any other entity
+        while let Some(position) = other.len().checked_sub(1) {
+            other.remove_at(position, Some(self));
+        }
+    }
+
+    /// Removes the Entity at `position`, moving its components to `dst` (if it has the same row), or dropping them.
+    ///
+    /// The last Entity is swapped into `position`, every lookup by position has to be fixed up here.
+    fn remove_at(&mut self, position: usize, mut dst: Option<&mut Self>) -> Entity {
+        for row in &mut self.rows {
+            let dst_row = dst.as_deref_mut().and_then(|dst| {
+                dst.rows
+                    .iter_mut()
+                    .find(|dst_row| dst_row.tid() == row.tid())
+            });
+
+            match dst_row {
+                // same row type to move to
+                Some(dst_row) => row.move_push_entity(dst_row, position),
+
+                // drop component
+                None => row.swap_remove(position),
+            }
         }
 
         let removed = self.entities.swap_remove(position);
-        debug_assert_eq!(removed, *entity);
-        dst.entities.push(*entity);
+
+        if let Some(dst) = dst {
+            dst.entities.push(removed);
+        }
+
+        removed
     }
 
-    pub fn try_get_row_ref<C: Component>(&self) -> Result<RowAccessRef<C>, ()> {
+    pub fn try_get_row_ref<C: Component>(&self) -> Result<RowAccessRef<'_, C>, ()> {
         let id = TypeId::of::<C>();
         for row in &self.rows {
             if row.tid() == id {
@@ -245,7 +329,7 @@ impl Table {
         Err(())
     }
 
-    pub fn try_get_row_mut<C: Component>(&self) -> Result<RowAccessMut<C>, ()> {
+    pub fn try_get_row_mut<C: Component>(&self) -> Result<RowAccessMut<'_, C>, ()> {
         let id = TypeId::of::<C>();
         for row in &self.rows {
             if row.tid() == id {
@@ -256,6 +340,51 @@ impl Table {
         Err(())
     }
 
+    pub fn try_get_row_prev<C: Component>(&self) -> Result<RowAccessPrev<'_, C>, ()> {
+        let id = TypeId::of::<C>();
+        for row in &self.rows {
+            if row.tid() == id {
+                return Ok(RowAccessPrev {
+                    current: row.get_access_ref(),
+                    previous: row.previous(),
+                });
+            }
+        }
+
+        Err(())
+    }
+
+    /// Returns the component `C` of the Entity, if this table has a row of `C`.
+    pub fn get_component_mut<C: Component>(&mut self, entity: &Entity) -> Option<&mut C> {
+        let position = self.get_entity_position(entity);
+
+        let id = TypeId::of::<C>();
+        let row = self.rows.iter_mut().find(|row| row.tid() == id)?;
+
+        row.get_mut::<C>().get_mut(position)
+    }
+
+    /// Returns the component `C` of the Entity, if this table has a row of `C`.
+    ///
+    /// # Safety
+    /// The row of `C` may not be mutably borrowed, while the returned reference is alive.
+    pub unsafe fn get_component_unchecked<C: Component>(&self, entity: &Entity) -> Option<&C> {
+        let position = self.get_entity_position(entity);
+
+        let id = TypeId::of::<C>();
+        let row = self.rows.iter().find(|row| row.tid() == id)?;
+
+        unsafe { row.get_unchecked::<C>() }.get(position)
+    }
+
+    /// Snapshots the row of `C`, if this table has one.
+    pub fn snapshot_row<C: Component + Clone>(&mut self) {
+        let id = TypeId::of::<C>();
+        if let Some(row) = self.rows.iter_mut().find(|row| row.tid() == id) {
+            row.snapshot::<C>();
+        }
+    }
+
     #[inline]
     pub const fn id(&self) -> TableId {
         self.id
@@ -266,6 +395,12 @@ impl Table {
         self.rows.iter().map(|row| row.tid())
     }
 
+    #[inline]
+    /// Returns the names of all components of this table, see `Component::name`.
+    pub fn component_type_names(&self) -> impl Iterator<Item = &'static str> {
+        self.rows.iter().map(|row| row.name)
+    }
+
     #[inline]
     pub fn contains_all(&self, types: &[TypeId]) -> bool {
         types.iter().all(|t| {
@@ -309,6 +444,7 @@ impl std::fmt::Debug for Table {
             _ = writeln!(&mut out, "    {} - {:?}: [..]", row.type_name, row.tid());
         }
 
+        _ = writeln!(&mut out, "    len:     {}", self.len());
         _ = writeln!(&mut out, "    ents:    {:?}", self.entities);
         _ = writeln!(
             &mut out,
@@ -325,11 +461,17 @@ pub struct Row {
 
     #[cfg(feature = "debug-utils")]
     type_name: &'static str,
+    name: &'static str,
     components: AtomicRefCell<Box<RowComponent>>,
 
+    // snapshot of the components at the last frame boundary, see `PrevAndCurr`
+    previous: Option<Box<RowComponent>>,
+
     v_clone_empty: fn() -> Self,
-    v_swap_remove: fn(row: &mut Row, position: usize),
-    v_move_entity: fn(src: &mut Row, dst: &mut Row, position: usize),
+    // only set for clonable components, see `Row::new_clonable`
+    v_clone_full: Option<fn(&Self) -> Self>,
+    v_swap_remove: fn(row: &mut Self, position: usize),
+    v_move_entity: fn(src: &mut Self, dst: &mut Self, position: usize),
 }
 
 impl Row {
@@ -341,14 +483,47 @@ impl Row {
             type_id: TypeId::of::<C>(),
             #[cfg(feature = "debug-utils")]
             type_name: std::any::type_name::<C>(),
+            name: C::name(),
             components: AtomicRefCell::new(boxed),
+            previous: None,
 
             v_clone_empty: Self::new::<C>,
+            v_clone_full: None,
             v_swap_remove: Self::v_swap_remove::<C>,
             v_move_entity: Self::v_move_entity::<C>,
         }
     }
 
+    /// Same as `new`, but the row (and every empty clone of it) can be cloned with `try_clone`.
+    pub fn new_clonable<C: Component + Clone>() -> Self {
+        let mut row = Self::new::<C>();
+        row.v_clone_empty = Self::new_clonable::<C>;
+        row.v_clone_full = Some(Self::clone_full::<C>);
+        row
+    }
+
+    /// Clones the row including its components, without the previous values.
+    ///
+    /// Returns `None`, if the row was not created by `new_clonable`.
+    ///
+    /// # Panics
+    /// - if the row is currently borrowed mutably
+    pub fn try_clone(&self) -> Option<Self> {
+        self.v_clone_full.map(|clone| clone(self))
+    }
+
+    /// Clones the row including its components, without the previous values.
+    ///
+    /// # Panics
+    /// - if the row is currently borrowed mutably
+    pub fn clone_full<C: Component + Clone>(&self) -> Self {
+        let components = self.components.borrow();
+
+        let mut row = self.clone_empty();
+        *row.get_mut::<C>() = unwrap!(components.downcast_ref::<Vec<C>>()).clone();
+        row
+    }
+
     pub fn clone_empty(&self) -> Self {
         (self.v_clone_empty)()
     }
@@ -362,11 +537,11 @@ impl Row {
         self.get_mut().push(component);
     }
 
-    #[allow(clippy::debug_assert_with_mut_call)]
     pub fn update<C: Component>(&mut self, position: usize, component: C) {
-        debug_assert!(self.get_mut::<C>().len() > position);
+        let components = self.get_mut::<C>();
+        debug_assert!(components.len() > position);
 
-        self.get_mut().insert(position, component);
+        components[position] = component;
     }
 
     pub fn push_or_update<C: Component>(&mut self, position: usize, component: C) {
@@ -380,11 +555,73 @@ impl Row {
         }
     }
 
+    pub fn push_missing<C: Component>(&mut self, position: usize, component: C) {
+        let components = self.get_mut::<C>();
+
+        // keep existing component
+        if components.len() == position {
+            components.push(component);
+        } else {
+            debug_assert!(components.len() > position);
+        }
+    }
+
     #[inline]
     pub fn get_mut<C: Component>(&mut self) -> &mut Vec<C> {
         unwrap!(self.components.get_mut().downcast_mut::<Vec<C>>())
     }
 
+    #[inline]
+    /// Returns the components, without updating the borrow counter.
+    ///
+    /// # Safety
+    /// The row may not be mutably borrowed, while the returned reference is alive.
+    pub unsafe fn get_unchecked<C: Component>(&self) -> &Vec<C> {
+        unwrap!(unsafe { self.components.get_unchecked() }.downcast_ref::<Vec<C>>())
+    }
+
+    /// Stores a copy of all components, readable as their previous values.
+    pub fn snapshot<C: Component + Clone>(&mut self) {
+        let components = unwrap!(self.components.get_mut().downcast_ref::<Vec<C>>());
+
+        match self.previous.as_mut() {
+            Some(previous) => {
+                let previous = unwrap!(previous.downcast_mut::<Vec<Option<C>>>());
+                previous.clear();
+                previous.extend(components.iter().cloned().map(Some));
+            }
+            None => {
+                let previous = components.iter().cloned().map(Some).collect::<Vec<_>>();
+                self.previous = Some(Box::new(previous));
+            }
+        }
+    }
+
+    #[inline]
+    /// Returns the components of the last snapshot.
+    ///
+    /// Can be shorter than the row, components pushed since then have no previous value.
+    pub fn previous<C: Component>(&self) -> &[Option<C>] {
+        self.previous.as_ref().map_or(&[], |previous| {
+            unwrap!(previous.downcast_ref::<Vec<Option<C>>>()).as_slice()
+        })
+    }
+
+    #[inline]
+    fn previous_mut<C: Component>(&mut self) -> Option<&mut Vec<Option<C>>> {
+        let previous = self.previous.as_mut()?;
+        Some(unwrap!(previous.downcast_mut::<Vec<Option<C>>>()))
+    }
+
+    // mirrors a swap_remove of the components, `len` being the length before removing
+    fn swap_remove_previous<C: Component>(&mut self, len: usize, position: usize) -> Option<C> {
+        let previous = self.previous_mut::<C>()?;
+
+        // components pushed since the last snapshot have no previous value
+        previous.resize_with(len, || None);
+        previous.swap_remove(position)
+    }
+
     #[inline]
     pub fn swap_remove(&mut self, position: usize) {
         (self.v_swap_remove)(self, position)
@@ -396,7 +633,7 @@ impl Row {
     }
 
     #[inline]
-    pub fn get_access_ref<C: Component>(&self) -> RowAccessRef<C> {
+    pub fn get_access_ref<C: Component>(&self) -> RowAccessRef<'_, C> {
         RowAccessRef {
             guard: self.components.borrow(),
             _p: PhantomData,
@@ -404,7 +641,7 @@ impl Row {
     }
 
     #[inline]
-    pub fn get_access_mut<C: Component>(&self) -> RowAccessMut<C> {
+    pub fn get_access_mut<C: Component>(&self) -> RowAccessMut<'_, C> {
         RowAccessMut {
             guard: self.components.borrow_mut(),
             _p: PhantomData,
@@ -413,14 +650,27 @@ impl Row {
 
     fn v_swap_remove<C: Component>(&mut self, position: usize) {
         let vec = unwrap!(self.components.get_mut().downcast_mut::<Vec<C>>());
+        let len = vec.len();
         vec.swap_remove(position);
+
+        _ = self.swap_remove_previous::<C>(len, position);
     }
 
     fn v_move_entity<C: Component>(&mut self, dst: &mut Self, position: usize) {
         debug_assert_eq!(self.tid(), dst.tid());
 
+        let len = self.get_mut::<C>().len();
         let removed = self.get_mut::<C>().swap_remove(position);
+        let previous = self.swap_remove_previous::<C>(len, position);
+
+        let dst_len = dst.get_mut::<C>().len();
         dst.get_mut::<C>().push(removed);
+
+        // keep the previous value, if the destination is tracked as well
+        if let Some(dst_previous) = dst.previous_mut::<C>() {
+            dst_previous.resize_with(dst_len, || None);
+            dst_previous.push(previous);
+        }
     }
 }
 
@@ -448,6 +698,9 @@ impl ExtendableTable {
     pub fn finish(self) -> Table {
         self.check();
 
+        #[cfg(feature = "debug-utils")]
+        self.id.register(&self.rows);
+
         Table {
             id: self.id,
             rows: self.rows.into_boxed_slice(),
@@ -474,6 +727,20 @@ pub struct RowAccessRef<'a, C: Component> {
     _p: PhantomData<C>,
 }
 
+impl<C: Component> RowAccessRef<'_, C> {
+    #[inline]
+    /// Returns the component at `position`, or `None` if out of bounds.
+    pub fn at(&self, position: usize) -> Option<&C> {
+        self.get(position)
+    }
+
+    #[inline]
+    /// Returns the row as `R`, if `R` is the component type of this row.
+    pub fn downcast_row<R: Component>(&self) -> Option<&[R]> {
+        self.guard.downcast_ref::<Vec<R>>().map(Vec::as_slice)
+    }
+}
+
 impl<C: Component> std::ops::Deref for RowAccessRef<'_, C> {
     type Target = [C];
 
@@ -488,6 +755,32 @@ pub struct RowAccessMut<'a, C: Component> {
     _p: PhantomData<C>,
 }
 
+impl<C: Component> RowAccessMut<'_, C> {
+    #[inline]
+    /// Returns the component at `position`, or `None` if out of bounds.
+    pub fn at(&self, position: usize) -> Option<&C> {
+        self.get(position)
+    }
+
+    #[inline]
+    /// Returns the component at `position` mutably, or `None` if out of bounds.
+    pub fn at_mut(&mut self, position: usize) -> Option<&mut C> {
+        self.get_mut(position)
+    }
+
+    #[inline]
+    /// Returns the row as `R`, if `R` is the component type of this row.
+    pub fn downcast_row<R: Component>(&self) -> Option<&[R]> {
+        self.guard.downcast_ref::<Vec<R>>().map(Vec::as_slice)
+    }
+
+    #[inline]
+    /// Returns the row mutably as `R`, if `R` is the component type of this row.
+    pub fn downcast_row_mut<R: Component>(&mut self) -> Option<&mut [R]> {
+        self.guard.downcast_mut::<Vec<R>>().map(Vec::as_mut_slice)
+    }
+}
+
 impl<C: Component> std::ops::Deref for RowAccessMut<'_, C> {
     type Target = [C];
 
@@ -504,7 +797,57 @@ impl<C: Component> std::ops::DerefMut for RowAccessMut<'_, C> {
     }
 }
 
+pub struct RowAccessPrev<'a, C: Component> {
+    current: RowAccessRef<'a, C>,
+    previous: &'a [Option<C>],
+}
+
+impl<C: Component> RowAccessPrev<'_, C> {
+    #[inline]
+    /// Returns the previous and current component at `position`, or `None` if out of bounds.
+    ///
+    /// Components without a snapshot report their current value as previous value.
+    pub fn at(&self, position: usize) -> Option<(&C, &C)> {
+        let current = self.current.at(position)?;
+        let previous = self.previous.get(position).and_then(Option::as_ref);
+
+        Some((previous.unwrap_or(current), current))
+    }
+
+    #[inline]
+    pub fn iter(&self) -> PrevAndCurrIter<'_, C> {
+        PrevAndCurrIter {
+            current: self.current.iter(),
+            previous: self.previous.iter(),
+        }
+    }
+
+    #[inline]
+    /// Returns the row as `R`, if `R` is the component type of this row.
+    pub fn downcast_row<R: Component>(&self) -> Option<&[R]> {
+        self.current.downcast_row()
+    }
+}
+
+pub struct PrevAndCurrIter<'a, C: Component> {
+    current: std::slice::Iter<'a, C>,
+    previous: std::slice::Iter<'a, Option<C>>,
+}
+
+impl<'a, C: Component> Iterator for PrevAndCurrIter<'a, C> {
+    type Item = (&'a C, &'a C);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.next()?;
+        let previous = self.previous.next().and_then(Option::as_ref);
+
+        Some((previous.unwrap_or(current), current))
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::significant_drop_tightening)]
 mod tests {
 
     use std::{any::TypeId, ops::Deref};
@@ -514,7 +857,7 @@ mod tests {
         table::RowAccessRef,
     };
 
-    use super::Table;
+    use super::{Row, Table};
 
     #[test]
     fn test_create_table() {
@@ -530,6 +873,28 @@ mod tests {
         assert_eq!(table.rows[1].type_id, TypeId::of::<i32>());
     }
 
+    #[test]
+    fn test_table_contains_one() {
+        use crate::{With, WithOut, filter::Filter, table::TableId};
+
+        let table = Table::new::<(u32, i32)>();
+        assert!(table.contains_one(TypeId::of::<u32>()));
+        assert!(table.contains_one(TypeId::of::<i32>()));
+        assert!(!table.contains_one(TypeId::of::<u8>()));
+        assert!(With::<u32>::check(&table));
+        assert!(!WithOut::<u32>::check(&table));
+
+        // a table without rows contains nothing
+        let table = Table {
+            id: TableId::invalid(),
+            rows: Box::new([]),
+            entities: Vec::new(),
+        };
+        assert!(!table.contains_one(TypeId::of::<u32>()));
+        assert!(!With::<u32>::check(&table));
+        assert!(WithOut::<u32>::check(&table));
+    }
+
     #[cfg(feature = "runtime-checks")]
     #[test]
     #[should_panic]
@@ -558,6 +923,41 @@ mod tests {
         assert_eq!(&row, &[200]);
     }
 
+    #[test]
+    fn test_row_try_clone() {
+        assert!(Row::new::<u32>().try_clone().is_none());
+
+        let mut row = Row::new_clonable::<u32>();
+        row.push(1u32);
+        row.push(2u32);
+
+        let mut copy = row.try_clone().unwrap();
+        copy.get_mut::<u32>()[0] = 10;
+        copy.push(3u32);
+
+        assert_eq!(row.get_mut::<u32>(), &[1, 2]);
+        assert_eq!(copy.get_mut::<u32>(), &[10, 2, 3]);
+
+        // empty clones stay clonable
+        assert!(copy.clone_empty().try_clone().is_some());
+    }
+
+    #[test]
+    fn test_row_access_at() {
+        let mut table = Table::new::<(u32, i32)>();
+        table.push(Entity::new(0, Generation::new()), (100u32, 200i32));
+        table.push(Entity::new(1, Generation::new()), (101u32, 201i32));
+
+        let row = table.try_get_row_ref::<u32>().unwrap();
+        assert_eq!(row.at(1), Some(&101));
+        assert_eq!(row.at(2), None);
+
+        let mut row = table.try_get_row_mut::<i32>().unwrap();
+        *row.at_mut(0).unwrap() = 300;
+        assert_eq!(row.at(0), Some(&300));
+        assert_eq!(row.at_mut(2), None);
+    }
+
     #[test]
     fn test_push_or_update() {
         let mut table = Table::new::<(u32, i32)>();
@@ -636,4 +1036,57 @@ mod tests {
         let row = RowAccessRef::deref(&row);
         assert_eq!(&row, &[100]);
     }
+
+    #[test]
+    fn test_remove_middle_entity() {
+        let mut table = Table::new::<(u32, i32)>();
+        let mut table_single = Table::new::<u32>();
+        let ents = (0..3)
+            .map(|i| Entity::new(i, Generation::new()))
+            .collect::<Vec<_>>();
+
+        for (i, ent) in ents.iter().enumerate() {
+            table.push(*ent, (i as u32, -(i as i32)));
+        }
+
+        // last entity gets swapped into the middle
+        table.delete_entity(ents[1]);
+        assert_eq!(&table.entities, &[ents[0], ents[2]]);
+        assert_eq!(table.get_entity_position(&ents[2]), 1);
+
+        let row = table.try_get_row_ref::<u32>().unwrap();
+        assert_eq!(RowAccessRef::deref(&row), &[0, 2]);
+        drop(row);
+        let row = table.try_get_row_ref::<i32>().unwrap();
+        assert_eq!(RowAccessRef::deref(&row), &[0, -2]);
+        drop(row);
+
+        // moving the first entity swaps the last one to the front
+        table.move_entity_down(&mut table_single, &ents[0]);
+        assert_eq!(&table.entities, &[ents[2]]);
+        assert_eq!(table.get_entity_position(&ents[2]), 0);
+        assert_eq!(&table_single.entities, &[ents[0]]);
+
+        let row = table.try_get_row_ref::<u32>().unwrap();
+        assert_eq!(RowAccessRef::deref(&row), &[2]);
+        let row = table_single.try_get_row_ref::<u32>().unwrap();
+        assert_eq!(RowAccessRef::deref(&row), &[0]);
+    }
+
+    #[cfg(feature = "debug-utils")]
+    #[test]
+    fn test_table_id_describe() {
+        use super::TableIdent;
+
+        let table = Table::new::<u32>();
+        assert_eq!(table.id().describe().as_deref(), Some("Table[u32]"));
+
+        let id = <(u32, i32)>::table_id();
+        let mut extend = table.get_extendable_precomputed(id);
+        extend.extend_rows::<i32>();
+        let table = extend.finish();
+
+        assert_eq!(table.id().describe().as_deref(), Some("Table[i32, u32]"));
+        assert_eq!(table.id().to_string(), "Table[i32, u32]");
+    }
 }
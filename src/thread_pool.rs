@@ -6,24 +6,51 @@ use std::{
         atomic::AtomicUsize,
         mpsc::{self, Sender},
     },
+    time::{Duration, Instant},
 };
 
 pub struct ThreadPool {
     threads: Box<[ThreadHandle]>,
+
+    /// How long to wait for all jobs of a scope, before reporting them as stuck.
+    join_timeout: Option<Duration>,
 }
 
 impl ThreadPool {
-    pub fn new(thread_count: usize) -> Self {
+    /// Creates a new `ThreadPool`, optionally pinning each thread to a distinct core.
+    ///
+    /// The first core is left for the main thread.
+    /// Pinning requires the `thread-affinity` feature and is a no-op on unsupported platforms.
+    pub fn new(thread_count: usize, pin_threads: bool) -> Self {
+        let cores = std::thread::available_parallelism().map_or(1, usize::from);
+
         let mut threads = Vec::with_capacity(thread_count);
         for id in 0..thread_count {
-            threads.push(ThreadHandle::new(id));
+            let core = pin_threads.then_some((id + 1) % cores);
+            threads.push(ThreadHandle::new(id, core));
         }
 
         Self {
             threads: threads.into_boxed_slice(),
+            join_timeout: None,
         }
     }
 
+    #[inline]
+    pub const fn thread_count(&self) -> usize {
+        self.threads.len()
+    }
+
+    #[inline]
+    pub const fn set_join_timeout(&mut self, timeout: Option<Duration>) {
+        self.join_timeout = timeout;
+    }
+
+    #[inline]
+    pub const fn join_timeout(&self) -> Option<Duration> {
+        self.join_timeout
+    }
+
     pub fn scope<'env, F>(&self, f: F) -> ScopeHandle<'_, 'env>
     where
         F: for<'scope> FnOnce(&'scope Scope<'scope, 'env>),
@@ -72,6 +99,7 @@ impl std::fmt::Debug for ThreadPool {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ThreadPool")
             .field("threads", &self.threads.len())
+            .field("join_timeout", &self.join_timeout)
             .finish_non_exhaustive()
     }
 }
@@ -82,11 +110,19 @@ struct ThreadHandle {
 }
 
 impl ThreadHandle {
-    fn new(id: usize) -> Self {
+    fn new(id: usize, core: Option<usize>) -> Self {
         let (tx, rx) = mpsc::channel();
         let handle = std::thread::Builder::new()
             .name(format!("Pool Thread: [{id}]"))
             .spawn(move || {
+                #[cfg(feature = "thread-affinity")]
+                if let Some(core) = core {
+                    _ = affinity::pin_current_thread(core);
+                }
+
+                #[cfg(not(feature = "thread-affinity"))]
+                let _ = core;
+
                 loop {
                     if let Ok(msg) = rx.recv() {
                         match msg {
@@ -173,6 +209,29 @@ impl<'scope, 'env: 'scope> ScopeHandle<'scope, 'env> {
         self.join_inner();
     }
 
+    /// Waits for all jobs of this scope to finish, but gives up after `timeout`.
+    ///
+    /// # Errors
+    /// Returns a `JoinTimeoutError`, if the jobs did not finish in time.
+    /// The jobs keep running and keep their borrows (e.g. of the world), leaving them in an indeterminate state.
+    /// The returned error holds on to the scope, dropping or waiting on it blocks until all jobs are finished.
+    pub fn join_timeout(self, timeout: Duration) -> Result<(), JoinTimeoutError<'scope, 'env>> {
+        let start = Instant::now();
+
+        while self.counter.load(std::sync::atomic::Ordering::Relaxed) != 0 {
+            if start.elapsed() >= timeout {
+                return Err(JoinTimeoutError {
+                    handle: self,
+                    timeout,
+                });
+            }
+
+            std::hint::spin_loop();
+        }
+
+        Ok(())
+    }
+
     #[inline]
     fn join_inner(&self) {
         while self.counter.load(std::sync::atomic::Ordering::Relaxed) != 0 {
@@ -188,9 +247,163 @@ impl<'scope, 'env: 'scope> std::ops::Drop for ScopeHandle<'scope, 'env> {
     }
 }
 
+/// The jobs of a scope did not finish in time.
+pub struct JoinTimeoutError<'scope, 'env: 'scope> {
+    handle: ScopeHandle<'scope, 'env>,
+    timeout: Duration,
+}
+
+impl JoinTimeoutError<'_, '_> {
+    #[inline]
+    /// Returns the number of jobs that are still running.
+    pub fn pending(&self) -> usize {
+        self.handle
+            .counter
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    #[inline]
+    /// Keeps waiting until all jobs are finished.
+    pub fn wait(self) {
+        self.handle.join();
+    }
+}
+
+impl std::fmt::Debug for JoinTimeoutError<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JoinTimeoutError")
+            .field("timeout", &self.timeout)
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for JoinTimeoutError<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} job(s) did not finish within {:?}",
+            self.pending(),
+            self.timeout
+        )
+    }
+}
+
+impl std::error::Error for JoinTimeoutError<'_, '_> {}
+
 type Task<'a> = dyn FnOnce() + Send + 'a;
 
 enum Message {
     Finish,
     Job(Box<Task<'static>>, Arc<AtomicUsize>),
 }
+
+#[cfg(feature = "thread-affinity")]
+mod affinity {
+    #[cfg(target_os = "linux")]
+    /// Pins the calling thread to the given core.
+    ///
+    /// Returns `false`, if the thread could not be pinned.
+    pub fn pin_current_thread(core: usize) -> bool {
+        // matches the size of `cpu_set_t`
+        const SET_SIZE: usize = 1024 / u64::BITS as usize;
+
+        unsafe extern "C" {
+            fn sched_setaffinity(pid: i32, cpusetsize: usize, mask: *const u64) -> i32;
+        }
+
+        if core >= SET_SIZE * u64::BITS as usize {
+            return false;
+        }
+
+        let mut set = [0u64; SET_SIZE];
+        set[core / u64::BITS as usize] |= 1 << (core % u64::BITS as usize);
+
+        // pid 0 refers to the calling thread
+        unsafe { sched_setaffinity(0, std::mem::size_of_val(&set), set.as_ptr()) == 0 }
+    }
+
+    #[cfg(target_os = "windows")]
+    /// Pins the calling thread to the given core.
+    ///
+    /// Returns `false`, if the thread could not be pinned.
+    pub fn pin_current_thread(core: usize) -> bool {
+        unsafe extern "system" {
+            fn GetCurrentThread() -> *mut std::ffi::c_void;
+            fn SetThreadAffinityMask(thread: *mut std::ffi::c_void, mask: usize) -> usize;
+        }
+
+        if core >= usize::BITS as usize {
+            return false;
+        }
+
+        unsafe { SetThreadAffinityMask(GetCurrentThread(), 1 << core) != 0 }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    #[inline]
+    /// Pinning is not supported on this platform.
+    pub const fn pin_current_thread(_: usize) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::ThreadPool;
+
+    #[test]
+    fn test_join_timeout() {
+        let pool = ThreadPool::new(2, false);
+
+        let handle = pool.scope(|s| {
+            for thread in s.threads() {
+                thread.run(|| {});
+            }
+        });
+
+        assert!(handle.join_timeout(Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn test_join_timeout_expired() {
+        let pool = ThreadPool::new(2, false);
+
+        let handle = pool.scope(|s| {
+            for thread in s.threads() {
+                thread.run(|| std::thread::sleep(Duration::from_millis(200)));
+            }
+        });
+
+        let err = handle.join_timeout(Duration::from_millis(1)).unwrap_err();
+        assert_eq!(err.pending(), 2);
+
+        err.wait();
+    }
+
+    #[test]
+    fn test_thread_affinity() {
+        let pool = ThreadPool::new(2, true);
+
+        let handle = pool.scope(|s| {
+            for thread in s.threads() {
+                thread.run(|| {});
+            }
+        });
+
+        assert!(handle.join_timeout(Duration::from_secs(10)).is_ok());
+    }
+
+    #[cfg(all(feature = "thread-affinity", target_os = "linux"))]
+    #[test]
+    fn test_pin_current_thread() {
+        let pinned = std::thread::spawn(|| super::affinity::pin_current_thread(0))
+            .join()
+            .unwrap();
+        assert!(pinned);
+
+        assert!(!super::affinity::pin_current_thread(usize::MAX));
+    }
+}
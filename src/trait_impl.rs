@@ -6,23 +6,33 @@ use std::{
 
 use crate::{
     Commands, Component, NoSend, Query, Resource, World,
-    cells::{WorldCellComplete, WorldCellSend},
+    cells::{AtomicRefCell, WorldCellComplete, WorldCellSend},
     components::ComponentSet,
-    entity::Entity,
+    entity::{Entity, Spawner},
+    events::{EventReader, EventWriter, Events},
     filter::{Filter, FilterType},
     macros::{
-        component_set_impl, extract_impl, filter_impl, into_system_set_impl, row_access_impl,
-        system_impl, table_ident_impl, unwrap,
+        component_set_impl, extract_impl, filter_impl, into_system_set_impl, resource_set_impl,
+        row_access_impl, system_impl, table_ident_impl, unwrap,
+    },
+    query::{
+        EntityIter, Extract, GetComponentAccess, NoneIter, PrevAndCurr, RowAccess, Single,
+        TableAccess,
     },
-    query::{Extract, GetComponentAccess, NoneIter, RowAccess, TableAccess},
     resources::{
-        GlobalRes, GlobalResMut, GlobalUnsendMut, GlobalUnsendRef, Res, ResMut, UnsendMut,
-        UnsendRef,
+        GlobalRes, GlobalResMut, GlobalUnsendMut, GlobalUnsendRef, Res, ResInit, ResMut,
+        ResourceSet, Resources, ScopedRes, UnsendMut, UnsendRef,
     },
     schedule::{IntoSystemSet, SystemSet},
-    system::{FunctionSystem, IntoSystem, ParamType, System, SystemParam},
-    table::{Row, RowAccessMut, RowAccessRef, Table, TableId, TableIdBuilder, TableIdent},
-    world::SendWorld,
+    system::{
+        Fallible, FunctionSystem, InitFn, IntoSystem, Local, LocalState, ParamType, System,
+        SystemOutput, SystemParam, retrieved,
+    },
+    table::{
+        PrevAndCurrIter, Row, RowAccessMut, RowAccessPrev, RowAccessRef, Table, TableId,
+        TableIdBuilder, TableIdent,
+    },
+    world::{FromWorld, SendWorld},
 };
 
 // ComponentSet
@@ -62,6 +72,13 @@ const _: () = {
             unwrap!(table.rows.iter_mut().find(|x| x.tid() == TypeId::of::<A>()))
                 .push_or_update::<A>(position, a);
         }
+
+        fn push_missing(self, table: &mut Table, position: usize) {
+            let a = self;
+
+            unwrap!(table.rows.iter_mut().find(|x| x.tid() == TypeId::of::<A>()))
+                .push_missing::<A>(position, a);
+        }
     }
 
     component_set_impl!(A, B);
@@ -245,18 +262,52 @@ const _: () = {
         }
     }
 
+    impl<C: Component + Clone> Extract for PrevAndCurr<C> {
+        type Extracted<'new> = TableAccess<'new, Self::RowOnly<'new>>;
+        type RowOnly<'new> = RowAccessPrev<'new, C>;
+
+        #[inline]
+        fn raw_unit_type() -> (TypeId, bool) {
+            (TypeId::of::<C>(), true)
+        }
+
+        #[inline]
+        fn types() -> Vec<ParamType> {
+            vec![ParamType::new_shared::<C>()]
+        }
+
+        #[cfg(feature = "runtime-checks")]
+        fn validate() {}
+
+        #[inline]
+        fn extract(table: &'_ Table) -> Result<Self::Extracted<'_>, ()> {
+            let entities = &table.entities;
+
+            let access = TableAccess {
+                table_id: table.id(),
+                entities,
+                table_rows: table.try_get_row_prev::<C>()?,
+            };
+
+            Ok(access)
+        }
+
+        #[inline]
+        fn get_row_only(table: &'_ Table) -> Result<Self::RowOnly<'_>, ()> {
+            table.try_get_row_prev()
+        }
+    }
+
     extract_impl!(A, B);
     extract_impl!(A, B, C);
     extract_impl!(A, B, C, D);
     extract_impl!(A, B, C, D, E);
     extract_impl!(A, B, C, D, E, F);
+    extract_impl!(A, B, C, D, E, F, G);
+    extract_impl!(A, B, C, D, E, F, G, H);
 
     #[cfg(feature = "large_tuples")]
-    {
-        extract_impl!(A, B, C, D, E, F, G);
-        extract_impl!(A, B, C, D, E, F, G, H);
-        extract_impl!(A, B, C, D, E, F, G, H, I);
-    }
+    extract_impl!(A, B, C, D, E, F, G, H, I);
 };
 
 // GetComponentAccess
@@ -277,6 +328,11 @@ const _: () = {
             self.table_id
         }
 
+        #[inline]
+        fn len(&self) -> usize {
+            self.entities.len()
+        }
+
         #[inline]
         fn get_entity(&mut self, entity: &Entity) -> Option<Self::Item<'_>> {
             let position = self.entities.iter().position(|ent| ent == entity)?;
@@ -288,6 +344,24 @@ const _: () = {
         fn iter(&mut self) -> Self::Iter<'_> {
             self.table_rows.get_iter()
         }
+
+        #[inline]
+        fn iter_with_entities(&mut self) -> EntityIter<'_, Self::Iter<'_>> {
+            self.entities
+                .iter()
+                .copied()
+                .zip(self.table_rows.get_iter())
+        }
+
+        #[inline]
+        fn row<C: Component>(&self) -> Option<&[C]> {
+            self.table_rows.row()
+        }
+
+        #[inline]
+        fn row_mut<C: Component>(&mut self) -> Option<&mut [C]> {
+            self.table_rows.row_mut()
+        }
     }
 };
 
@@ -301,7 +375,7 @@ const _: () = {
 
         #[inline]
         fn get_entity_components(&mut self, position: usize) -> Self::Item<'_> {
-            unwrap!(RowAccessRef::deref(self).get(position))
+            unwrap!(self.at(position))
         }
 
         type Iter<'a>
@@ -313,6 +387,16 @@ const _: () = {
         fn get_iter(&mut self) -> Self::Iter<'_> {
             RowAccessRef::deref(self).iter()
         }
+
+        #[inline]
+        fn row<R: Component>(&self) -> Option<&[R]> {
+            self.downcast_row()
+        }
+
+        #[inline]
+        fn row_mut<R: Component>(&mut self) -> Option<&mut [R]> {
+            None
+        }
     }
 
     impl<C: Component> RowAccess for RowAccessMut<'_, C> {
@@ -323,7 +407,7 @@ const _: () = {
 
         #[inline]
         fn get_entity_components(&mut self, position: usize) -> Self::Item<'_> {
-            unwrap!(RowAccessMut::deref_mut(self).get_mut(position))
+            unwrap!(self.at_mut(position))
         }
 
         type Iter<'a>
@@ -335,6 +419,48 @@ const _: () = {
         fn get_iter(&mut self) -> Self::Iter<'_> {
             RowAccessMut::deref_mut(self).iter_mut()
         }
+
+        #[inline]
+        fn row<R: Component>(&self) -> Option<&[R]> {
+            self.downcast_row()
+        }
+
+        #[inline]
+        fn row_mut<R: Component>(&mut self) -> Option<&mut [R]> {
+            self.downcast_row_mut()
+        }
+    }
+
+    impl<C: Component> RowAccess for RowAccessPrev<'_, C> {
+        type Item<'a>
+            = (&'a C, &'a C)
+        where
+            Self: 'a;
+
+        #[inline]
+        fn get_entity_components(&mut self, position: usize) -> Self::Item<'_> {
+            unwrap!(self.at(position))
+        }
+
+        type Iter<'a>
+            = PrevAndCurrIter<'a, C>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn get_iter(&mut self) -> Self::Iter<'_> {
+            RowAccessPrev::iter(self)
+        }
+
+        #[inline]
+        fn row<R: Component>(&self) -> Option<&[R]> {
+            self.downcast_row()
+        }
+
+        #[inline]
+        fn row_mut<R: Component>(&mut self) -> Option<&mut [R]> {
+            None
+        }
     }
 
     impl<C: Component> RowAccess for Option<RowAccessRef<'_, C>> {
@@ -345,7 +471,7 @@ const _: () = {
 
         #[inline]
         fn get_entity_components(&mut self, position: usize) -> Self::Item<'_> {
-            let out = self.as_mut()?.get(position);
+            let out = self.as_ref()?.at(position);
 
             #[cfg(feature = "runtime-checks")]
             assert!(out.is_some());
@@ -363,6 +489,16 @@ const _: () = {
             self.as_mut()
                 .map_or(NoneIter::None, |row| NoneIter::Iter(row.get_iter()))
         }
+
+        #[inline]
+        fn row<R: Component>(&self) -> Option<&[R]> {
+            self.as_ref()?.row()
+        }
+
+        #[inline]
+        fn row_mut<R: Component>(&mut self) -> Option<&mut [R]> {
+            self.as_mut()?.row_mut()
+        }
     }
 
     impl<C: Component> RowAccess for Option<RowAccessMut<'_, C>> {
@@ -373,7 +509,7 @@ const _: () = {
 
         #[inline]
         fn get_entity_components(&mut self, position: usize) -> Self::Item<'_> {
-            let out = self.as_mut()?.get_mut(position);
+            let out = self.as_mut()?.at_mut(position);
 
             #[cfg(feature = "runtime-checks")]
             assert!(out.is_some());
@@ -391,6 +527,16 @@ const _: () = {
             self.as_mut()
                 .map_or(NoneIter::None, |row| NoneIter::Iter(row.get_iter()))
         }
+
+        #[inline]
+        fn row<R: Component>(&self) -> Option<&[R]> {
+            self.as_ref()?.row()
+        }
+
+        #[inline]
+        fn row_mut<R: Component>(&mut self) -> Option<&mut [R]> {
+            self.as_mut()?.row_mut()
+        }
     }
 
     impl<A: RowAccess, B: RowAccess> RowAccess for (A, B) {
@@ -421,19 +567,31 @@ const _: () = {
 
             a.get_iter().zip(b.get_iter())
         }
+
+        #[inline]
+        fn row<C: Component>(&self) -> Option<&[C]> {
+            let (a, b) = self;
+
+            a.row().or_else(|| b.row())
+        }
+
+        #[inline]
+        fn row_mut<C: Component>(&mut self) -> Option<&mut [C]> {
+            let (a, b) = self;
+
+            a.row_mut().or_else(|| b.row_mut())
+        }
     }
 
     row_access_impl!(A, B, C);
     row_access_impl!(A, B, C, D);
     row_access_impl!(A, B, C, D, E);
     row_access_impl!(A, B, C, D, E, F);
+    row_access_impl!(A, B, C, D, E, F, G);
+    row_access_impl!(A, B, C, D, E, F, G, H);
 
     #[cfg(feature = "large_tuples")]
-    {
-        row_access_impl!(A, B, C, D, E, F, G);
-        row_access_impl!(A, B, C, D, E, F, G, H);
-        row_access_impl!(A, B, C, D, E, F, G, H, I);
-    }
+    row_access_impl!(A, B, C, D, E, F, G, H, I);
 };
 
 // SystemParam
@@ -447,7 +605,7 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(world: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
             world.scene.get_resource_ref()
         }
     }
@@ -461,11 +619,40 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(world: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
             world.scene.get_resource_mut()
         }
     }
 
+    // a missing resource doesn't skip the system, it is passed as `None` instead
+    impl<R: Resource> SystemParam for Option<Res<'_, R>> {
+        type Item<'new> = Option<Res<'new, R>>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            vec![ParamType::new_shared::<R>()]
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            Some(world.scene.get_resource_ref())
+        }
+    }
+
+    impl<R: Resource> SystemParam for Option<ResMut<'_, R>> {
+        type Item<'new> = Option<ResMut<'new, R>>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            vec![ParamType::new_mut::<R>()]
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            Some(world.scene.get_resource_mut())
+        }
+    }
+
     impl<R: Resource> SystemParam for GlobalRes<'_, R> {
         type Item<'new> = GlobalRes<'new, R>;
 
@@ -475,7 +662,7 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(world: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
             world
                 .global_resource
                 .get_resource_ref::<R>()
@@ -483,6 +670,26 @@ const _: () = {
         }
     }
 
+    impl<R: Sync + 'static> SystemParam for ScopedRes<'_, R> {
+        type Item<'new> = ScopedRes<'new, R>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            // read-only and separate from all other resources
+            vec![ParamType::new_shared::<ScopedRes<'static, R>>()]
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            // # SAFETY
+            // `with_scoped_resource` only stores the pointer while its borrow of the value is alive,
+            // and removes it before that borrow ends, so a found pointer is valid.
+            // The reference is only dereferenced while this system runs, which happens inside of the scope.
+            let value = unsafe { world.scoped.get::<R>() }?;
+            Some(ScopedRes { value })
+        }
+    }
+
     impl<R: Resource> SystemParam for GlobalResMut<'_, R> {
         type Item<'new> = GlobalResMut<'new, R>;
 
@@ -492,7 +699,29 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(world: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            world
+                .global_resource
+                .get_resource_mut::<R>()
+                .map(Into::into)
+        }
+    }
+
+    impl<R: Resource + FromWorld> SystemParam for ResInit<'_, R> {
+        type Item<'new> = ResInit<'new, R>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            vec![ParamType::new_mut::<R>()]
+        }
+
+        #[inline]
+        fn init() -> Option<InitFn> {
+            Some(World::init_resource_from_world::<R>)
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
             world
                 .global_resource
                 .get_resource_mut::<R>()
@@ -513,7 +742,7 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(_: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(_: SendWorld<'_>) -> Option<Self::Item<'_>> {
             unimplemented!()
         }
 
@@ -540,7 +769,7 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(_: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(_: SendWorld<'_>) -> Option<Self::Item<'_>> {
             unimplemented!()
         }
 
@@ -565,7 +794,7 @@ const _: () = {
             true
         }
 
-        fn retrieve(_: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(_: SendWorld<'_>) -> Option<Self::Item<'_>> {
             unimplemented!()
         }
 
@@ -590,7 +819,7 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(_: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(_: SendWorld<'_>) -> Option<Self::Item<'_>> {
             unimplemented!()
         }
 
@@ -602,6 +831,34 @@ const _: () = {
         }
     }
 
+    impl<T: Default + Send + Sync + 'static> SystemParam for Local<'_, T> {
+        type Item<'new> = Local<'new, T>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            // only accesses state owned by the system
+            Vec::new()
+        }
+
+        #[inline]
+        fn init_local() -> Option<LocalState> {
+            Some(AtomicRefCell::new(Box::new(T::default())))
+        }
+
+        #[inline]
+        fn retrieve(_: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            unimplemented!()
+        }
+
+        #[inline]
+        fn retrieve_with_local<'a>(
+            _: SendWorld<'a>,
+            local: Option<&'a LocalState>,
+        ) -> Option<Self::Item<'a>> {
+            Some(Local::new(local?.borrow_mut()))
+        }
+    }
+
     impl SystemParam for Commands {
         type Item<'new> = Self;
 
@@ -611,11 +868,57 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(world: SendWorld) -> Option<Self::Item<'_>> {
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
             Some(world.commands.commands(world.scene.entities.spawner()))
         }
     }
 
+    impl SystemParam for Spawner {
+        type Item<'new> = Self;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            vec![ParamType::new_shared::<Self>()]
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            Some(Self {
+                spawner: world.scene.entities.spawner(),
+            })
+        }
+    }
+
+    impl<E: Send + Sync + 'static> SystemParam for EventReader<'_, E> {
+        type Item<'new> = EventReader<'new, E>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            vec![ParamType::new_shared::<Events<E>>()]
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            let handle = world.global_resource.get_resource_ref::<Events<E>>()?;
+            Some(EventReader { handle })
+        }
+    }
+
+    impl<E: Send + Sync + 'static> SystemParam for EventWriter<'_, E> {
+        type Item<'new> = EventWriter<'new, E>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            vec![ParamType::new_mut::<Events<E>>()]
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            let handle = world.global_resource.get_resource_mut::<Events<E>>()?;
+            Some(EventWriter { handle })
+        }
+    }
+
     impl<E: Extract, F: Filter> SystemParam for Query<'_, E, F> {
         type Item<'new> = Query<'new, E, F>;
 
@@ -630,9 +933,89 @@ const _: () = {
         }
 
         #[inline]
-        fn retrieve(world: SendWorld) -> Option<Self::Item<'_>> {
+        fn init_local() -> Option<LocalState> {
+            // worked out once per system, instead of every time the query is created
+            let pruned = Query::<E, F>::pruned_filter()?;
+            Some(AtomicRefCell::new(Box::new(pruned)))
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
             Query::new_internal(world.scene.entities)
         }
+
+        #[inline]
+        fn retrieve_with_local<'a>(
+            world: SendWorld<'a>,
+            local: Option<&'a LocalState>,
+        ) -> Option<Self::Item<'a>> {
+            let Some(local) = local else {
+                return Self::retrieve(world);
+            };
+
+            Query::new_pruned(
+                world.scene.entities,
+                local
+                    .borrow()
+                    .downcast_ref::<Vec<FilterType>>()
+                    .map(Vec::as_slice),
+            )
+        }
+
+        #[inline]
+        fn skip_silently() -> bool {
+            // no matching entities
+            true
+        }
+    }
+
+    impl<E: Extract, F: Filter> SystemParam for Single<'_, E, F> {
+        type Item<'new> = Single<'new, E, F>;
+
+        #[inline]
+        fn get_types() -> Vec<ParamType> {
+            E::types()
+        }
+
+        #[inline]
+        fn get_filter() -> Vec<FilterType> {
+            F::types()
+        }
+
+        #[inline]
+        fn retrieve(world: SendWorld<'_>) -> Option<Self::Item<'_>> {
+            let query = Query::new_internal(world.scene.entities)?;
+
+            #[cfg(feature = "debug-utils")]
+            {
+                let count = query
+                    .tables
+                    .iter()
+                    .map(GetComponentAccess::len)
+                    .sum::<usize>();
+                if count > 1 {
+                    let name = std::any::type_name::<Self>();
+
+                    #[cfg(feature = "log")]
+                    {
+                        log::warn!("[{name}] matched {count} entities, expected one")
+                    }
+
+                    #[cfg(not(feature = "log"))]
+                    {
+                        println!("[WARN] [{name}] matched {count} entities, expected one")
+                    }
+                }
+            }
+
+            Single::new(query)
+        }
+
+        #[inline]
+        fn skip_silently() -> bool {
+            // no matching entity, more than one is reported in `retrieve`
+            true
+        }
     }
 };
 
@@ -681,10 +1064,13 @@ const _: () = {
             FunctionSystem {
                 f: self,
                 marker: Default::default(),
+                locals: Box::new([]),
             }
         }
     }
 
+    // `&mut World` is not a `SystemParam`, so it can not be combined with other parameters (e.g. `Commands`).
+    // Such a system runs exclusively on the main thread and can apply changes directly.
     impl<'x, FF: Fn(&'x mut World) + Send + Sync> IntoSystem<&'x mut World> for FF
     where
         for<'a, 'b> &'a FF: Fn(&mut World) + Fn(&mut World),
@@ -695,6 +1081,7 @@ const _: () = {
             FunctionSystem {
                 f: self,
                 marker: Default::default(),
+                locals: Box::new([]),
             }
         }
     }
@@ -745,11 +1132,11 @@ const _: () = {
     system_impl!(A, B, C, D);
     system_impl!(A, B, C, D, E);
     system_impl!(A, B, C, D, E, F);
+    system_impl!(A, B, C, D, E, F, G);
+    system_impl!(A, B, C, D, E, F, G, H);
 
     #[cfg(feature = "large_tuples")]
     {
-        system_impl!(A, B, C, D, E, F, G);
-        system_impl!(A, B, C, D, E, F, G, H);
         system_impl!(A, B, C, D, E, F, G, H, I);
     }
 };
@@ -794,7 +1181,10 @@ const _: () = {
     {
         fn into_set(self) -> SystemSet {
             let system: Box<dyn System> = Box::new(self.into_system());
-            SystemSet::Single { system }
+            SystemSet::Single {
+                system,
+                label: None,
+            }
         }
     }
 
@@ -805,24 +1195,38 @@ const _: () = {
 
     #[rustfmt::skip]
     into_system_set_impl!(A, B, C, D, E, F | AI, BI, CI, DI, EI, FI | AS, BS, CS, DS, ES, FS);
+    #[rustfmt::skip]
+    into_system_set_impl!(A, B, C, D, E, F, G | AI, BI, CI, DI, EI, FI, GI | AS, BS, CS, DS, ES, FS, GS);
+    #[rustfmt::skip]
+    into_system_set_impl!(A, B, C, D, E, F, G, H | AI, BI, CI, DI, EI, FI, GI, HI | AS, BS, CS, DS, ES, FS, GS, HS);
 
     #[cfg(feature = "large_tuples")]
     {
-        #[rustfmt::skip]
-        into_system_set_impl!(A, B, C, D, E, F, G | AI, BI, CI, DI, EI, FI, GI | AS, BS, CS, DS, ES, FS, GS);
-        #[rustfmt::skip]
-        into_system_set_impl!(A, B, C, D, E, F, G, H | AI, BI, CI, DI, EI, FI, GI, HI | AS, BS, CS, DS, ES, FS, GS, HS);
         #[rustfmt::skip]
         into_system_set_impl!(A, B, C, D, E, F, G, H, I | AI, BI, CI, DI, EI, FI, GI, HI, II | AS, BS, CS, DS, ES, FS, GS, HS, IS);
     }
 };
 
+// ResourceSet
+const _: () = {
+    resource_set_impl!(A, B);
+    resource_set_impl!(A, B, C);
+    resource_set_impl!(A, B, C, D);
+    resource_set_impl!(A, B, C, D, E);
+    resource_set_impl!(A, B, C, D, E, F);
+    resource_set_impl!(A, B, C, D, E, F, G);
+    resource_set_impl!(A, B, C, D, E, F, G, H);
+};
+
 #[cfg(feature = "runtime-checks")]
 fn unique_tuple<const N: usize>(types: &[TypeId; N]) {
     for (i, t1) in types.iter().enumerate() {
         for (j, t2) in types.iter().enumerate() {
             if i != j {
-                assert_ne!(t1, t2)
+                assert_ne!(
+                    t1, t2,
+                    "A component type appears more than once in the same tuple!"
+                )
             }
         }
     }
@@ -1,21 +1,72 @@
+use std::{any::TypeId, ops::Range};
+
 use crate::{
-    commands::{CommandCenter, Commands, ComponentCommands, EntityCommands, ResourceCommands},
+    Component,
+    commands::{
+        CommandCenter, CommandStats, Commands, ComponentCommands, EntityCommands, ResourceCommands,
+    },
+    components::ComponentSet,
+    entity::{Entity, SpawnError},
+    events::Events,
+    macros::unwrap,
     resources::{
-        GlobalRes, GlobalResMut, GlobalUnsendMut, GlobalUnsendRef, NoSend, Resource, Resources,
+        FrameUpdateFn, GlobalRes, GlobalResMut, GlobalUnsendMut, GlobalUnsendRef, NoSend, Resource,
+        ResourceSet, Resources, ScopedPtr, ScopedResources,
     },
-    scene::{Scene, SendScene, SendScene2},
+    scene::{EntityMut, Scene, SendScene, SendScene2},
+    table::{Row, Table},
 };
 
-#[cfg_attr(feature = "debug-utils", derive(Debug))]
+/// Copies the tracked component of every table, see `World::track_previous`.
+type SnapshotFn = fn(&mut [Table]);
+
+/// Clones a row of a registered component, see `World::register_clone`.
+type CloneRowFn = fn(&Row) -> Row;
+
+/// A trait for types that can be created from a `World`, e.g. by reading other resources.
+///
+/// Implemented for all types that implement `Default`.
+pub trait FromWorld {
+    fn from_world(world: &mut World) -> Self;
+}
+
+impl<T: Default> FromWorld for T {
+    #[inline]
+    fn from_world(_: &mut World) -> Self {
+        T::default()
+    }
+}
+
 pub struct World {
     pub(crate) commands: CommandCenter,
 
     global_resources: Resources<dyn Resource>,
     global_nosend: Resources<dyn NoSend>,
 
+    /// Frame updates of the inserted global resources, e.g. swapping the buffers of each event type.
+    frame_updates: Vec<(TypeId, FrameUpdateFn)>,
+    // components with tracked previous values
+    previous_updates: Vec<(TypeId, SnapshotFn)>,
+    // components that can be cloned by `clone_scene`
+    clone_rows: Vec<(TypeId, CloneRowFn)>,
+    // values borrowed by `with_scoped_resource`
+    scoped: ScopedResources,
+    // set while a schedule runs on this world, see `swap_scene`
+    scene_pinned: bool,
+
     current_scene: Scene,
 }
 
+#[cfg(feature = "debug-utils")]
+impl std::fmt::Debug for World {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "World")?;
+        writeln!(f, "    global resources: {:?}", self.global_resources)?;
+        writeln!(f, "    global nosend:    {:?}", self.global_nosend)?;
+        f.write_str(&self.current_scene.debug_archetypes())
+    }
+}
+
 #[cfg(not(feature = "debug-utils"))]
 impl std::fmt::Debug for World {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -29,10 +80,26 @@ impl World {
             commands: CommandCenter::new(),
             global_resources: Resources::new(),
             global_nosend: Resources::new(),
+            frame_updates: Vec::new(),
+            previous_updates: Vec::new(),
+            clone_rows: Vec::new(),
+            scoped: ScopedResources::default(),
+            scene_pinned: false,
             current_scene: Scene::new(),
         }
     }
 
+    /// Creates a world, whose entities are only spawned within `range`, e.g. for one shard of a sharded server.
+    ///
+    /// Giving every shard a disjoint range keeps their entities apart, so they can be referenced across shards.
+    /// Spawning panics once every position of the range is in use.
+    /// Only the initial scene uses the range, a scene left behind by `take_scene` uses all positions again.
+    pub fn new_with_entity_range(range: Range<u32>) -> Self {
+        let mut world = Self::new();
+        world.current_scene = Scene::with_entity_range(range);
+        world
+    }
+
     #[inline]
     pub const fn current_scene(&self) -> &Scene {
         &self.current_scene
@@ -43,6 +110,59 @@ impl World {
         &mut self.current_scene
     }
 
+    /// Allows the component `C` to be cloned by `clone_scene`.
+    pub fn register_clone<C: Component + Clone>(&mut self) {
+        let type_id = TypeId::of::<C>();
+        if self.clone_rows.iter().any(|(id, _)| *id == type_id) {
+            return;
+        }
+
+        self.clone_rows.push((type_id, Row::clone_full::<C>));
+    }
+
+    /// Clones all entities and components of the current scene, e.g. to roll back to it later.
+    ///
+    /// Resources are not cloned. Returns `None`, if a component is neither registered with `register_clone`, nor stored in a clonable row.
+    pub fn clone_scene(&self) -> Option<Scene> {
+        self.current_scene.clone_state(|row| {
+            row.try_clone().or_else(|| {
+                let (_, clone) = self.clone_rows.iter().find(|(id, _)| *id == row.tid())?;
+                Some(clone(row))
+            })
+        })
+    }
+
+    #[inline]
+    /// Takes the current scene out of the world, leaving an empty scene in its place.
+    ///
+    /// # Panics
+    /// Panics while a `Schedule` runs on this world, see `swap_scene`.
+    pub fn take_scene(&mut self) -> Scene {
+        self.swap_scene(Scene::new())
+    }
+
+    #[inline]
+    /// Replaces the current scene, returning the previous one.
+    ///
+    /// Pending commands are applied to the new scene, so apply them before swapping if needed.
+    ///
+    /// # Panics
+    /// Panics while a `Schedule` runs on this world (e.g. when called from an exclusive system),
+    /// as running systems still point into the current scene.
+    pub const fn swap_scene(&mut self, scene: Scene) -> Scene {
+        assert!(
+            !self.scene_pinned,
+            "Can't swap the scene while a schedule runs on the world"
+        );
+        std::mem::replace(&mut self.current_scene, scene)
+    }
+
+    #[inline]
+    /// Marks the world as run by a schedule, which keeps the current scene in place.
+    pub(crate) const fn pin_scene(&mut self, pinned: bool) {
+        self.scene_pinned = pinned;
+    }
+
     #[inline]
     pub const fn global_resources(&self) -> &Resources<dyn Resource> {
         &self.global_resources
@@ -58,67 +178,307 @@ impl World {
         self.commands.commands(self.current_scene.spawner())
     }
 
+    #[inline]
+    /// Returns a handle to a specific `Entity` of the current scene, spawning it if needed, see `Scene::get_or_spawn`.
+    ///
+    /// # Errors
+    /// Returns a `SpawnError`, if the slot is live with a different generation, reserved, or the `Entity` is invalid.
+    pub fn get_or_spawn(&mut self, entity: Entity) -> Result<EntityMut<'_>, SpawnError> {
+        self.current_scene.get_or_spawn(entity)
+    }
+
+    #[inline]
+    /// Adds the components to each `Entity` of the current scene, spawning it at its exact id if needed, see `Scene::insert_or_spawn_batch`.
+    ///
+    /// # Errors
+    /// Returns a `SpawnError` for every `Entity` that could not be spawned, its components are skipped.
+    pub fn insert_or_spawn_batch<C: ComponentSet>(
+        &mut self,
+        iter: impl IntoIterator<Item = (Entity, C)>,
+    ) -> Result<(), Vec<SpawnError>> {
+        self.current_scene.insert_or_spawn_batch(iter)
+    }
+
+    #[inline]
+    /// Returns a handle to edit a live `Entity` of the current scene right away.
+    ///
+    /// Returns `None`, if the `Entity` is not alive.
+    pub fn entity_mut(&mut self, entity: Entity) -> Option<EntityWorldMut<'_>> {
+        if !self.current_scene.is_alive(&entity) {
+            return None;
+        }
+
+        Some(EntityWorldMut {
+            scene: &mut self.current_scene,
+            entity,
+        })
+    }
+
+    #[inline]
+    /// Deletes an `Entity` of the current scene right away.
+    ///
+    /// Returns `false`, if the `Entity` was not alive.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        self.current_scene.delete_entity(entity)
+    }
+
+    #[inline]
+    /// Deletes all entities in the current scene that have the component `C`.
+    pub fn despawn_all_with<C: Component>(&mut self) {
+        self.current_scene.despawn_all_with::<C>();
+    }
+
     #[inline]
     pub fn insert_resource<R: Resource>(&mut self, res: R) {
+        Self::add_frame_update(
+            &mut self.frame_updates,
+            TypeId::of::<R>(),
+            R::frame_update(),
+        );
         self.global_resources.insert_resource(res);
     }
 
+    /// Registers the frame update of a global resource once, see `Resource::frame_update`.
+    fn add_frame_update(
+        updates: &mut Vec<(TypeId, FrameUpdateFn)>,
+        type_id: TypeId,
+        update: Option<FrameUpdateFn>,
+    ) {
+        let Some(update) = update else {
+            return;
+        };
+
+        if updates.iter().any(|(id, _)| *id == type_id) {
+            return;
+        }
+
+        updates.push((type_id, update));
+    }
+
+    /// Creates and inserts a global resource from this `World`.
+    ///
+    /// If the resource already exists, nothing happens.
+    pub fn init_resource_from_world<R: Resource + FromWorld>(&mut self) {
+        if self.global_resources.contains_resource::<R>() {
+            return;
+        }
+
+        let res = R::from_world(self);
+        self.insert_resource(res);
+    }
+
     #[inline]
-    pub fn get_resource_ref<R: Resource>(&self) -> Option<GlobalRes<R>> {
+    pub fn get_resource_ref<R: Resource>(&self) -> Option<GlobalRes<'_, R>> {
         let handle = self.global_resources.get_resource_ref::<R>()?.into();
         Some(handle)
     }
 
+    /// Makes `value` available to systems as `ScopedRes<R>` while `f` runs, e.g. to pass input borrowed from elsewhere to a `Schedule::run`.
+    ///
+    /// The value is removed when `f` returns (or panics), even if `f` moves the world out of the given reference.
+    /// Nested scopes of the same type shadow the outer value until they end.
+    pub fn with_scoped_resource<R: Sync + 'static, T>(
+        &mut self,
+        value: &R,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        struct ScopeGuard {
+            scoped: ScopedResources,
+            previous: Option<ScopedPtr>,
+            type_id: TypeId,
+        }
+
+        impl Drop for ScopeGuard {
+            fn drop(&mut self) {
+                self.scoped.restore(self.type_id, self.previous.take());
+            }
+        }
+
+        let scoped = self.scoped.clone();
+        let previous = scoped.insert(value);
+
+        let _guard = ScopeGuard {
+            scoped,
+            previous,
+            type_id: TypeId::of::<R>(),
+        };
+
+        f(self)
+    }
+
     #[inline]
-    pub fn get_resource_mut<R: Resource>(&mut self) -> Option<GlobalResMut<R>> {
+    /// Returns a copy of a global resource, without holding on to a borrow.
+    pub fn clone_resource<R: Resource + Clone>(&self) -> Option<R> {
+        self.global_resources.get_cloned::<R>()
+    }
+
+    #[inline]
+    /// Returns a reference to a global resource, skipping the atomic borrow tracking.
+    ///
+    /// With `runtime-checks`, this still panics if the resource is borrowed mutably.
+    ///
+    /// # Safety
+    /// The resource must not be borrowed mutably (e.g. by a running system), while the returned reference is alive.
+    pub unsafe fn get_resource_ref_unchecked<R: Resource>(&self) -> Option<&R> {
+        unsafe { self.global_resources.get_resource_ref_unchecked::<R>() }
+    }
+
+    #[inline]
+    pub fn get_resource_mut<R: Resource>(&mut self) -> Option<GlobalResMut<'_, R>> {
         let handle = self.global_resources.get_resource_mut::<R>()?;
         Some(GlobalResMut { handle })
     }
 
+    #[inline]
+    /// Returns exclusive handles to multiple distinct global resources at once, e.g. `get_resources_mut::<(A, B)>()`.
+    ///
+    /// Returns `None`, if the same resource is requested twice, or any of them is missing or already borrowed.
+    pub fn get_resources_mut<S: ResourceSet>(&mut self) -> Option<S::Handles<'_>> {
+        S::get_mut(&self.global_resources)
+    }
+
+    /// Registers the event type `E`, by inserting an `Events<E>` global resource.
+    ///
+    /// If the event type is already registered, nothing happens.
+    pub fn add_event<E: Send + Sync + 'static>(&mut self) {
+        if self.global_resources.contains_resource::<Events<E>>() {
+            return;
+        }
+
+        self.insert_resource(Events::<E>::new());
+    }
+
+    /// Tracks the previous values of `C`, readable through the `PrevAndCurr<C>` query term.
+    ///
+    /// Stores a copy of every `C` once per frame (in `clear_trackers`), doubling its memory usage.
+    /// If `C` is already tracked, nothing happens.
+    pub fn track_previous<C: Component + Clone>(&mut self) {
+        let type_id = TypeId::of::<C>();
+        if self.previous_updates.iter().any(|(id, _)| *id == type_id) {
+            return;
+        }
+
+        self.previous_updates.push((type_id, |tables| {
+            for table in tables {
+                table.snapshot_row::<C>();
+            }
+        }));
+    }
+
+    /// Sends an event, readable by `EventReader`s in the next frame.
+    ///
+    /// Registers the event type, if necessary.
+    pub fn send_event<E: Send + Sync + 'static>(&mut self, event: E) {
+        self.add_event::<E>();
+
+        let mut events = unwrap!(self.global_resources.get_resource_mut::<Events<E>>());
+        events.send(event);
+    }
+
+    /// Advances all per-frame state, e.g. making the events sent during the last frame readable.
+    ///
+    /// Called once at the start of every frame by `Schedule::run`.
+    pub fn clear_trackers(&mut self) {
+        self.global_resources.clear_changed();
+        self.global_nosend.clear_changed();
+        self.current_scene.resources.clear_changed();
+        self.current_scene.unsend.clear_changed();
+
+        for (_, update) in &self.frame_updates {
+            update(&self.global_resources);
+        }
+
+        for (_, snapshot) in &self.previous_updates {
+            snapshot(&mut self.current_scene.entities.tables);
+        }
+    }
+
     #[inline]
     pub fn insert_nosend_resource<R: NoSend>(&mut self, res: R) {
         self.global_nosend.insert_resource(res);
     }
 
     #[inline]
-    pub fn get_nosend_resource_ref<R: NoSend>(&self) -> Option<GlobalUnsendRef<R>> {
+    pub fn get_nosend_resource_ref<R: NoSend>(&self) -> Option<GlobalUnsendRef<'_, R>> {
         let handle = self.global_nosend.get_resource_ref::<R>()?.into();
         Some(handle)
     }
 
     #[inline]
-    pub fn get_nosend_resource_mut<R: NoSend>(&mut self) -> Option<GlobalUnsendMut<R>> {
+    pub fn get_nosend_resource_mut<R: NoSend>(&mut self) -> Option<GlobalUnsendMut<'_, R>> {
         let handle = self.global_nosend.get_resource_mut::<R>()?;
         Some(GlobalUnsendMut { handle })
     }
 
     #[inline]
     /// Executes all deferred commands.
-    pub fn apply_commands(&mut self) {
-        self.apply_entity_commands();
-        self.apply_component_commands();
-        self.apply_resource_commands();
+    ///
+    /// Returns the number of applied commands per kind.
+    pub fn apply_commands(&mut self) -> CommandStats {
+        // entities first, so components can be added to reserved entities
+        CommandStats {
+            entity: self.apply_entity_commands(),
+            component: self.apply_component_commands(),
+            resource: self.apply_resource_commands(),
+        }
     }
 
-    fn apply_entity_commands(&mut self) {
+    fn apply_entity_commands(&mut self) -> usize {
+        // resize once for all pending reservations
+        self.current_scene.entities.reserve_slots();
+
         let cmds = self.commands.entity_commands();
 
+        let mut count = 0;
         for cmd in cmds {
+            count += 1;
+
             match cmd {
                 EntityCommands::SpawnEntity(entity) => {
-                    self.current_scene.entities.activate_entity(entity)
+                    Self::activate_reserved(&mut self.current_scene, entity)
+                }
+                EntityCommands::SpawnBatch(entities) => {
+                    for entity in entities {
+                        Self::activate_reserved(&mut self.current_scene, entity);
+                    }
                 }
                 EntityCommands::DeleteEntity(entity) => {
-                    self.current_scene.entities.delete_entity(entity)
+                    self.current_scene.entities.delete_entity(entity);
+                }
+                EntityCommands::DeleteAllWith(type_id) => {
+                    self.current_scene.entities.delete_entities_with(type_id)
                 }
             }
         }
+
+        count
     }
 
-    fn apply_component_commands(&mut self) {
+    fn activate_reserved(scene: &mut Scene, entity: Entity) {
+        // skip stale reservations, the slot is already used by another entity
+        if !scene.entities.activate_entity(entity) {
+            #[cfg(feature = "debug-utils")]
+            {
+                #[cfg(feature = "log")]
+                {
+                    log::warn!("Dropped stale entity reservation: {entity:?}")
+                }
+
+                #[cfg(not(feature = "log"))]
+                {
+                    println!("[WARN] Dropped stale entity reservation: {entity:?}")
+                }
+            }
+        }
+    }
+
+    fn apply_component_commands(&mut self) -> usize {
         let cmds = self.commands.component_commands();
 
+        let mut count = 0;
         for cmd in cmds {
+            count += 1;
+
             match cmd {
                 ComponentCommands::AddComponent {
                     entity,
@@ -128,18 +488,53 @@ impl World {
                     self.current_scene
                         .add_component_untyped(&entity, components, (producer)());
                 }
+                ComponentCommands::DespawnIf {
+                    entity,
+                    type_id,
+                    predicate,
+                    modifier,
+                } => {
+                    self.current_scene
+                        .despawn_if_untyped(entity, type_id, predicate, (modifier)());
+                }
+                ComponentCommands::AddBatch {
+                    entities,
+                    components,
+                    producer,
+                } => {
+                    self.current_scene
+                        .add_batch_untyped(&entities, components, (producer)());
+                }
                 ComponentCommands::RemoveComponent { entity, modifier } => {
                     self.current_scene
                         .remove_components_untyped(entity, (modifier)());
                 }
+                ComponentCommands::MutateComponent {
+                    entity,
+                    type_id,
+                    mutation,
+                    modifier,
+                } => {
+                    self.current_scene.mutate_component_untyped(
+                        &entity,
+                        type_id,
+                        mutation,
+                        (modifier)(),
+                    );
+                }
             }
         }
+
+        count
     }
 
-    fn apply_resource_commands(&mut self) {
+    fn apply_resource_commands(&mut self) -> usize {
         let cmds = self.commands.resource_commands();
 
+        let mut count = 0;
         for cmd in cmds {
+            count += 1;
+
             match cmd {
                 ResourceCommands::AddResource { resource, producer } => {
                     //
@@ -151,30 +546,43 @@ impl World {
                     self.current_scene.remove_resource_untyped(type_id);
                 }
 
-                ResourceCommands::GlobalAddResource { resource, producer } => self
-                    .global_resources
-                    .insert_resource_untyped(resource, producer),
+                ResourceCommands::GlobalAddResource { resource, producer } => {
+                    Self::add_frame_update(
+                        &mut self.frame_updates,
+                        producer.resource_type(),
+                        producer.frame_update(),
+                    );
+                    self.global_resources
+                        .insert_resource_untyped(resource, producer);
+                }
                 ResourceCommands::GlobalRemoveResource { type_id } => {
                     self.global_resources.remove_resource_untyped(type_id)
                 }
+
+                ResourceCommands::GlobalInitNoSend { init } => (init)(&mut self.global_nosend),
             }
         }
+
+        count
     }
 
     #[inline]
-    pub(crate) const fn send_world(&self) -> SendWorld {
+    /// Returns a view of the `World`, that only contains thread-safe parts.
+    pub const fn send_world(&self) -> SendWorld<'_> {
         SendWorld {
             commands: &self.commands,
             scene: self.current_scene.send_scene(),
             global_resource: &self.global_resources,
+            scoped: &self.scoped,
         }
     }
 
-    pub(crate) const fn send_world2(&self) -> SendWorldPtr {
+    pub(crate) const fn send_world2(&self) -> SendWorldPtr<'_> {
         SendWorldPtr {
             commands: &self.commands,
             scene: self.current_scene().send_scene2(),
             global_resource: self.global_resources(),
+            scoped: &self.scoped,
         }
     }
 }
@@ -186,10 +594,65 @@ impl Default for World {
     }
 }
 
+/// Exclusive access to a live `Entity` of the current scene, see `World::entity_mut`.
+///
+/// All changes are applied immediately, unlike with `Commands`.
+pub struct EntityWorldMut<'a> {
+    scene: &'a mut Scene,
+    entity: Entity,
+}
+
+impl EntityWorldMut<'_> {
+    #[inline]
+    pub const fn id(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    pub fn insert<C: ComponentSet>(&mut self, components: C) -> &mut Self {
+        self.scene.add_component(&self.entity, components);
+
+        self
+    }
+
+    #[inline]
+    pub fn remove<C: ComponentSet>(&mut self) -> &mut Self {
+        self.scene.remove_components::<C>(&self.entity);
+
+        self
+    }
+
+    #[inline]
+    /// Returns the component `C`, if the `Entity` has one.
+    pub fn get<C: Component>(&self) -> Option<&C> {
+        let table = self.scene.entities.table_of(&self.entity)?;
+
+        // the scene is borrowed exclusively, so no row can be borrowed mutably
+        unsafe { table.get_component_unchecked(&self.entity) }
+    }
+
+    #[inline]
+    /// Returns the component `C` mutably, if the `Entity` has one.
+    pub fn get_mut<C: Component>(&mut self) -> Option<&mut C> {
+        self.scene
+            .entities
+            .table_of_mut(&self.entity)?
+            .get_component_mut(&self.entity)
+    }
+
+    #[inline]
+    /// Deletes the `Entity` and all its components.
+    pub fn despawn(self) {
+        self.scene.delete_entity(self.entity);
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct SendWorld<'a> {
     pub(crate) commands: &'a CommandCenter,
     pub(crate) scene: SendScene<'a>,
     pub(crate) global_resource: &'a Resources<dyn Resource>,
+    pub(crate) scoped: &'a ScopedResources,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -197,6 +660,7 @@ pub struct SendWorldPtr<'a> {
     pub(crate) commands: *const CommandCenter,
     pub(crate) scene: SendScene2<'a>,
     pub(crate) global_resource: *const Resources<dyn Resource>,
+    pub(crate) scoped: *const ScopedResources,
 }
 
 unsafe impl Send for SendWorldPtr<'_> {}
@@ -204,11 +668,12 @@ unsafe impl Sync for SendWorldPtr<'_> {}
 
 impl SendWorldPtr<'_> {
     #[inline]
-    pub const fn send_world(&self) -> SendWorld {
+    pub const fn send_world(&self) -> SendWorld<'_> {
         SendWorld {
             commands: unsafe { self.commands.as_ref() }.unwrap(),
             scene: self.scene.send_scene(),
             global_resource: unsafe { self.global_resource.as_ref() }.unwrap(),
+            scoped: unsafe { self.scoped.as_ref() }.unwrap(),
         }
     }
 }
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use eonix::{Component, NoSend, Resource};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
@@ -9,8 +11,23 @@ pub struct C2(pub u32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
 pub struct C3(pub u32);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct C4(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct C5(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct C6(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct C7(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct C8(pub u32);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
 pub struct R1(pub u32);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, NoSend)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, NoSend)]
 pub struct R2(pub u32);
@@ -1,6 +1,11 @@
 mod common;
 
-use eonix::{Query, With, WithOut, World};
+use std::any::TypeId;
+
+use eonix::{
+    CommandStats, Component, DynFilter, Entity, Or, PrevAndCurr, Query, QueryState, Scene,
+    SpawnError, With, WithOut, World,
+};
 
 use common::*;
 
@@ -46,7 +51,7 @@ fn test_query_get_optional() {
         ents.push(entity);
     }
 
-    let mut query = Query::<(&C1, Option<&mut C2>)>::new(&scene).unwrap();
+    let mut query = Query::<(&C1, Option<&mut C2>)>::new(scene).unwrap();
     assert_eq!(query.table_count(), 2);
 
     for (i, ent) in ents.iter().enumerate() {
@@ -114,7 +119,7 @@ fn test_add_components() {
 
     //
 
-    scene.add_component(&entity, C3(090));
+    scene.add_component(&entity, C3(90));
 
     {
         let mut query = Query::<&C1>::new(scene).unwrap();
@@ -127,7 +132,7 @@ fn test_add_components() {
 
         let mut query = Query::<&mut C3>::new(scene).unwrap();
         let res = query.get_entity_components(&entity).unwrap();
-        assert_eq!(*res, C3(090));
+        assert_eq!(*res, C3(90));
     }
 }
 
@@ -137,22 +142,22 @@ fn test_remove_components() {
 
     let scene = world.current_scene_mut();
     let entity = scene.spawn_entity();
-    scene.add_component(&entity, C1(001));
-    scene.add_component(&entity, C2(002));
-    scene.add_component(&entity, C3(003));
+    scene.add_component(&entity, C1(1));
+    scene.add_component(&entity, C2(2));
+    scene.add_component(&entity, C3(3));
 
     {
         let mut query = Query::<&C1>::new(scene).unwrap();
         let res = query.get_entity_components(&entity).unwrap();
-        assert_eq!(*res, C1(001));
+        assert_eq!(*res, C1(1));
 
         let mut query = Query::<&C2>::new(scene).unwrap();
         let res = query.get_entity_components(&entity).unwrap();
-        assert_eq!(*res, C2(002));
+        assert_eq!(*res, C2(2));
 
         let mut query = Query::<&C3>::new(scene).unwrap();
         let res = query.get_entity_components(&entity).unwrap();
-        assert_eq!(*res, C3(003));
+        assert_eq!(*res, C3(3));
     }
 
     scene.remove_components::<C1>(&entity);
@@ -163,11 +168,11 @@ fn test_remove_components() {
 
         let mut query = Query::<&C2>::new(scene).unwrap();
         let res = query.get_entity_components(&entity).unwrap();
-        assert_eq!(*res, C2(002));
+        assert_eq!(*res, C2(2));
 
         let mut query = Query::<&C3>::new(scene).unwrap();
         let res = query.get_entity_components(&entity).unwrap();
-        assert_eq!(*res, C3(003));
+        assert_eq!(*res, C3(3));
     }
 
     scene.remove_components::<C2>(&entity);
@@ -181,7 +186,7 @@ fn test_remove_components() {
 
         let mut query = Query::<&C3>::new(scene).unwrap();
         let res = query.get_entity_components(&entity).unwrap();
-        assert_eq!(*res, C3(003));
+        assert_eq!(*res, C3(3));
     }
 
     scene.remove_components::<C3>(&entity);
@@ -211,7 +216,7 @@ fn test_query_iter_single_table() {
         ents.push(entity);
     }
 
-    let mut query = Query::<(&C1, &mut C2)>::new(&scene).unwrap();
+    let mut query = Query::<(&C1, &mut C2)>::new(scene).unwrap();
     assert_eq!(query.table_count(), 1);
 
     let iter = query.iter();
@@ -245,7 +250,7 @@ fn test_query_iter_multiple_table() {
         scene.add_component(entity, C2(i as u32 + 100));
     }
 
-    let mut query = Query::<&C2>::new(&scene).unwrap();
+    let mut query = Query::<&C2>::new(scene).unwrap();
     assert_eq!(query.table_count(), 2);
 
     let mut iter = query.iter().enumerate();
@@ -284,7 +289,7 @@ fn test_query_iter_filter_with() {
         scene.add_component(entity, C2(i as u32 + 100));
     }
 
-    let mut query = Query::<&C2, With<C1>>::new(&scene).unwrap();
+    let mut query = Query::<&C2, With<C1>>::new(scene).unwrap();
     assert_eq!(query.table_count(), 1);
 
     let mut iter = query.iter().enumerate();
@@ -323,7 +328,7 @@ fn test_query_iter_filter_without() {
         scene.add_component(entity, C2(i as u32 + 100));
     }
 
-    let mut query = Query::<&C2, WithOut<C1>>::new(&scene).unwrap();
+    let mut query = Query::<&C2, WithOut<C1>>::new(scene).unwrap();
     assert_eq!(query.table_count(), 1);
 
     let mut iter = query.iter().enumerate();
@@ -335,6 +340,41 @@ fn test_query_iter_filter_without() {
     assert_eq!(iter.next(), None);
 }
 
+#[test]
+fn test_query_filter_single_entity_tables() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let with = scene.spawn_entity();
+    scene.add_component(&with, (C1(1), C2(1)));
+    let without = scene.spawn_entity();
+    scene.add_component(&without, C1(2));
+    // no components at all, not part of any table
+    let empty = scene.spawn_entity();
+
+    let mut query = Query::<&C1, With<C2>>::new(scene).unwrap();
+    assert_eq!(query.table_count(), 1);
+    assert_eq!(query.iter().map(|c1| c1.0).collect::<Vec<_>>(), [1]);
+    drop(query);
+
+    let mut query = Query::<&C1, WithOut<C2>>::new(scene).unwrap();
+    assert_eq!(query.table_count(), 1);
+    assert_eq!(
+        query
+            .iter_with_entities()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>(),
+        [without]
+    );
+    drop(query);
+
+    // nothing left, once the only entity is gone
+    assert!(scene.delete_entity(without));
+    assert!(scene.is_alive(&empty));
+    assert!(Query::<&C1, WithOut<C2>>::new(scene).is_none());
+}
+
 #[test]
 fn test_add_untyped() {
     let mut world = World::new();
@@ -353,7 +393,7 @@ fn test_add_untyped() {
 
     let scene = world.current_scene();
 
-    let mut query = Query::<(&C1, &mut C2)>::new(&scene).unwrap();
+    let mut query = Query::<(&C1, &mut C2)>::new(scene).unwrap();
     assert_eq!(query.table_count(), 1);
 
     let iter = query.iter();
@@ -394,10 +434,38 @@ fn test_remove_components_untyped() {
 
     let scene = world.current_scene();
 
-    let res = Query::<(&C1, &mut C2)>::new(&scene);
+    let res = Query::<(&C1, &mut C2)>::new(scene);
     assert!(res.is_none());
 }
 
+#[test]
+fn test_delete_entity_keeps_other_entities() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    // the table of `e2` is the second one, while slot 1 belongs to `e1`
+    let e0 = scene.spawn_entity();
+    let e1 = scene.spawn_entity();
+    let e2 = scene.spawn_entity();
+    scene.add_component(&e0, C1(0));
+    scene.add_component(&e1, C1(1));
+    scene.add_component(&e2, C2(2));
+
+    // an entity without components has no table to delete from
+    let empty = scene.spawn_entity();
+    assert!(scene.delete_entity(empty));
+
+    // only unlinks `e2`, not the entity at the position of its table
+    assert!(scene.delete_entity(e2));
+    assert!(!scene.is_alive(&e2));
+    assert_eq!(scene.archetype_of(&e2), None);
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(*query.get_entity_components(&e0).unwrap(), C1(0));
+    assert_eq!(*query.get_entity_components(&e1).unwrap(), C1(1));
+}
+
 #[test]
 fn test_delete_entity_untyped() {
     let mut world = World::new();
@@ -428,6 +496,1565 @@ fn test_delete_entity_untyped() {
 
     let scene = world.current_scene();
 
-    let res = Query::<(&C1, &mut C2)>::new(&scene);
+    let res = Query::<(&C1, &mut C2)>::new(scene);
     assert!(res.is_none());
 }
+
+#[test]
+fn test_despawn_all_with() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let mut ents = Vec::with_capacity(30);
+
+    for i in 0..30 {
+        let entity = scene.spawn_entity();
+
+        match i % 3 {
+            0 => scene.add_component(&entity, C1(i)),
+            1 => scene.add_component(&entity, (C1(i), C2(i))),
+            _ => scene.add_component(&entity, (C2(i), C3(i))),
+        }
+
+        ents.push(entity);
+    }
+
+    world.despawn_all_with::<C2>();
+
+    let scene = world.current_scene();
+
+    assert!(Query::<&C2>::new(scene).is_none());
+    assert!(Query::<&C3>::new(scene).is_none());
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(query.table_count(), 1);
+    assert_eq!(query.iter().count(), 10);
+
+    for (i, ent) in ents.iter().enumerate() {
+        assert_eq!(query.get_entity_components(ent).is_some(), i % 3 == 0);
+    }
+}
+
+#[test]
+fn test_despawn_all_with_untyped() {
+    let mut world = World::new();
+
+    let commands = world.commands();
+
+    for i in 0..10 {
+        let entity = commands.reserve_entity();
+        if i % 2 == 0 {
+            commands.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            commands.add_component(&entity, C1(i));
+        }
+    }
+
+    world.apply_commands();
+
+    commands.despawn_all_with::<C2>();
+
+    world.apply_commands();
+
+    let scene = world.current_scene();
+
+    assert!(Query::<&C2>::new(scene).is_none());
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 5);
+}
+
+#[cfg(feature = "debug-utils")]
+#[test]
+fn test_debug_archetypes() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i % 2 == 0 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, C3(i));
+        }
+    }
+
+    let out = world.current_scene().debug_archetypes();
+    assert!(out.starts_with("Scene: 2 archetypes, 10 entities"));
+    assert!(out.contains("common::C1"));
+    assert!(out.contains("common::C2"));
+    assert!(out.contains("common::C3"));
+    assert!(out.contains("len:     5"));
+
+    let out = format!("{world:?}");
+    assert!(out.contains("Scene: 2 archetypes, 10 entities"));
+}
+
+#[test]
+fn test_spawn_entity_with_id() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let ent_3 = Entity::from_raw(3, 7);
+    let ent_0 = Entity::from_raw(0, 2);
+
+    scene.spawn_entity_with_id(ent_3).unwrap();
+    scene.spawn_entity_with_id(ent_0).unwrap();
+
+    scene.add_component(&ent_3, C1(3));
+    scene.add_component(&ent_0, C1(0));
+
+    // occupied slots
+    assert_eq!(
+        scene.spawn_entity_with_id(Entity::from_raw(3, 8)),
+        Err(SpawnError::Occupied(Entity::from_raw(3, 8)))
+    );
+    assert_eq!(
+        scene.spawn_entity_with_id(ent_0),
+        Err(SpawnError::Occupied(ent_0))
+    );
+
+    // skipped slots get reused, without colliding with the spawned ones
+    let mut positions = (0..3)
+        .map(|_| scene.spawn_entity().to_raw().0)
+        .collect::<Vec<_>>();
+    positions.sort_unstable();
+    assert_eq!(positions, [1, 2, 4]);
+
+    // slot can be reused after deleting
+    scene.delete_entity(ent_3);
+    scene.spawn_entity_with_id(Entity::from_raw(3, 8)).unwrap();
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(*query.get_entity_components(&ent_0).unwrap(), C1(0));
+    assert!(query.get_entity_components(&ent_3).is_none());
+    assert!(
+        query
+            .get_entity_components(&Entity::from_raw(3, 8))
+            .is_none()
+    );
+}
+
+#[test]
+fn test_query_iter_table() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i < 4 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, C1(i));
+        }
+    }
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    let ids = query.table_ids().collect::<Vec<_>>();
+    assert_eq!(ids.len(), 2);
+
+    let counts = ids
+        .iter()
+        .map(|id| query.iter_table(*id).count())
+        .collect::<Vec<_>>();
+    assert!(counts == [4, 6] || counts == [6, 4]);
+
+    let values = query.iter_table(ids[0]).map(|c1| c1.0).collect::<Vec<_>>();
+    assert!(values == [0, 1, 2, 3] || values == [4, 5, 6, 7, 8, 9]);
+
+    // table not part of the query
+    let mut query = Query::<&C2>::new(scene).unwrap();
+    let id = query.table_ids().next().unwrap();
+    let missing = ids.into_iter().find(|x| *x != id).unwrap();
+    assert_eq!(query.iter_table(missing).count(), 0);
+}
+
+#[test]
+fn test_query_iter_by_table() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i < 4 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, C1(i));
+        }
+    }
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    let ids = query.table_ids().collect::<Vec<_>>();
+
+    let mut batches = query
+        .iter_by_table()
+        .map(|(id, items)| (id, items.map(|c1| c1.0).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    assert_eq!(batches.iter().map(|(id, _)| *id).collect::<Vec<_>>(), ids);
+
+    batches.sort_by_key(|(_, values)| values.len());
+    assert_eq!(batches[0].1, [0, 1, 2, 3]);
+    assert_eq!(batches[1].1, [4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_reserve_delete_apply() {
+    let mut world = World::new();
+
+    let commands = world.commands();
+
+    // reserve and delete in the same flush
+    let entity = commands.reserve_entity();
+    commands.add_component(&entity, C1(1));
+    commands.delete_entity(entity);
+
+    world.apply_commands();
+
+    assert!(Query::<&C1>::new(world.current_scene()).is_none());
+
+    // reserving again reuses the slot with a new generation
+    let reused = commands.reserve_entity();
+    assert_eq!(reused.to_raw().0, entity.to_raw().0);
+    assert_ne!(reused, entity);
+    commands.add_component(&reused, C1(2));
+    commands.add_component(&entity, C1(3));
+
+    world.apply_commands();
+
+    let mut query = Query::<&C1>::new(world.current_scene()).unwrap();
+    assert_eq!(*query.get_entity_components(&reused).unwrap(), C1(2));
+    assert!(query.get_entity_components(&entity).is_none());
+    assert_eq!(query.iter().count(), 1);
+}
+
+#[test]
+fn test_stale_reservation() {
+    let mut world = World::new();
+
+    let commands = world.commands();
+
+    // the second activation of the same reservation is dropped
+    let entity = commands.reserve_entity();
+    commands.spawn_reserved(entity);
+    commands.add_component(&entity, C1(1));
+
+    world.apply_commands();
+
+    let scene = world.current_scene_mut();
+    assert!(scene.is_alive(&entity));
+    assert_eq!(scene.iter_entities().collect::<Vec<_>>(), [entity]);
+
+    // the slot isn't handed out again
+    let other = scene.spawn_entity();
+    assert_ne!(other.to_raw().0, entity.to_raw().0);
+    scene.add_component(&other, C1(2));
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(*query.get_entity_components(&entity).unwrap(), C1(1));
+    assert_eq!(*query.get_entity_components(&other).unwrap(), C1(2));
+    assert_eq!(query.iter().count(), 2);
+}
+
+#[test]
+fn test_query_aggregates() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i % 2 == 0 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, C1(i));
+        }
+    }
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(query.count_matching(|c1| c1.0 >= 5), 5);
+    assert_eq!(query.count_matching(|_| false), 0);
+    assert!(query.any(|c1| c1.0 == 9));
+    assert!(!query.any(|c1| c1.0 > 9));
+    assert!(query.all(|c1| c1.0 < 10));
+    assert!(!query.all(|c1| c1.0 < 9));
+
+    // short-circuits on the first match
+    let mut visited = 0;
+    assert!(query.any(|_| {
+        visited += 1;
+        true
+    }));
+    assert_eq!(visited, 1);
+
+    let mut query = Query::<&mut C2>::new(scene).unwrap();
+    assert_eq!(query.count_matching(|c2| c2.0 % 4 == 0), 3);
+}
+
+#[test]
+fn test_reserve_many_entities() {
+    let mut world = World::new();
+
+    let commands = world.commands();
+    let entities = (0..10_000)
+        .map(|i| {
+            let entity = commands.reserve_entity();
+            commands.add_component(&entity, C1(i));
+            entity
+        })
+        .collect::<Vec<_>>();
+
+    world.apply_commands();
+
+    let mut query = Query::<&C1>::new(world.current_scene()).unwrap();
+    assert_eq!(query.iter().count(), 10_000);
+
+    for (i, entity) in entities.iter().enumerate() {
+        let c1 = query.get_entity_components(entity).unwrap();
+        assert_eq!(c1.0, i as u32);
+    }
+}
+
+#[test]
+fn test_or_filter_requires_extract() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let e1 = scene.spawn_entity();
+    scene.add_component(&e1, (C1(1), C2(1)));
+    let e2 = scene.spawn_entity();
+    scene.add_component(&e2, (C1(2), C3(2)));
+    let e3 = scene.spawn_entity();
+    scene.add_component(&e3, C1(3));
+    // matches the filter, but lacks the extracted component
+    let e4 = scene.spawn_entity();
+    scene.add_component(&e4, C2(4));
+
+    let mut query = Query::<&C1, Or<With<C2>, With<C3>>>::new(scene).unwrap();
+    let mut values = query.iter().map(|c1| c1.0).collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, [1, 2]);
+
+    // optional extracts don't restrict the matched tables
+    let mut query = Query::<(Option<&C1>, &C2), Or<With<C3>, WithOut<C3>>>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 2);
+
+    // no table has C3 without C1
+    assert!(Query::<&C3, Or<WithOut<C1>, With<C2>>>::new(scene).is_none());
+}
+
+#[cfg(feature = "runtime-checks")]
+#[test]
+#[should_panic]
+fn test_or_filter_extract_conflict() {
+    let world = World::new();
+
+    let _ = Query::<&C1, Or<With<C1>, With<C2>>>::new(world.current_scene());
+}
+
+#[test]
+fn test_query_table_slices() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i < 3 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, (C1(i), C3(i)));
+        }
+    }
+
+    let query = Query::<(&C1, Option<&C2>)>::new(scene).unwrap();
+    let slices = query.table_slices::<C1>().collect::<Vec<_>>();
+    assert_eq!(slices.len(), 2);
+    let sum = slices
+        .iter()
+        .flat_map(|s| s.iter())
+        .map(|c1| c1.0)
+        .sum::<u32>();
+    assert_eq!(sum, (0..10).sum());
+
+    // optional term, only present in one table
+    let c2 = query.table_slices::<C2>().collect::<Vec<_>>();
+    assert_eq!(c2, [[C2(0), C2(1), C2(2)].as_slice()]);
+    drop(query);
+
+    let mut query = Query::<(&mut C1, &C3)>::new(scene).unwrap();
+    for slice in query.table_slices_mut::<C1>() {
+        for c1 in slice {
+            c1.0 *= 2;
+        }
+    }
+    drop(query);
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    let per_element = query.iter().map(|c1| c1.0).sum::<u32>();
+    let sliced = query
+        .table_slices::<C1>()
+        .map(|s| s.iter().map(|c1| c1.0).sum::<u32>())
+        .sum::<u32>();
+    assert_eq!(per_element, sliced);
+    assert_eq!(
+        sliced,
+        (0..3).sum::<u32>() + (3..10).map(|i| i * 2).sum::<u32>()
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_query_table_slices_not_mut() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entity = scene.spawn_entity();
+    scene.add_component(&entity, C1(0));
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    let _ = query.table_slices_mut::<C1>().count();
+}
+
+#[test]
+fn test_query_table_columns() {
+    let mut world = World::new();
+
+    // C1 as position, C2 as velocity
+    let scene = world.current_scene_mut();
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i < 4 {
+            scene.add_component(&entity, (C1(i), C2(i + 1)));
+        } else {
+            scene.add_component(&entity, (C1(i), C2(i + 1), C3(i)));
+        }
+    }
+
+    let mut query = Query::<(&mut C1, &C2)>::new(scene).unwrap();
+    let expected = query.iter().map(|(c1, c2)| c1.0 + c2.0).collect::<Vec<_>>();
+
+    let mut tables = 0;
+    for (velocity, position) in query.table_columns::<C2, C1>() {
+        assert_eq!(velocity.len(), position.len());
+
+        for (c1, c2) in position.iter_mut().zip(velocity) {
+            c1.0 += c2.0;
+        }
+        tables += 1;
+    }
+    assert_eq!(tables, 2);
+
+    let integrated = query.iter().map(|(c1, _)| c1.0).collect::<Vec<_>>();
+    assert_eq!(integrated, expected);
+}
+
+#[test]
+#[should_panic]
+fn test_query_table_columns_same_type() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entity = scene.spawn_entity();
+    scene.add_component(&entity, C1(0));
+
+    let mut query = Query::<&mut C1>::new(scene).unwrap();
+    let _ = query.table_columns::<C1, C1>().count();
+}
+
+#[cfg(feature = "large_tuples")]
+#[test]
+fn test_largest_tuples() {
+    use eonix::Component;
+
+    #[derive(Debug, PartialEq, Component)]
+    struct C4(u32);
+    #[derive(Debug, PartialEq, Component)]
+    struct C5(u32);
+    #[derive(Debug, PartialEq, Component)]
+    struct C6(u32);
+    #[derive(Debug, PartialEq, Component)]
+    struct C7(u32);
+    #[derive(Debug, PartialEq, Component)]
+    struct C8(u32);
+
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let e1 = scene.spawn_entity();
+    scene.add_component(
+        &e1,
+        (C1(1), C2(2), C3(3), C4(4), C5(5), C6(6), C7(7), 8u32, 9i32),
+    );
+    let e2 = scene.spawn_entity();
+    scene.add_component(&e2, (C1(1), C2(2), C3(3), C4(4), C5(5), C6(6), C7(7)));
+
+    let mut query =
+        Query::<(&C1, &C2, &C3, &C4, &C5, &C6, &C7, &u32, &mut i32)>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 1);
+    let (c1, .., c7, u, i) = query.get_entity_components(&e1).unwrap();
+    assert_eq!((c1.0, c7.0, *u, *i), (1, 7, 8, 9));
+    drop(query);
+
+    let mut query = Query::<
+        &C2,
+        (
+            With<C1>,
+            With<C3>,
+            With<C4>,
+            With<C5>,
+            With<C6>,
+            With<C7>,
+            WithOut<u32>,
+            WithOut<i32>,
+            WithOut<C8>,
+        ),
+    >::new(scene)
+    .unwrap();
+    assert_eq!(query.iter().count(), 1);
+    drop(query);
+
+    scene.remove_components::<(C1, C2, C3, C4, C5, C6, C7, u32, i32)>(&e1);
+    let mut query = Query::<(&C1, &C2, &C3, &C4, &C5, &C6, &C7)>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 1);
+}
+
+#[test]
+fn test_tuple_arities() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let e1 = scene.spawn_entity();
+    scene.add_component(&e1, (C1(1), C2(2), C3(3)));
+    let e2 = scene.spawn_entity();
+    scene.add_component(&e2, (C1(1), C2(2), C3(3), 4u32, 5i32));
+
+    // push into existing table, that has more rows than the set
+    scene.add_component(&e1, (4u32, 5i32));
+
+    let mut query = Query::<(&C1, &C2, &C3, &u32, &i32)>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 2);
+    let (c1, c2, c3, u, i) = query.get_entity_components(&e1).unwrap();
+    assert_eq!((c1.0, c2.0, c3.0, *u, *i), (1, 2, 3, 4, 5));
+}
+
+#[test]
+fn test_insert_if_new() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let e1 = scene.spawn_entity();
+    scene.add_component_if_new(&e1, C1(1));
+    scene.add_component_if_new(&e1, C1(2));
+    scene.add_component_if_new(&e1, (C1(3), C2(3)));
+
+    let mut query = Query::<(&C1, &C2)>::new(scene).unwrap();
+    let (c1, c2) = query.get_entity_components(&e1).unwrap();
+    assert_eq!((c1.0, c2.0), (1, 3));
+    drop(query);
+
+    // via commands
+    let commands = world.commands();
+    let e2 = commands.reserve_entity();
+    commands.add_component(&e2, (C1(10), C2(10)));
+    commands.insert_if_new(&e2, (C2(20), C3(20)));
+    commands.insert_if_new(&e1, C2(20));
+    world.apply_commands();
+
+    let mut query = Query::<(&C1, &C2, Option<&C3>)>::new(world.current_scene()).unwrap();
+    let (c1, c2, c3) = query.get_entity_components(&e2).unwrap();
+    assert_eq!((c1.0, c2.0, c3.map(|c3| c3.0)), (10, 10, Some(20)));
+    let (c1, c2, c3) = query.get_entity_components(&e1).unwrap();
+    assert_eq!((c1.0, c2.0, c3), (1, 3, None));
+}
+
+#[test]
+fn test_query_iter_with_index() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i % 3 == 0 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, C1(i));
+        }
+    }
+
+    let mut query = Query::<&mut C1>::new(scene).unwrap();
+    let order = query.iter().map(|c1| c1.0).collect::<Vec<_>>();
+
+    // write into a side buffer, using the index
+    let mut buffer = vec![0; 10];
+    for (index, c1) in query.iter_with_index() {
+        buffer[index] = c1.0;
+        c1.0 += 100;
+    }
+    assert_eq!(buffer, order);
+
+    let indices = query
+        .iter_with_index()
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    assert_eq!(indices, (0..10).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_archetype_of() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let archetype = |scene: &Scene, entity: &Entity| {
+        scene.archetype_of(entity).map(|mut types| {
+            types.sort();
+            types
+        })
+    };
+    let types = |mut types: Vec<TypeId>| {
+        types.sort();
+        Some(types)
+    };
+
+    let entity = scene.spawn_entity();
+    assert_eq!(archetype(scene, &entity), None);
+
+    scene.add_component(&entity, (C1(1), C2(2)));
+    assert_eq!(
+        archetype(scene, &entity),
+        types(vec![TypeId::of::<C1>(), TypeId::of::<C2>()])
+    );
+
+    scene.add_component(&entity, C3(3));
+    assert_eq!(
+        archetype(scene, &entity),
+        types(vec![
+            TypeId::of::<C1>(),
+            TypeId::of::<C2>(),
+            TypeId::of::<C3>()
+        ])
+    );
+
+    scene.remove_components::<(C1, C2)>(&entity);
+    assert_eq!(archetype(scene, &entity), types(vec![TypeId::of::<C3>()]));
+
+    assert_eq!(
+        scene.archetype_names_of(&entity),
+        Some(vec![std::any::type_name::<C3>()])
+    );
+
+    scene.remove_components::<C3>(&entity);
+    assert_eq!(archetype(scene, &entity), None);
+
+    scene.add_component(&entity, C1(1));
+    scene.delete_entity(entity);
+    assert_eq!(archetype(scene, &entity), None);
+}
+
+#[test]
+fn test_apply_commands_stats() {
+    let mut world = World::new();
+
+    // nothing to apply
+    let stats = world.apply_commands();
+    assert!(stats.is_empty());
+
+    let commands = world.commands();
+    let e1 = commands.reserve_entity();
+    let e2 = commands.reserve_entity();
+    commands.add_component(&e1, C1(1));
+    commands.add_component(&e2, (C1(2), C2(2)));
+    commands.remove_component::<C2>(&e2);
+    commands.delete_entity(e1);
+    commands.add_global_resource(R1(1));
+
+    let stats = world.apply_commands();
+    assert_eq!(
+        stats,
+        CommandStats {
+            entity: 3,
+            component: 3,
+            resource: 1,
+        }
+    );
+    assert_eq!(stats.total(), 7);
+
+    // all commands were consumed
+    assert!(world.apply_commands().is_empty());
+}
+
+#[test]
+fn test_prev_and_curr() {
+    let mut world = World::new();
+    world.track_previous::<C1>();
+
+    let scene = world.current_scene_mut();
+    let e1 = scene.spawn_entity();
+    let e2 = scene.spawn_entity();
+    scene.add_component(&e1, C1(1));
+    scene.add_component(&e2, (C1(2), C2(2)));
+
+    // not yet snapshotted, the current value is reported twice
+    let mut query = Query::<PrevAndCurr<C1>>::new(scene).unwrap();
+    assert!(query.all(|(prev, curr)| prev == curr));
+    drop(query);
+
+    world.clear_trackers();
+
+    let scene = world.current_scene_mut();
+    let mut query = Query::<&mut C1>::new(scene).unwrap();
+    for c1 in query.iter() {
+        c1.0 += 10;
+    }
+    drop(query);
+
+    let mut query = Query::<PrevAndCurr<C1>>::new(scene).unwrap();
+    let (prev, curr) = query.get_entity_components(&e1).unwrap();
+    assert_eq!((prev.0, curr.0), (1, 11));
+    let (prev, curr) = query.get_entity_components(&e2).unwrap();
+    assert_eq!((prev.0, curr.0), (2, 12));
+    drop(query);
+
+    // previous values survive structural changes
+    let e3 = scene.spawn_entity();
+    scene.add_component(&e3, (C1(3), C2(3)));
+    scene.delete_entity(e2);
+
+    let mut query = Query::<(PrevAndCurr<C1>, &C2)>::new(scene).unwrap();
+    let ((prev, curr), _) = query.get_entity_components(&e3).unwrap();
+    assert_eq!((prev.0, curr.0), (3, 3));
+    drop(query);
+
+    let mut query = Query::<PrevAndCurr<C1>>::new(scene).unwrap();
+    let (prev, curr) = query.get_entity_components(&e1).unwrap();
+    assert_eq!((prev.0, curr.0), (1, 11));
+    drop(query);
+
+    world.clear_trackers();
+
+    let scene = world.current_scene_mut();
+    let mut query = Query::<PrevAndCurr<C1>>::new(scene).unwrap();
+    assert!(query.all(|(prev, curr)| prev == curr));
+}
+
+#[derive(Debug, Component)]
+#[component(name = "renamed")]
+struct Renamed;
+
+#[test]
+fn test_component_name() {
+    assert_eq!(C1::name(), std::any::type_name::<C1>());
+    assert_eq!(Renamed::name(), "renamed");
+
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entity = scene.spawn_entity();
+    scene.add_component(&entity, Renamed);
+
+    assert_eq!(scene.archetype_names_of(&entity), Some(vec!["renamed"]));
+}
+
+#[test]
+fn test_take_and_swap_scene() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let mut ents = Vec::new();
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, (C1(i), C2(i)));
+        ents.push(entity);
+    }
+
+    let taken = world.take_scene();
+
+    // the world is empty now
+    assert!(Query::<&C1>::new(world.current_scene()).is_none());
+    assert_eq!(world.current_scene().archetype_of(&ents[0]), None);
+
+    // the taken scene still works
+    let mut query = Query::<(&C1, &C2)>::new(&taken).unwrap();
+    for (i, entity) in ents.iter().enumerate() {
+        let (c1, c2) = query.get_entity_components(entity).unwrap();
+        assert_eq!((c1.0, c2.0), (i as u32, i as u32));
+    }
+    drop(query);
+
+    // swap it back in
+    let empty = world.swap_scene(taken);
+    assert!(Query::<&C1>::new(&empty).is_none());
+
+    let mut query = Query::<&C1>::new(world.current_scene()).unwrap();
+    assert_eq!(query.iter().count(), 10);
+}
+
+#[test]
+fn test_remove_components_batch() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    // spread over two source tables
+    let mut ents = Vec::with_capacity(1000);
+    for i in 0..1000 {
+        let entity = scene.spawn_entity();
+        if i % 2 == 0 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, (C1(i), C3(i)));
+        }
+        ents.push(entity);
+    }
+
+    // an entity only having C1 and a duplicate
+    let single = scene.spawn_entity();
+    scene.add_component(&single, C1(0));
+    ents.push(single);
+    ents.push(ents[0]);
+
+    scene.remove_components_batch::<C1>(&ents);
+
+    assert!(Query::<&C1>::new(scene).is_none());
+    assert_eq!(scene.archetype_of(&single), None);
+
+    let mut query = Query::<&C2>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 500);
+    for (i, entity) in ents.iter().take(1000).enumerate().step_by(2) {
+        assert_eq!(query.get_entity_components(entity).unwrap().0, i as u32);
+    }
+    drop(query);
+
+    let mut query = Query::<&C3>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 500);
+    for (i, entity) in ents.iter().take(1000).enumerate().skip(1).step_by(2) {
+        assert_eq!(query.get_entity_components(entity).unwrap().0, i as u32);
+    }
+}
+
+#[test]
+fn test_contains_component() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let entity = scene.spawn_entity();
+    assert!(!scene.contains_component::<C1>(&entity));
+
+    scene.add_component(&entity, (C1(1), C2(2)));
+    assert!(scene.contains_component::<C1>(&entity));
+    assert!(scene.contains_component::<C2>(&entity));
+    assert!(!scene.contains_component::<C3>(&entity));
+
+    scene.remove_components::<C1>(&entity);
+    assert!(!scene.contains_component::<C1>(&entity));
+    assert!(scene.contains_component::<C2>(&entity));
+
+    scene.delete_entity(entity);
+    assert!(!scene.contains_component::<C2>(&entity));
+}
+
+#[test]
+fn test_query_iter_size_hint() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..10 {
+        let entity = scene.spawn_entity();
+        if i % 3 == 0 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, C1(i));
+        }
+    }
+
+    // multiple tables
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(query.table_count(), 2);
+
+    let mut iter = query.iter();
+    assert_eq!(iter.size_hint(), (10, Some(10)));
+
+    iter.next();
+    iter.next();
+    assert_eq!(iter.len(), 8);
+    assert_eq!(iter.count(), 8);
+
+    // single table
+    let mut query = Query::<(&C1, &mut C2)>::new(scene).unwrap();
+    assert_eq!(query.iter().len(), 4);
+    assert_eq!(query.iter().count(), 4);
+    assert_eq!(query.iter_with_index().len(), 4);
+}
+
+#[test]
+fn test_spawner_free_count_and_compact() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let entities = (0..10).map(|_| scene.spawn_entity()).collect::<Vec<_>>();
+    assert_eq!(scene.spawner().free_count(), 0);
+
+    // churn, delete from the back
+    for entity in entities.iter().rev().step_by(2) {
+        scene.delete_entity(*entity);
+    }
+    assert_eq!(scene.spawner().free_count(), 5);
+
+    // free-list is FIFO, so the highest position would be reused first
+    scene.spawner().compact();
+    assert_eq!(scene.spawner().free_count(), 5);
+
+    let reused = (0..5)
+        .map(|_| scene.spawn_entity().to_raw().0)
+        .collect::<Vec<_>>();
+    assert_eq!(reused, vec![1, 3, 5, 7, 9]);
+    assert_eq!(scene.spawner().free_count(), 0);
+    assert_eq!(scene.spawner().reserved_len(), 10);
+}
+
+#[test]
+fn test_query_dyn_filter() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..8 {
+        let entity = scene.spawn_entity();
+        match i % 4 {
+            0 => scene.add_component(&entity, (C1(i), C3(i))),
+            1 => scene.add_component(&entity, (C1(i), C2(i), C3(i))),
+            2 => scene.add_component(&entity, C3(i)),
+            _ => scene.add_component(&entity, C1(i)),
+        }
+    }
+
+    // has C1, not C2
+    let filter = DynFilter::new()
+        .with(TypeId::of::<C1>())
+        .without(TypeId::of::<C2>());
+
+    let mut query = Query::<&C3>::new_dyn(scene, &filter).unwrap();
+    assert_eq!(query.table_count(), 1);
+
+    let mut values = query.iter().map(|c3| c3.0).collect::<Vec<_>>();
+    values.sort();
+    assert_eq!(values, vec![0, 4]);
+
+    // nothing matches
+    let filter = DynFilter::new()
+        .with(TypeId::of::<C2>())
+        .without(TypeId::of::<C1>());
+    assert!(Query::<&C3>::new_dyn(scene, &filter).is_none());
+}
+
+#[test]
+fn test_get_or_spawn() {
+    let mut world = World::new();
+
+    let entity = Entity::from_raw(5, 1);
+
+    // referenced before being spawned
+    world
+        .get_or_spawn(entity)
+        .unwrap()
+        .insert(C1(1))
+        .insert((C2(2), C3(3)));
+
+    // already alive, add to it
+    let mut handle = world.get_or_spawn(entity).unwrap();
+    assert_eq!(handle.id(), entity);
+    handle.remove::<C3>().insert_if_new(C1(10));
+    assert!(handle.contains::<C1>());
+    assert!(!handle.contains::<C3>());
+
+    let scene = world.current_scene();
+    let mut query = Query::<(&C1, &C2)>::new(scene).unwrap();
+    let (c1, c2) = query.get_entity_components(&entity).unwrap();
+    assert_eq!((c1, c2), (&C1(1), &C2(2)));
+    drop(query);
+
+    // slot is live with another generation
+    assert!(matches!(
+        world.get_or_spawn(Entity::from_raw(5, 2)),
+        Err(SpawnError::GenerationMismatch(_))
+    ));
+
+    // the dead slot has the same generation, marked as invalid
+    assert!(world.despawn(entity));
+    let invalid = Entity::from_raw(5, 0x8000_0001);
+    assert!(matches!(
+        world.get_or_spawn(invalid),
+        Err(SpawnError::InvalidGeneration(_))
+    ));
+    assert!(!world.current_scene().is_alive(&invalid));
+}
+
+fn sum_c1(query: &mut Query<&C1>) -> u32 {
+    query.iter().map(|c1| c1.0).sum()
+}
+
+#[test]
+fn test_query_transmute_lens() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    for i in 0..6 {
+        let entity = scene.spawn_entity();
+        if i % 2 == 0 {
+            scene.add_component(&entity, (C1(i), C2(i)));
+        } else {
+            scene.add_component(&entity, (C1(i), C2(i), C3(i)));
+        }
+    }
+
+    let mut query = Query::<(&C1, &mut C2, Option<&C3>)>::new(scene).unwrap();
+
+    // pass a narrowed query to a helper
+    assert_eq!(sum_c1(&mut query.transmute_lens::<&C1>().unwrap()), 15);
+
+    // mutable terms can be narrowed to shared ones, optional ones filter the tables
+    {
+        let mut lens = query.transmute_lens::<(&mut C2, &C3)>().unwrap();
+        assert_eq!(lens.table_count(), 1);
+
+        for (c2, c3) in lens.iter() {
+            c2.0 += c3.0;
+        }
+    }
+
+    // the original query is restored
+    assert_eq!(query.table_count(), 2);
+    let c2 = query.iter().map(|(_, c2, _)| c2.0).sum::<u32>();
+    assert_eq!(c2, 15 + 9);
+}
+
+#[test]
+fn test_query_transmute_lens_no_tables() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    for i in 0..3 {
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, (C1(i), C2(i)));
+    }
+
+    // no table has a `C3`
+    let mut query = Query::<(&C1, &mut C2, Option<&C3>)>::new(scene).unwrap();
+    assert!(query.transmute_lens::<(&mut C2, &C3)>().is_none());
+
+    // the original query is untouched
+    assert_eq!(query.table_count(), 1);
+    assert_eq!(query.iter().count(), 3);
+}
+
+#[test]
+#[should_panic(expected = "is not covered by the terms of this query")]
+fn test_query_transmute_lens_upgrade() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entity = scene.spawn_entity();
+    scene.add_component(&entity, (C1(0), C2(0)));
+
+    let mut query = Query::<(&C1, &mut C2)>::new(scene).unwrap();
+    let _lens = query.transmute_lens::<&mut C1>();
+}
+
+#[test]
+#[should_panic(expected = "is not covered by the terms of this query")]
+fn test_query_transmute_lens_missing_term() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entity = scene.spawn_entity();
+    scene.add_component(&entity, (C1(0), C2(0)));
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    let _lens = query.transmute_lens::<&C2>();
+}
+
+#[test]
+fn test_iter_entities() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    assert_eq!(scene.iter_entities().count(), 0);
+
+    let with_c1 = scene.spawn_entity();
+    scene.add_component(&with_c1, C1(0));
+    let empty = scene.spawn_entity();
+    let with_c2 = scene.spawn_entity();
+    scene.add_component(&with_c2, (C1(2), C2(2)));
+    let deleted = scene.spawn_entity();
+    scene.delete_entity(deleted);
+
+    // only reserved, not spawned
+    let _reserved = scene.spawner().reserve();
+
+    assert_eq!(
+        scene.iter_entities().collect::<Vec<_>>(),
+        vec![with_c1, empty, with_c2]
+    );
+}
+
+#[test]
+fn test_query_iter_excluding() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+
+    let entities = (0..10)
+        .map(|i| {
+            let entity = scene.spawn_entity();
+            if i % 2 == 0 {
+                scene.add_component(&entity, C1(i));
+            } else {
+                scene.add_component(&entity, (C1(i), C2(i)));
+            }
+            entity
+        })
+        .collect::<Vec<_>>();
+
+    let mut query = Query::<&C1>::new(scene).unwrap();
+
+    for (entity, c1) in query.iter_with_entities() {
+        assert_eq!(entities[c1.0 as usize], entity);
+    }
+
+    let mut values = query
+        .iter_excluding(&[entities[3], entities[8]])
+        .map(|c1| c1.0)
+        .collect::<Vec<_>>();
+    values.sort_unstable();
+    assert_eq!(values, vec![0, 1, 2, 4, 5, 6, 7, 9]);
+}
+
+#[test]
+fn test_query_state_picks_up_new_tables() {
+    let mut world = World::new();
+    let state = QueryState::<&C1>::new();
+
+    // nothing to match yet
+    assert!(state.get(world.current_scene()).is_none());
+
+    let scene = world.current_scene_mut();
+    for i in 0..5 {
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, C1(i));
+    }
+
+    let mut query = state.get(world.current_scene()).unwrap();
+    assert_eq!(query.table_count(), 1);
+    assert_eq!(query.iter().count(), 5);
+    drop(query);
+
+    // a new matching table, and one that does not match
+    let scene = world.current_scene_mut();
+    for i in 0..3 {
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, (C1(i), C2(i)));
+
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, C3(i));
+    }
+
+    let mut query = state.get(world.current_scene()).unwrap();
+    assert_eq!(query.table_count(), 2);
+    assert_eq!(query.iter().count(), 8);
+    drop(query);
+
+    // removed tables are dropped from the cache
+    world.current_scene_mut().despawn_all_with::<C2>();
+
+    let mut query = state.get(world.current_scene()).unwrap();
+    assert_eq!(query.table_count(), 1);
+    assert_eq!(query.iter().count(), 5);
+}
+
+#[test]
+fn test_world_despawn() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let with_components = scene.spawn_entity();
+    scene.add_component(&with_components, (C1(0), C2(0)));
+    let other = scene.spawn_entity();
+    scene.add_component(&other, (C1(1), C2(1)));
+    let without_components = scene.spawn_entity();
+
+    assert!(world.despawn(with_components));
+    assert!(world.despawn(without_components));
+
+    // already dead
+    assert!(!world.despawn(with_components));
+    assert!(!world.despawn(without_components));
+
+    let scene = world.current_scene();
+    assert!(!scene.contains_component::<C1>(&with_components));
+    assert!(scene.contains_component::<C1>(&other));
+    assert_eq!(scene.iter_entities().collect::<Vec<_>>(), [other]);
+}
+
+#[test]
+fn test_spawn_from_template() {
+    let mut world = World::new();
+    let template = (C1(1), C2(2));
+
+    let scene = world.current_scene_mut();
+    let mut entities = scene.spawn_cloned(&template, 99);
+
+    let entity = scene.spawn_entity();
+    scene.add_component_cloned(&entity, &template);
+    entities.push(entity);
+
+    // the template is still usable
+    assert_eq!(template, (C1(1), C2(2)));
+
+    // every entity got its own copy
+    let mut query = Query::<&mut C1>::new(scene).unwrap();
+    assert_eq!(query.iter().len(), 100);
+    query.get_entity_components(&entities[0]).unwrap().0 = 10;
+
+    let values = entities
+        .iter()
+        .map(|entity| query.get_entity_components(entity).unwrap().0)
+        .collect::<Vec<_>>();
+    assert_eq!(values[0], 10);
+    assert!(values[1..].iter().all(|value| *value == 1));
+}
+
+#[test]
+fn test_world_entity_range() {
+    let mut shard_a = World::new_with_entity_range(0..1000);
+    let mut shard_b = World::new_with_entity_range(1000..2000);
+
+    let a = (0..100)
+        .map(|_| shard_a.current_scene_mut().spawn_entity())
+        .collect::<Vec<_>>();
+    let b = (0..100)
+        .map(|_| shard_b.current_scene_mut().spawn_entity())
+        .collect::<Vec<_>>();
+
+    assert!(a.iter().all(|entity| !b.contains(entity)));
+    assert_eq!(shard_b.current_scene().spawner().reserved_len(), 1100);
+
+    // entities of the shard can be used as usual
+    let scene = shard_b.current_scene_mut();
+    scene.add_component(&b[0], C1(0));
+    assert!(scene.contains_component::<C1>(&b[0]));
+    assert_eq!(scene.iter_entities().count(), 100);
+    assert_eq!(scene.iter_entities().collect::<Vec<_>>(), b);
+
+    // entities of the other shard have no slot here
+    assert!(!scene.is_alive(&a[0]));
+    assert!(matches!(
+        scene.spawn_entity_with_id(Entity::from_raw(5, 0)),
+        Err(SpawnError::OutOfRange(_))
+    ));
+    assert!(matches!(
+        scene.spawn_entity_with_id(Entity::from_raw(2000, 0)),
+        Err(SpawnError::OutOfRange(_))
+    ));
+}
+
+#[test]
+fn test_world_entity_range_large_start() {
+    let start = 1 << 30;
+    let mut world = World::new_with_entity_range(start..start + 1000);
+
+    // slots start at the range, instead of growing up to it
+    let commands = world.commands();
+    let entities = (0..10)
+        .map(|i| {
+            let entity = commands.reserve_entity();
+            commands.add_component(&entity, C1(i));
+            entity
+        })
+        .collect::<Vec<_>>();
+    world.apply_commands();
+
+    assert!(entities.iter().all(|entity| entity.to_raw().0 >= start));
+
+    let mut query = Query::<&C1>::new(world.current_scene()).unwrap();
+    for (i, entity) in entities.iter().enumerate() {
+        assert_eq!(query.get_entity_components(entity).unwrap().0, i as u32);
+    }
+    drop(query);
+
+    // freed positions are reused within the range
+    assert!(world.despawn(entities[3]));
+    let reused = world.current_scene_mut().spawn_entity();
+    assert_eq!(reused.to_raw().0, entities[3].to_raw().0);
+}
+
+#[test]
+#[should_panic(expected = "Entity range exhausted")]
+fn test_world_entity_range_exhausted() {
+    let mut world = World::new_with_entity_range(10..13);
+
+    let scene = world.current_scene_mut();
+    for _ in 0..3 {
+        scene.spawn_entity();
+    }
+
+    scene.spawn_entity();
+}
+
+#[test]
+fn test_query_get_superset_archetype() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entity = scene.spawn_entity();
+    scene.add_component(&entity, (C1(1), C2(2), C3(3)));
+    let other = scene.spawn_entity();
+    scene.add_component(&other, C1(4));
+
+    // the entity is stored in the (C1, C2, C3) table, which the query extracts C1 from
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(query.get_entity_components(&entity), Some(&C1(1)));
+    assert_eq!(query.get_entity_components(&other), Some(&C1(4)));
+}
+
+#[test]
+fn test_scene_table_count() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    assert!(scene.is_empty());
+    assert_eq!(scene.table_count(), 0);
+
+    // entities without components are not stored in tables
+    let empty = scene.spawn_entity();
+    assert!(scene.is_empty());
+
+    for i in 0..3 {
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, C1(i));
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, (C1(i), C2(i)));
+    }
+
+    assert!(!scene.is_empty());
+    assert_eq!(scene.table_count(), 2);
+    assert_eq!(scene.iter_entities().count(), 7);
+    assert!(scene.iter_entities().any(|entity| entity == empty));
+}
+
+#[test]
+fn test_commands_spawn_batch() {
+    let mut world = World::new();
+
+    let commands = world.commands();
+    let entities = commands.spawn_batch((0..1_000).map(|i| (C1(i), C2(i))));
+    assert_eq!(entities.len(), 1_000);
+
+    // one entity and one component command for the whole batch
+    let stats = world.apply_commands();
+    assert_eq!(stats.entity, 1);
+    assert_eq!(stats.component, 1);
+
+    let scene = world.current_scene();
+    assert_eq!(scene.table_count(), 1);
+
+    let mut query = Query::<(&C1, &C2)>::new(scene).unwrap();
+    for (i, entity) in entities.iter().enumerate() {
+        let (c1, c2) = query.get_entity_components(entity).unwrap();
+        assert_eq!(c1.0, i as u32);
+        assert_eq!(c2.0, i as u32);
+    }
+    assert_eq!(query.iter().count(), 1_000);
+}
+
+#[test]
+fn test_world_entity_mut() {
+    let mut world = World::new();
+    let entity = world.current_scene_mut().spawn_entity();
+
+    let mut handle = world.entity_mut(entity).unwrap();
+    assert_eq!(handle.id(), entity);
+    assert!(handle.get::<C1>().is_none());
+
+    handle.insert(C1(1)).insert(C2(2));
+    assert_eq!(handle.get::<C1>().unwrap().0, 1);
+    assert_eq!(handle.get::<C2>().unwrap().0, 2);
+
+    handle.get_mut::<C1>().unwrap().0 = 10;
+    assert_eq!(handle.get::<C1>().unwrap().0, 10);
+
+    handle.remove::<C2>();
+    assert!(handle.get::<C2>().is_none());
+    assert!(handle.get_mut::<C2>().is_none());
+
+    // changes are visible right away
+    assert!(world.current_scene().contains_component::<C1>(&entity));
+    assert!(!world.current_scene().contains_component::<C2>(&entity));
+
+    world.entity_mut(entity).unwrap().despawn();
+    assert!(world.entity_mut(entity).is_none());
+    assert!(!world.current_scene().is_alive(&entity));
+}
+
+#[cfg(feature = "runtime-checks")]
+#[test]
+#[should_panic(expected = "appears more than once")]
+fn test_optional_mut_aliases_shared() {
+    let world = World::new();
+
+    let _ = Query::<(Option<&mut C1>, &C1)>::new(world.current_scene());
+}
+
+#[test]
+fn test_insert_or_spawn_batch() {
+    let mut world = World::new();
+
+    let saved = [
+        (Entity::from_raw(3, 1), (C1(3), C2(3))),
+        (Entity::from_raw(7, 2), (C1(7), C2(7))),
+        (Entity::from_raw(0, 4), (C1(0), C2(0))),
+    ];
+    world.insert_or_spawn_batch(saved).unwrap();
+
+    let scene = world.current_scene();
+    let mut query = Query::<(&C1, &C2)>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 3);
+    for (entity, (c1, _)) in saved {
+        let (restored, _) = query.get_entity_components(&entity).unwrap();
+        assert_eq!(restored, &c1);
+    }
+    drop(query);
+
+    // live entities are updated, mismatching generations are skipped
+    let result = world.insert_or_spawn_batch([
+        (Entity::from_raw(3, 1), (C1(30), C2(30))),
+        (Entity::from_raw(7, 1), (C1(70), C2(70))),
+    ]);
+    assert_eq!(
+        result,
+        Err(vec![SpawnError::GenerationMismatch(Entity::from_raw(7, 1))])
+    );
+
+    let scene = world.current_scene();
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(
+        query.get_entity_components(&Entity::from_raw(3, 1)),
+        Some(&C1(30))
+    );
+    assert_eq!(
+        query.get_entity_components(&Entity::from_raw(7, 2)),
+        Some(&C1(7))
+    );
+}
+
+#[test]
+fn test_query_many_terms() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entity = scene.spawn_entity();
+    scene.add_component(&entity, (C1(1), C2(2), C3(3), C4(4), C5(5), C6(6)));
+
+    let mut query = Query::<(&C1, &C2, &C3, &C4, &C5, &mut C6)>::new(scene).unwrap();
+    let (c1, c2, c3, c4, c5, c6) = query.get_entity_components(&entity).unwrap();
+    assert_eq!(c1.0 + c2.0 + c3.0 + c4.0 + c5.0, 15);
+    c6.0 = 60;
+    drop(query);
+
+    // 8 terms don't require `large_tuples`
+    scene.add_component(&entity, (C7(7), C8(8)));
+    let mut query =
+        Query::<(&C1, &C2, &C3, &C4, &C5, &C6, &C7, Option<&mut C8>)>::new(scene).unwrap();
+    let (_, _, _, _, _, c6, c7, c8) = query.iter().next().unwrap();
+    assert_eq!((c6.0, c7.0, c8.map(|c8| c8.0)), (60, 7, Some(8)));
+}
+
+#[test]
+fn test_clone_scene() {
+    let mut world = World::new();
+    world.register_clone::<C1>();
+    world.register_clone::<C2>();
+
+    let scene = world.current_scene_mut();
+    let e1 = scene.spawn_entity();
+    scene.add_component(&e1, (C1(1), C2(1)));
+    let e2 = scene.spawn_entity();
+    scene.add_component(&e2, C1(2));
+    let deleted = scene.spawn_entity();
+    scene.delete_entity(deleted);
+
+    let mut snapshot = world.clone_scene().unwrap();
+    assert_eq!(snapshot.table_count(), 2);
+    assert!(snapshot.is_alive(&e1) && snapshot.is_alive(&e2));
+    assert!(!snapshot.is_alive(&deleted));
+
+    // mutating the clone leaves the original untouched
+    let mut query = Query::<&mut C1>::new(&snapshot).unwrap();
+    query.iter().for_each(|c1| c1.0 += 10);
+    drop(query);
+    snapshot.add_component(&e2, C2(2));
+
+    let mut query = Query::<&C1>::new(world.current_scene()).unwrap();
+    assert_eq!(query.get_entity_components(&e1), Some(&C1(1)));
+    assert_eq!(query.get_entity_components(&e2), Some(&C1(2)));
+    drop(query);
+    assert!(!world.current_scene().contains_component::<C2>(&e2));
+
+    // both spawners continue at the same point
+    let spawned = snapshot.spawn_entity();
+    assert_eq!(world.current_scene_mut().spawn_entity(), spawned);
+
+    // roll back
+    world.swap_scene(snapshot);
+    let mut query = Query::<(&C1, &C2)>::new(world.current_scene()).unwrap();
+    assert_eq!(query.iter().count(), 2);
+    drop(query);
+
+    // C3 can't be cloned
+    world.current_scene_mut().add_component(&e1, C3(3));
+    assert!(world.clone_scene().is_none());
+}
+
+#[test]
+fn test_commands_despawn_if() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    let entities = (0..10)
+        .map(|i| {
+            let entity = scene.spawn_entity();
+            scene.add_component(&entity, C1(i));
+            entity
+        })
+        .collect::<Vec<_>>();
+    let without_c1 = scene.spawn_entity();
+    scene.add_component(&without_c1, C2(100));
+
+    let commands = world.commands();
+    for entity in entities.iter().chain([&without_c1]) {
+        commands.despawn_if::<C1>(entity, |c1| c1.0 > 5);
+    }
+    // evaluated against the value at apply time
+    commands.mutate_component::<C1>(&entities[0], |c1| c1.0 = 50);
+    commands.despawn_if::<C1>(&entities[0], |c1| c1.0 > 5);
+    world.apply_commands();
+
+    let scene = world.current_scene();
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(query.iter().count(), 5);
+    assert!(query.iter().all(|c1| c1.0 <= 5));
+    assert!(!scene.is_alive(&entities[0]));
+    assert!(scene.is_alive(&without_c1));
+}
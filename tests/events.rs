@@ -0,0 +1,114 @@
+mod common;
+
+use eonix::{EventReader, EventWriter, Events, GlobalResMut, ScheduleBuilder, Update, World};
+
+use common::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Hit(u32);
+
+#[test]
+fn test_events_double_buffered() {
+    let mut events = Events::new();
+
+    events.send(Hit(1));
+    assert!(events.is_empty());
+
+    events.update();
+    events.send(Hit(2));
+    assert_eq!(events.read().copied().collect::<Vec<_>>(), [Hit(1)]);
+
+    events.update();
+    assert_eq!(events.read().copied().collect::<Vec<_>>(), [Hit(2)]);
+
+    events.update();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn test_send_event() {
+    let mut world = World::new();
+
+    world.send_event(Hit(1));
+    world.send_event(Hit(2));
+
+    let events = world.get_resource_ref::<Events<Hit>>().unwrap();
+    assert!(events.is_empty());
+    drop(events);
+
+    world.clear_trackers();
+    let events = world.get_resource_ref::<Events<Hit>>().unwrap();
+    assert_eq!(events.len(), 2);
+    drop(events);
+
+    world.clear_trackers();
+    let events = world.get_resource_ref::<Events<Hit>>().unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn test_inserted_events_rotate() {
+    let mut world = World::new();
+
+    // inserted directly and through commands, instead of `add_event`
+    world.insert_resource(Events::<Hit>::new());
+    world.commands().add_global_resource(Events::<u32>::new());
+    world.apply_commands();
+
+    world
+        .get_resource_mut::<Events<Hit>>()
+        .unwrap()
+        .send(Hit(1));
+    world.get_resource_mut::<Events<u32>>().unwrap().send(2);
+
+    world.clear_trackers();
+    assert_eq!(world.get_resource_ref::<Events<Hit>>().unwrap().len(), 1);
+    assert_eq!(world.get_resource_ref::<Events<u32>>().unwrap().len(), 1);
+
+    // re-inserting doesn't rotate twice per frame
+    world.insert_resource(Events::<Hit>::new());
+    world
+        .get_resource_mut::<Events<Hit>>()
+        .unwrap()
+        .send(Hit(3));
+
+    world.clear_trackers();
+    let events = world.get_resource_ref::<Events<Hit>>().unwrap();
+    assert_eq!(events.read().copied().collect::<Vec<_>>(), [Hit(3)]);
+    drop(events);
+    assert!(world.get_resource_ref::<Events<u32>>().unwrap().is_empty());
+}
+
+#[test]
+fn test_event_systems() {
+    let mut world = World::new();
+    world.add_event::<Hit>();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, (system_read, system_write))
+        .build()
+        .unwrap();
+
+    // frame 1: nothing to read, sends 1
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 0);
+
+    // frame 2: reads 1, sends 2
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+
+    // frame 3: reads 2 (and nothing from frame 1)
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 3);
+}
+
+fn system_write(mut writer: EventWriter<Hit>, res: GlobalResMut<R1>) {
+    writer.send(Hit(res.0 + 1));
+}
+
+fn system_read(reader: EventReader<Hit>, mut res: GlobalResMut<R1>) {
+    for hit in reader.iter() {
+        res.0 += hit.0;
+    }
+}
@@ -1,6 +1,14 @@
 mod common;
 
-use eonix::World;
+use std::{
+    any::{Any, TypeId},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use eonix::{
+    Commands, FromWorld, GlobalRes, GlobalResMut, ResInit, Resource, ResourceSet,
+    ResourceStorageModifier, SceneRes, SceneResMut, ScheduleBuilder, ScopedRes, Update, World,
+};
 
 use common::*;
 
@@ -67,3 +75,301 @@ fn test_global_nosend_insert_get() {
     assert_eq!(&mut res.0, &mut 100);
     drop(res);
 }
+
+#[test]
+fn test_init_nosend_resource_command() {
+    let mut world = World::new();
+
+    fn system(commands: Commands) {
+        commands.init_nosend_resource::<R2>();
+    }
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, system)
+        .build()
+        .unwrap();
+    schedule.run(&mut world);
+
+    let mut res = world.get_nosend_resource_mut::<R2>().unwrap();
+    assert_eq!(*res, R2(0));
+    res.0 = 5;
+    drop(res);
+
+    // already existing resources are not replaced
+    schedule.run(&mut world);
+
+    let res = world.get_nosend_resource_ref::<R2>().unwrap();
+    assert_eq!(*res, R2(5));
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+struct Seen {
+    scene: u32,
+    global: u32,
+}
+
+#[test]
+fn test_scene_and_global_params() {
+    fn system(scene: SceneRes<R1>, global: GlobalRes<R1>, commands: Commands) {
+        commands.add_resource(Seen {
+            scene: scene.0,
+            global: global.0,
+        });
+    }
+
+    let mut world = World::new();
+
+    world.insert_resource(R1(1));
+    world.current_scene_mut().insert_resource(R1(2));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, system)
+        .build()
+        .unwrap();
+    schedule.run(&mut world);
+
+    let seen = world.current_scene().get_resource_ref::<Seen>().unwrap();
+    assert_eq!(
+        *seen,
+        Seen {
+            scene: 2,
+            global: 1
+        }
+    );
+}
+
+#[cfg(feature = "runtime-checks")]
+#[test]
+#[should_panic(expected = "R1] is accessed mutably more than once")]
+fn test_scene_and_global_params_conflict() {
+    fn system(_: SceneResMut<R1>, _: GlobalResMut<R1>) {}
+
+    let _ = ScheduleBuilder::new().add_system(Update, system);
+}
+
+#[derive(Debug, Resource)]
+#[resource(from_world)]
+struct Doubled(u32);
+
+impl FromWorld for Doubled {
+    fn from_world(world: &mut World) -> Self {
+        let r1 = world.get_resource_ref::<R1>().unwrap();
+        Self(r1.0 * 2)
+    }
+}
+
+#[test]
+fn test_init_resource_from_world() {
+    let mut world = World::new();
+
+    world.insert_resource(R1(21));
+    world.init_resource_from_world::<Doubled>();
+
+    let res = world.get_resource_ref::<Doubled>().unwrap();
+    assert_eq!(res.0, 42);
+    drop(res);
+
+    // already existing resources are not replaced
+    world.insert_resource(R1(1));
+    world.init_resource_from_world::<Doubled>();
+
+    let res = world.get_resource_ref::<Doubled>().unwrap();
+    assert_eq!(res.0, 42);
+}
+
+#[test]
+fn test_resource_ref_unchecked() {
+    let mut world = World::new();
+    world.insert_resource(R1(5));
+
+    let res = unsafe { world.get_resource_ref_unchecked::<R1>() };
+    assert_eq!(res, Some(&R1(5)));
+
+    // shared borrows can coexist
+    let guard = world.get_resource_ref::<R1>().unwrap();
+    let res = unsafe { world.get_resource_ref_unchecked::<R1>() }.unwrap();
+    assert_eq!(guard.0, res.0);
+    drop(guard);
+
+    let world = World::new();
+    assert!(unsafe { world.get_resource_ref_unchecked::<R1>() }.is_none());
+}
+
+#[cfg(feature = "runtime-checks")]
+#[test]
+#[should_panic]
+fn test_resource_ref_unchecked_mutably_borrowed() {
+    let mut world = World::new();
+    world.insert_resource(R1(5));
+
+    let resources = world.global_resources();
+    let _guard = resources.get_resource_mut::<R1>().unwrap();
+    let _ = unsafe { resources.get_resource_ref_unchecked::<R1>() };
+}
+
+#[test]
+fn test_get_resources_mut() {
+    let mut world = World::new();
+    world.insert_resource(R1(1));
+    world.insert_resource(Doubled(2));
+
+    let (mut r1, mut doubled) = world.get_resources_mut::<(R1, Doubled)>().unwrap();
+    r1.0 += 10;
+    doubled.0 += 20;
+    drop((r1, doubled));
+
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 11);
+    assert_eq!(world.get_resource_ref::<Doubled>().unwrap().0, 22);
+
+    // the same resource twice
+    assert!(world.get_resources_mut::<(R1, R1)>().is_none());
+    assert!(world.get_resources_mut::<(R1, Doubled, R1)>().is_none());
+
+    // missing resource
+    let mut empty = World::new();
+    empty.insert_resource(R1(1));
+    assert!(empty.get_resources_mut::<(R1, Doubled)>().is_none());
+
+    // already borrowed
+    let resources = world.global_resources();
+    let guard = resources.get_resource_ref::<Doubled>().unwrap();
+    assert!(<(R1, Doubled) as ResourceSet>::get_mut(resources).is_none());
+    drop(guard);
+    assert!(<(R1, Doubled) as ResourceSet>::get_mut(resources).is_some());
+}
+
+#[cfg(feature = "debug-utils")]
+#[test]
+#[should_panic(expected = "Already shared borrowed! Resource: [resources::common::R1]")]
+fn test_resource_borrow_conflict_names_type() {
+    let mut world = World::new();
+    world.current_scene_mut().insert_resource(R1(0));
+
+    let scene = world.current_scene();
+    let _shared = scene.get_resource_ref::<R1>().unwrap();
+    let _exclusive = scene.get_resource_mut::<R1>();
+}
+
+static R4_CONSTRUCTED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Resource)]
+struct R4(u32);
+
+impl Default for R4 {
+    fn default() -> Self {
+        R4_CONSTRUCTED.fetch_add(1, Ordering::Relaxed);
+        Self(0)
+    }
+}
+
+fn init_r4(mut r4: ResInit<R4>) {
+    r4.0 += 1;
+}
+
+#[test]
+fn test_res_init() {
+    let mut world = World::new();
+
+    // both systems would initialize the resource
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, init_r4)
+        .add_system(Update, init_r4)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+
+    assert_eq!(R4_CONSTRUCTED.load(Ordering::Relaxed), 1);
+    assert_eq!(world.get_resource_ref::<R4>().unwrap().0, 4);
+}
+
+#[test]
+fn test_clone_resource() {
+    let mut world = World::new();
+    world.insert_resource(R1(7));
+
+    // cloning works while the resource is borrowed
+    let borrowed = world.get_resource_ref::<R1>().unwrap();
+    assert_eq!(world.clone_resource::<R1>(), Some(R1(7)));
+    drop(borrowed);
+
+    // the clone holds no borrow
+    let mut copy = world.clone_resource::<R1>().unwrap();
+    copy.0 += 1;
+    world.get_resource_mut::<R1>().unwrap().0 = 10;
+    assert_eq!(copy, R1(8));
+
+    assert_eq!(World::new().clone_resource::<R1>(), None);
+}
+
+struct Input {
+    pressed: Vec<u32>,
+}
+
+fn read_input(input: ScopedRes<Input>, mut r1: GlobalResMut<R1>) {
+    r1.0 += input.pressed.iter().sum::<u32>();
+}
+
+#[test]
+fn test_scoped_resource() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, read_input)
+        .build()
+        .unwrap();
+
+    // borrowed for the scope only
+    let input = Input {
+        pressed: vec![1, 2, 3],
+    };
+    world.with_scoped_resource(&input, |world| schedule.run(world));
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 6);
+
+    // nested scopes shadow the outer value
+    let other = Input { pressed: vec![10] };
+    world.with_scoped_resource(&input, |world| {
+        world.with_scoped_resource(&other, |world| schedule.run(world));
+        schedule.run(world);
+    });
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 22);
+
+    // outside of a scope the system is skipped
+    drop(input);
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 22);
+}
+
+#[test]
+fn test_scene_insert_resources() {
+    let mut world = World::new();
+
+    let scene = world.current_scene_mut();
+    scene.insert_resource(R1(1));
+
+    let resources: [(TypeId, Box<dyn Any>, ResourceStorageModifier); 2] = [
+        (
+            TypeId::of::<R1>(),
+            Box::new(R1(100)),
+            ResourceStorageModifier::new::<R1>(),
+        ),
+        (
+            TypeId::of::<R4>(),
+            Box::new(R4(200)),
+            ResourceStorageModifier::new::<R4>(),
+        ),
+    ];
+    scene.insert_resources(resources);
+
+    // replaced
+    assert_eq!(scene.get_resource_ref::<R1>().unwrap().0, 100);
+    assert_eq!(scene.get_resource_ref::<R4>().unwrap().0, 200);
+
+    let mut type_ids = scene.resource_type_ids().collect::<Vec<_>>();
+    type_ids.sort();
+    let mut expected = vec![TypeId::of::<R1>(), TypeId::of::<R4>()];
+    expected.sort();
+    assert_eq!(type_ids, expected);
+}
@@ -1,7 +1,17 @@
 mod common;
 
 use common::*;
-use eonix::{Query, ScheduleBuilder, Update, World};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use eonix::{
+    Commands, Component, GlobalRes, GlobalResMut, IntoSystemSet, Local, PostUpdate, PreUpdate,
+    Query, Res, ResInit, ResMut, Resource, ScheduleBuilder, Single, Spawner, StuckSystems,
+    SystemLabel, SystemParam, Update, With, World, global_resource_changed, global_resource_equals,
+    global_resource_exists, resource_exists,
+};
 
 #[test]
 fn test() {
@@ -9,7 +19,8 @@ fn test() {
     let schedule = ScheduleBuilder::new()
         .add_system(Update, system_add)
         .add_system(Update, system_world)
-        .build();
+        .build()
+        .unwrap();
 
     schedule.run(&mut world);
 }
@@ -23,3 +34,847 @@ fn system_add(mut query: Query<&mut C1>) {
 fn system_world(world: &mut World) {
     world.apply_commands();
 }
+
+#[test]
+fn test_many_params() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+    world.current_scene_mut().insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, system_six)
+        .add_system(Update, system_eight)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+}
+
+fn system_six(
+    _: Query<&C1>,
+    _: Query<&C2>,
+    _: Query<&C3>,
+    _: Res<R1>,
+    _: GlobalRes<R1>,
+    _: Commands,
+) {
+}
+
+#[allow(clippy::too_many_arguments)]
+fn system_eight(
+    _: Query<&C1>,
+    _: Query<&C2>,
+    _: Query<&C3>,
+    _: Query<&C1, With<C2>>,
+    _: Query<&C2, With<C3>>,
+    _: Res<R1>,
+    _: GlobalRes<R1>,
+    _: Commands,
+) {
+}
+
+#[derive(SystemParam)]
+struct Ctx<'w> {
+    query: Query<'w, &'w mut C1>,
+    res: Res<'w, R1>,
+    commands: Commands,
+}
+
+#[derive(SystemParam)]
+struct Unnamed<'w>(GlobalRes<'w, R1>, Query<'w, &'w C2, With<C1>>);
+
+#[test]
+fn test_derive_system_param() {
+    let mut world = World::new();
+    world.insert_resource(R1(10));
+    world.current_scene_mut().insert_resource(R1(3));
+
+    let commands = world.commands();
+    for i in 0..4 {
+        let entity = commands.reserve_entity();
+        commands.add_component(&entity, (C1(i), C2(i)));
+    }
+    world.apply_commands();
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, system_ctx)
+        .add_system(Update, system_unnamed)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+
+    let mut query = Query::<&C1>::new(world.current_scene()).unwrap();
+    let values = query.iter().map(|c1| c1.0).collect::<Vec<_>>();
+    assert_eq!(values, [3, 4, 5, 6]);
+}
+
+fn system_ctx(mut ctx: Ctx) {
+    let add = ctx.res.0;
+    for c1 in ctx.query.iter() {
+        c1.0 += add;
+    }
+
+    let _ = &ctx.commands;
+}
+
+fn system_unnamed(Unnamed(res, mut query): Unnamed) {
+    assert_eq!(res.0, 10);
+    assert_eq!(query.iter().count(), 4);
+}
+
+#[test]
+fn test_empty_stages_apply_commands() {
+    let mut world = World::new();
+
+    // commands issued outside of systems are still applied, even without systems
+    let schedule = ScheduleBuilder::new().build().unwrap();
+    let commands = world.commands();
+    let entity = commands.reserve_entity();
+    commands.add_component(&entity, C1(0));
+    schedule.run(&mut world);
+    assert_eq!(
+        Query::<&C1>::new(world.current_scene())
+            .unwrap()
+            .iter()
+            .count(),
+        1
+    );
+
+    // only the update stage has systems
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, system_spawn)
+        .build()
+        .unwrap();
+    for _ in 0..10 {
+        schedule.run(&mut world);
+    }
+    assert_eq!(
+        Query::<&C1>::new(world.current_scene())
+            .unwrap()
+            .iter()
+            .count(),
+        11
+    );
+}
+
+fn system_spawn(commands: Commands) {
+    let entity = commands.reserve_entity();
+    commands.add_component(&entity, C1(1));
+}
+
+#[test]
+fn test_run_conditions() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, system_count.run_if(resource_exists::<R3>()))
+        .add_system(Update, system_count.run_if(global_resource_exists::<R3>()))
+        .add_system(
+            Update,
+            (system_count, system_count).run_if(global_resource_equals(R3(2))),
+        )
+        .build()
+        .unwrap();
+
+    // missing resource
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 0);
+
+    // only the global one exists, but has the wrong value
+    world.insert_resource(R3(1));
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+
+    world.insert_resource(R3(2));
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 4);
+
+    world.current_scene_mut().insert_resource(R3(0));
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 8);
+}
+
+#[derive(Debug, PartialEq, Resource)]
+struct R3(u32);
+
+fn bump_r3_twice(mut r3: GlobalResMut<R3>) {
+    // only reading doesn't count as a change
+    assert!(!r3.is_changed());
+
+    if r3.0 < 2 {
+        r3.0 += 1;
+        assert!(r3.is_changed());
+    }
+}
+
+#[test]
+fn test_resource_changed() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+    world.insert_resource(R3(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(PreUpdate, bump_r3_twice)
+        .add_system(Update, system_count.run_if(global_resource_changed::<R3>()))
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 2);
+    assert!(world.get_resource_mut::<R3>().unwrap().is_changed());
+
+    // R3 stays the same
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 2);
+    assert!(!world.get_resource_mut::<R3>().unwrap().is_changed());
+
+    // replacing counts as a change
+    world.insert_resource(R3(2));
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 3);
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 3);
+}
+
+fn system_count(mut res: GlobalResMut<R1>) {
+    res.0 += 1;
+}
+
+fn count_global(mut r1: GlobalResMut<R1>) {
+    r1.0 += 1;
+}
+
+fn count_scene(mut r1: ResMut<R1>) {
+    r1.0 += 1;
+}
+
+#[test]
+fn test_remove_system() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+    world.current_scene_mut().insert_resource(R1(0));
+
+    let (builder, global) = ScheduleBuilder::new().add_system_with_handle(Update, count_global);
+    let (builder, scene) = builder.add_system_with_handle(Update, count_scene);
+    let mut schedule = builder.build().unwrap();
+
+    schedule.run(&mut world);
+
+    assert!(schedule.remove_system(global));
+    // already removed
+    assert!(!schedule.remove_system(global));
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+    assert_eq!(world.current_scene().get_resource_ref::<R1>().unwrap().0, 3);
+
+    // removing the last system leaves an empty stage
+    assert!(schedule.remove_system(scene));
+    schedule.run(&mut world);
+    assert_eq!(world.current_scene().get_resource_ref::<R1>().unwrap().0, 3);
+}
+
+fn fallible_count(mut r1: GlobalResMut<R1>) -> Result<(), String> {
+    r1.0 += 1;
+
+    if r1.0 > 1 {
+        return Err(format!("count too high: {}", r1.0));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_fallible_system() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+    world.current_scene_mut().insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, fallible_count)
+        .add_system(Update, count_scene)
+        .build()
+        .unwrap();
+
+    // the error of the second run is logged, not propagated
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 2);
+    assert_eq!(world.current_scene().get_resource_ref::<R1>().unwrap().0, 2);
+}
+
+fn always_fail(_r1: GlobalRes<R1>) -> Result<(), &'static str> {
+    Err("always fails")
+}
+
+#[test]
+fn test_failing_system_continues() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(PreUpdate, always_fail)
+        .add_system(Update, system_count)
+        .add_system(PostUpdate, system_count)
+        .build()
+        .unwrap();
+
+    // the error is logged every run, the later stages still run
+    for _ in 0..3 {
+        schedule.run(&mut world);
+    }
+
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 6);
+}
+
+fn add_r3(commands: Commands) {
+    commands.add_resource(R3(0));
+}
+
+#[test]
+fn test_apply_commands_after() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(PreUpdate, add_r3)
+        .add_system(Update, system_count.run_if(resource_exists::<R3>()))
+        .add_system(PostUpdate, system_count.run_if(resource_exists::<R3>()))
+        .apply_commands_after(&[&Update])
+        .build()
+        .unwrap();
+
+    // not applied after PreUpdate, but after Update
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 3);
+}
+
+fn count_local(mut counter: Local<u32>, mut r1: GlobalResMut<R1>) {
+    *counter += 1;
+    r1.0 = *counter;
+}
+
+#[test]
+fn test_local_state() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, count_local)
+        .build()
+        .unwrap();
+
+    for _ in 0..3 {
+        schedule.run(&mut world);
+    }
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 3);
+
+    // every system owns its state
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, count_local)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+}
+
+#[derive(SystemParam)]
+struct Counter<'w> {
+    calls: Local<'w, u32>,
+    total: Local<'w, u64>,
+    r1: GlobalResMut<'w, R1>,
+}
+
+fn count_derived(mut counter: Counter) {
+    *counter.calls += 1;
+    *counter.total += 10;
+    counter.r1.0 = *counter.calls + *counter.total as u32;
+}
+
+#[test]
+fn test_derive_local_state() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, count_derived)
+        .build()
+        .unwrap();
+
+    // every field keeps its own state across runs
+    for _ in 0..3 {
+        schedule.run(&mut world);
+    }
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 33);
+}
+
+#[derive(Resource, Default)]
+struct Frames(u32);
+
+#[derive(SystemParam)]
+struct FrameCtx<'w> {
+    frames: ResInit<'w, Frames>,
+    r1: GlobalRes<'w, R1>,
+}
+
+fn count_frames(mut ctx: FrameCtx) {
+    ctx.frames.0 += ctx.r1.0;
+}
+
+#[test]
+fn test_derive_init() {
+    let mut world = World::new();
+    world.insert_resource(R1(2));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, count_frames)
+        .build()
+        .unwrap();
+
+    // the field initializes the resource before the first run
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<Frames>().unwrap().0, 4);
+}
+
+fn count_redundant(mut query: Query<&C1, With<C1>>, mut r1: GlobalResMut<R1>) {
+    r1.0 += query.iter().map(|c1| c1.0).sum::<u32>();
+}
+
+#[test]
+fn test_redundant_filter_system() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let scene = world.current_scene_mut();
+    for i in 0..4 {
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, C1(i));
+    }
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, count_redundant)
+        .build()
+        .unwrap();
+
+    // the filter is pruned once, the query still matches every frame
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 12);
+}
+
+static STUCK_REPORTS: AtomicUsize = AtomicUsize::new(0);
+
+fn report_stuck(stuck: StuckSystems) {
+    assert_eq!(stuck.pending, 1);
+    assert_eq!(stuck.timeout, Duration::from_millis(20));
+    STUCK_REPORTS.fetch_add(1, Ordering::Relaxed);
+}
+
+fn idle() {}
+
+fn wait_for_report() {
+    // blocks until the schedule reported it, bounded so a missing report fails instead of hanging
+    let start = Instant::now();
+    while STUCK_REPORTS.load(Ordering::Relaxed) == 0 && start.elapsed() < Duration::from_secs(5) {
+        std::thread::yield_now();
+    }
+}
+
+#[test]
+fn test_on_stuck() {
+    let mut world = World::new();
+
+    // the first system runs on the main thread, the blocking one on the pool thread
+    let schedule = ScheduleBuilder::new()
+        .set_thread_count(1)
+        .set_join_timeout(Duration::from_millis(20))
+        .on_stuck(report_stuck)
+        .add_system(Update, idle)
+        .add_system(Update, wait_for_report)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+    assert_eq!(STUCK_REPORTS.load(Ordering::Relaxed), 1);
+
+    // the stage waited for the system, so the schedule keeps running
+    schedule.run(&mut world);
+    assert_eq!(STUCK_REPORTS.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_run_fixed() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+    world.current_scene_mut().insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, count_global)
+        .add_system(PostUpdate, count_scene)
+        .set_fixed_timestep(Duration::from_millis(10))
+        .build()
+        .unwrap();
+
+    let mut accumulator = Duration::ZERO;
+    let frames = [5, 5, 25, 0, 12];
+    let steps = frames
+        .map(|dt| schedule.run_fixed(&mut world, Duration::from_millis(dt), &mut accumulator));
+
+    assert_eq!(steps, [0, 1, 2, 0, 1]);
+    assert_eq!(accumulator, Duration::from_millis(7));
+
+    // fixed stage ran 4 times, the other stages once per frame
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 4);
+    assert_eq!(world.current_scene().get_resource_ref::<R1>().unwrap().0, 5);
+}
+
+fn panic_world(_world: &mut World) {
+    panic!("system failure");
+}
+
+fn take_scene_world(world: &mut World) {
+    world.take_scene();
+}
+
+#[test]
+#[cfg(feature = "runtime-checks")]
+fn test_swap_scene_while_running() {
+    let mut world = World::new();
+    let entity = world.current_scene_mut().spawn_entity();
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, take_scene_world)
+        .build()
+        .unwrap();
+
+    // the swap panics, the panic is caught and the scene stays in place
+    schedule.run(&mut world);
+    assert!(world.current_scene().is_alive(&entity));
+
+    // outside of the schedule the scene can be taken again
+    world.take_scene();
+    assert!(!world.current_scene().is_alive(&entity));
+}
+
+#[test]
+#[cfg(feature = "runtime-checks")]
+fn test_panic_releases_world() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(PreUpdate, panic_world)
+        .add_system(Update, system_count)
+        .add_system(PostUpdate, system_count)
+        .build()
+        .unwrap();
+
+    // the panic is caught, later stages can still borrow the world
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 4);
+}
+
+fn increment_deferred(mut query: Query<&C1>, commands: Commands) {
+    for (entity, _) in query.iter_with_entities() {
+        commands.mutate_component::<C1>(&entity, |c1| c1.0 += 1);
+    }
+}
+
+fn double_deferred(mut query: Query<&C1>, commands: Commands) {
+    for (entity, _) in query.iter_with_entities() {
+        commands.mutate_component::<C1>(&entity, |c1| c1.0 *= 2);
+    }
+}
+
+#[test]
+fn test_mutate_component_deferred() {
+    let mut world = World::new();
+    let scene = world.current_scene_mut();
+
+    let with_c1 = scene.spawn_entity();
+    scene.add_component(&with_c1, C1(1));
+    let without_c1 = scene.spawn_entity();
+    scene.add_component(&without_c1, C2(1));
+
+    // both systems only read C1, so they can run in parallel
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, increment_deferred)
+        .add_system(Update, double_deferred)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+
+    // applied once each, in any order
+    let scene = world.current_scene();
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    let c1 = query.get_entity_components(&with_c1).unwrap();
+    assert!(c1.0 == 3 || c1.0 == 4);
+
+    // skipped for entities without the component
+    assert!(!scene.contains_component::<C1>(&without_c1));
+    assert!(scene.contains_component::<C2>(&without_c1));
+}
+
+#[test]
+fn test_system_label() {
+    let labeled = system_count.label(SystemLabel("count"));
+    assert_eq!(labeled.get_info().label(), Some(SystemLabel("count")));
+
+    // the label is kept when wrapping the system
+    let conditional = system_count
+        .label(SystemLabel("count"))
+        .run_if(resource_exists::<R3>());
+    assert_eq!(conditional.get_info().label(), Some(SystemLabel("count")));
+
+    // bare systems have no label
+    assert_eq!(system_count.into_set().get_info().label(), None);
+
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, labeled)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+}
+
+fn reserve_many(spawner: Spawner, commands: Commands) {
+    for _ in 0..100 {
+        commands.spawn_reserved(spawner.reserve());
+    }
+}
+
+fn reserve_many_too(spawner: Spawner, commands: Commands) {
+    reserve_many(spawner, commands);
+}
+
+#[test]
+fn test_spawner_parallel_reservation() {
+    let mut world = World::new();
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, reserve_many)
+        .add_system(Update, reserve_many_too)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+
+    // every reservation got its own id, so none was dropped as stale while activating
+    assert_eq!(world.current_scene().iter_entities().count(), 200);
+}
+
+#[test]
+#[cfg(feature = "debug-utils")]
+fn test_named_system() {
+    let named = system_count.named("count");
+    assert_eq!(named.name(), "count");
+
+    // closures fall back to their (mangled) type name
+    let unnamed = (|mut res: GlobalResMut<R1>| res.0 += 1).into_set();
+    assert!(unnamed.name().contains("closure"));
+
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, named)
+        .add_system(Update, unnamed.named("closure_count"))
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 2);
+}
+
+fn destroy_marked(mut query: Query<&C1, With<C2>>, commands: Commands) {
+    for entity in query.collect_entities() {
+        commands.delete_entity(entity);
+    }
+}
+
+#[test]
+fn test_query_collect_entities() {
+    let mut world = World::new();
+    let scene = world.current_scene_mut();
+    for i in 0..4 {
+        let entity = scene.spawn_entity();
+        scene.add_component(&entity, C1(i));
+        if i % 2 == 0 {
+            scene.add_component(&entity, C2(i));
+        }
+    }
+    let untouched = scene.spawn_entity();
+    scene.add_component(&untouched, C2(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, destroy_marked)
+        .build()
+        .unwrap();
+    schedule.run(&mut world);
+
+    let scene = world.current_scene();
+    let mut query = Query::<&C1>::new(scene).unwrap();
+    assert_eq!(query.collect_entities().len(), 2);
+    assert!(query.iter().all(|c1| c1.0 % 2 == 1));
+    assert!(scene.is_alive(&untouched));
+}
+
+fn count_optional(mut r1: GlobalResMut<R1>, r3: Option<Res<R3>>) {
+    r1.0 += r3.map_or(1, |r3| r3.0);
+}
+
+fn bump_optional(r3: Option<ResMut<R3>>) {
+    if let Some(mut r3) = r3 {
+        r3.0 += 1;
+    }
+}
+
+fn require_r3(mut r1: GlobalResMut<R1>, _: Res<R3>) {
+    r1.0 += 1000;
+}
+
+#[test]
+fn test_optional_resource_param() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, count_optional)
+        .add_system(Update, bump_optional)
+        .add_system(Update, require_r3)
+        .build()
+        .unwrap();
+
+    // the required one is skipped, the optional ones still run
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+
+    world.current_scene_mut().insert_resource(R3(10));
+    schedule.run(&mut world);
+
+    let r1 = world.get_resource_ref::<R1>().unwrap().0;
+    assert!(r1 == 1011 || r1 == 1012);
+    assert_eq!(
+        world.current_scene().get_resource_ref::<R3>().unwrap().0,
+        11
+    );
+}
+
+#[derive(Debug, Component)]
+struct Camera {
+    zoom: u32,
+}
+
+fn zoom_camera(mut camera: Single<&mut Camera>, mut r1: GlobalResMut<R1>) {
+    camera.get().zoom += 1;
+    r1.0 += 1;
+}
+
+#[test]
+fn test_single_param() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, zoom_camera)
+        .build()
+        .unwrap();
+
+    // no camera, skipped
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 0);
+
+    let scene = world.current_scene_mut();
+    let camera = scene.spawn_entity();
+    scene.add_component(&camera, Camera { zoom: 1 });
+
+    schedule.run(&mut world);
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 2);
+
+    let mut single = Single::new(Query::<&Camera>::new(world.current_scene()).unwrap()).unwrap();
+    assert_eq!(single.get().zoom, 3);
+    assert_eq!(single.entity(), camera);
+    drop(single);
+
+    // two cameras, skipped
+    let scene = world.current_scene_mut();
+    let other = scene.spawn_entity();
+    scene.add_component(&other, Camera { zoom: 1 });
+
+    schedule.run(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 2);
+    assert!(Single::new(Query::<&Camera>::new(world.current_scene()).unwrap()).is_none());
+}
+
+#[test]
+fn test_run_stage() {
+    let mut world = World::new();
+    world.insert_resource(R1(0));
+    world.current_scene_mut().insert_resource(R1(0));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(PreUpdate, count_global)
+        .add_system(Update, (count_scene, add_r3))
+        .build()
+        .unwrap();
+
+    schedule.run_stage::<Update>(&mut world);
+
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 0);
+    assert_eq!(world.current_scene().get_resource_ref::<R1>().unwrap().0, 1);
+    // commands are applied after the stage
+    assert_eq!(world.current_scene().get_resource_ref::<R3>().unwrap().0, 0);
+
+    schedule.run_stage::<PreUpdate>(&mut world);
+    assert_eq!(world.get_resource_ref::<R1>().unwrap().0, 1);
+}
+
+fn read_r1_write_r3(r1: Res<R1>, mut r3: ResMut<R3>) {
+    r3.0 += r1.0;
+}
+
+fn read_write_r1(_: Res<R1>, _: ResMut<R1>) {}
+
+#[test]
+fn test_read_and_write_different_resources() {
+    let mut world = World::new();
+    let scene = world.current_scene_mut();
+    scene.insert_resource(R1(5));
+    scene.insert_resource(R3(1));
+
+    let schedule = ScheduleBuilder::new()
+        .add_system(Update, read_r1_write_r3)
+        .build()
+        .unwrap();
+
+    schedule.run(&mut world);
+    assert_eq!(world.current_scene().get_resource_ref::<R3>().unwrap().0, 6);
+}
+
+#[cfg(feature = "runtime-checks")]
+#[test]
+#[should_panic(
+    expected = "Invalid parameter combination: [systems::common::R1] is accessed both shared and mutably"
+)]
+fn test_read_and_write_same_resource() {
+    _ = ScheduleBuilder::new().add_system(Update, read_write_r1);
+}